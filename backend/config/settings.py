@@ -5,6 +5,9 @@ from typing import Optional
 class Settings(BaseSettings):
     """Application settings loaded from environment variables"""
     
+    # Clerk (used to revoke sessions on logout)
+    CLERK_SECRET_KEY: Optional[str] = None
+    
     # Supabase Database
     SUPABASE_URL: str
     SUPABASE_KEY: str
@@ -0,0 +1,31 @@
+-- ============================================
+-- DRIFT: Desktop Crash Reports Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Create crash_reports table
+CREATE TABLE IF NOT EXISTS crash_reports (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    report_id TEXT NOT NULL,
+    user_id TEXT,
+    kind TEXT NOT NULL,
+    app_version TEXT,
+    platform TEXT,
+    message TEXT,
+    stack TEXT,
+    minidump TEXT,
+    occurred_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- 2. Add indexes
+CREATE INDEX IF NOT EXISTS idx_crash_reports_user ON crash_reports(user_id);
+CREATE INDEX IF NOT EXISTS idx_crash_reports_version ON crash_reports(app_version);
+
+-- 3. Enable RLS
+ALTER TABLE crash_reports ENABLE ROW LEVEL SECURITY;
+
+-- 4. RLS Policies (allow all for now, backend handles auth)
+DROP POLICY IF EXISTS "Allow all crash_reports" ON crash_reports;
+CREATE POLICY "Allow all crash_reports" ON crash_reports
+    FOR ALL USING (true) WITH CHECK (true);
@@ -0,0 +1,29 @@
+-- ============================================
+-- DRIFT: Desktop Devices Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Create devices table
+CREATE TABLE IF NOT EXISTS devices (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    device_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    name TEXT,
+    platform TEXT,
+    app_version TEXT,
+    last_seen_at TIMESTAMPTZ DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, device_id)
+);
+
+-- 2. Add indexes
+CREATE INDEX IF NOT EXISTS idx_devices_user ON devices(user_id);
+
+-- 3. Enable RLS
+ALTER TABLE devices ENABLE ROW LEVEL SECURITY;
+
+-- 4. RLS Policies (allow all for now, backend handles auth)
+DROP POLICY IF EXISTS "Allow all devices" ON devices;
+CREATE POLICY "Allow all devices" ON devices
+    FOR ALL USING (true) WITH CHECK (true);
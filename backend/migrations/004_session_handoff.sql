@@ -0,0 +1,11 @@
+-- ============================================
+-- DRIFT: Session Handoff Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Link a session to the one it continues on another device
+ALTER TABLE work_sessions
+    ADD COLUMN IF NOT EXISTS continues_session_id UUID REFERENCES work_sessions(id) ON DELETE SET NULL;
+
+-- 2. Add index for walking a handoff chain
+CREATE INDEX IF NOT EXISTS idx_work_sessions_continues ON work_sessions(continues_session_id);
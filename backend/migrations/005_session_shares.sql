@@ -0,0 +1,26 @@
+-- ============================================
+-- DRIFT: Session Share Links Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Create session_shares table
+CREATE TABLE IF NOT EXISTS session_shares (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+    session_id UUID REFERENCES work_sessions(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- 2. Add indexes
+CREATE INDEX IF NOT EXISTS idx_session_shares_session ON session_shares(session_id);
+
+-- 3. Enable RLS
+ALTER TABLE session_shares ENABLE ROW LEVEL SECURITY;
+
+-- 4. RLS Policies (allow all for now, backend handles auth)
+DROP POLICY IF EXISTS "Allow all session_shares" ON session_shares;
+CREATE POLICY "Allow all session_shares" ON session_shares
+    FOR ALL USING (true) WITH CHECK (true);
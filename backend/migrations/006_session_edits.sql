@@ -0,0 +1,10 @@
+-- ============================================
+-- DRIFT: Session Edits Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Details users can add or correct after a session ended
+ALTER TABLE work_sessions
+    ADD COLUMN IF NOT EXISTS title TEXT,
+    ADD COLUMN IF NOT EXISTS description TEXT,
+    ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ;
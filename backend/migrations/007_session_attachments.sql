@@ -0,0 +1,29 @@
+-- ============================================
+-- DRIFT: Session Attachments Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Create session_attachments table
+CREATE TABLE IF NOT EXISTS session_attachments (
+    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,
+    attachment_id TEXT NOT NULL UNIQUE,
+    session_id UUID REFERENCES work_sessions(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    mime_type TEXT,
+    size_bytes BIGINT,
+    sha256 TEXT NOT NULL,
+    content TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- 2. Add indexes
+CREATE INDEX IF NOT EXISTS idx_session_attachments_session ON session_attachments(session_id);
+
+-- 3. Enable RLS
+ALTER TABLE session_attachments ENABLE ROW LEVEL SECURITY;
+
+-- 4. RLS Policies (allow all for now, backend handles auth)
+DROP POLICY IF EXISTS "Allow all session_attachments" ON session_attachments;
+CREATE POLICY "Allow all session_attachments" ON session_attachments
+    FOR ALL USING (true) WITH CHECK (true);
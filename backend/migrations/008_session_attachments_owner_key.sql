@@ -0,0 +1,13 @@
+-- ============================================
+-- DRIFT: Session Attachments Owner Key Migration
+-- Run this in Supabase SQL Editor
+-- ============================================
+
+-- 1. Attachment ids are chosen by the client; only unique per user
+ALTER TABLE session_attachments
+    DROP CONSTRAINT IF EXISTS session_attachments_attachment_id_key;
+
+ALTER TABLE session_attachments
+    DROP CONSTRAINT IF EXISTS session_attachments_user_attachment_key;
+ALTER TABLE session_attachments
+    ADD CONSTRAINT session_attachments_user_attachment_key UNIQUE (user_id, attachment_id);
@@ -1,5 +1,5 @@
 from fastapi import APIRouter, Header, HTTPException, status
-from services.clerk_auth import get_current_user
+from services.clerk_auth import get_current_user, revoke_clerk_session
 from services.supabase_client import get_supabase
 
 router = APIRouter()
@@ -49,3 +49,26 @@ async def validate_session(
         "role": role,
         "avatarUrl": user["avatarUrl"]
     }
+
+
+@router.post("/auth/logout")
+async def logout(
+    authorization: str = Header(...),
+):
+    """
+    Revoke the Clerk session behind this token.
+    Used by the Desktop App so a logged-out token can't be reused.
+    """
+    user = await get_current_user(authorization)
+    
+    if not user.get("sessionId"):
+        raise HTTPException(
+            status_code=status.HTTP_400_BAD_REQUEST,
+            detail={
+                "code": "NO_SESSION",
+                "message": "Token is not bound to a session"
+            }
+        )
+    
+    await revoke_clerk_session(user["sessionId"])
+    return {"revoked": True}
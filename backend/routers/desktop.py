@@ -1,11 +1,16 @@
 """
 Desktop App Integration Router - Handles desktop overlay communication.
 """
-from fastapi import APIRouter, HTTPException, Header, WebSocket, WebSocketDisconnect
-from pydantic import BaseModel
+from fastapi import APIRouter, HTTPException, Header, Request, WebSocket, WebSocketDisconnect
+from pydantic import BaseModel, Field
 from typing import List, Optional, Dict, Any
-from datetime import datetime
+from datetime import datetime, timedelta
+import base64
+import binascii
+import hashlib
 import json
+import secrets
+import time
 
 from services.clerk_auth import verify_clerk_token
 from services.supabase_client import get_supabase
@@ -29,6 +34,7 @@ class SessionStartRequest(BaseModel):
     """Request to start a work session."""
     briefId: str
     role: str
+    continuesSessionId: Optional[str] = None  # session handed off from another device
 
 
 class SessionEndRequest(BaseModel):
@@ -36,6 +42,13 @@ class SessionEndRequest(BaseModel):
     sessionId: str
     activities: List[ActivityEntry]
     summary: Optional[str] = None
+    idleSeconds: int = 0  # time the desktop app spent paused for inactivity
+
+
+class SessionReassignRequest(BaseModel):
+    """Request to move an active session to another brief."""
+    sessionId: str
+    briefId: str
 
 
 class DesktopSyncRequest(BaseModel):
@@ -72,6 +85,93 @@ class ScreenAnalysisRequest(BaseModel):
     previousInsights: Optional[List[str]] = None  # To avoid repetition
 
 
+class CrashReportRequest(BaseModel):
+    """Crash report submitted by the user from the desktop app."""
+    reportId: str
+    kind: str  # exception, rejection, renderer, native
+    appVersion: Optional[str] = None
+    platform: Optional[str] = None
+    message: Optional[str] = None
+    stack: Optional[str] = None
+    minidump: Optional[str] = None  # base64, native crashes only
+    occurredAt: Optional[int] = None  # unix ms
+
+
+# Largest minidump accepted, base64-encoded; real minidumps are well below this
+MAX_MINIDUMP_CHARS = 4 * 1024 * 1024
+
+# Crash reports accepted per signed-in user, or per IP address when signed out, in the window
+CRASH_REPORT_LIMIT = 10
+CRASH_REPORT_WINDOW_SECONDS = 60 * 60
+
+# Submission times per rate-limit key; in-process, so the limit applies per worker
+_crash_report_times: Dict[str, List[float]] = {}
+
+
+def _crash_report_allowed(key: str) -> bool:
+    """Record a crash report for `key` unless it is over the limit."""
+    now = time.monotonic()
+    # Forget keys whose reports have all left the window, so the table doesn't grow forever
+    for stale in [k for k, times in _crash_report_times.items() if not times or now - times[-1] >= CRASH_REPORT_WINDOW_SECONDS]:
+        del _crash_report_times[stale]
+    
+    recent = [t for t in _crash_report_times.get(key, []) if now - t < CRASH_REPORT_WINDOW_SECONDS]
+    if len(recent) >= CRASH_REPORT_LIMIT:
+        _crash_report_times[key] = recent
+        return False
+    recent.append(now)
+    _crash_report_times[key] = recent
+    return True
+
+
+class AttachmentUploadRequest(BaseModel):
+    """A file attached to a session in the desktop app."""
+    attachmentId: str
+    name: str
+    mimeType: Optional[str] = None
+    size: int
+    sha256: str  # hex, of the decoded content
+    data: str  # base64
+
+
+# Largest attachment accepted, base64-encoded
+MAX_ATTACHMENT_CHARS = 30 * 1024 * 1024
+
+
+class ShareLinkRequest(BaseModel):
+    """Request to share a session's summary via a link."""
+    expiresInHours: Optional[int] = Field(None, gt=0, le=24 * 365)  # None: until revoked; at most a year
+
+
+class SessionUpdateRequest(BaseModel):
+    """Corrections to a recorded session; fields left out stay unchanged."""
+    title: Optional[str] = None
+    description: Optional[str] = None
+    briefId: Optional[str] = None
+    startedAt: Optional[int] = None  # epoch ms
+
+
+class DeviceRegisterRequest(BaseModel):
+    """Desktop installation announcing itself after login."""
+    deviceId: str
+    name: Optional[str] = None
+    platform: Optional[str] = None
+    appVersion: Optional[str] = None
+
+
+class DesktopCommandRequest(BaseModel):
+    """Command for the user's desktop app, e.g. issued from the web app."""
+    command: str  # e.g. "stop_session"
+    sessionId: Optional[str] = None
+
+
+# Commands the desktop app knows how to execute
+DESKTOP_COMMANDS = {"stop_session"}
+
+# Live session events the desktop app may push
+SESSION_EVENTS = {"started", "ended", "paused", "resumed", "marker"}
+
+
 class ProcessNoteRequest(BaseModel):
     """Request to process a raw note into a clean bullet point."""
     note: str
@@ -143,10 +243,35 @@ Output ONLY the bullet point, nothing else."""
         return {"bullet": request.note, "processed": False, "error": str(e)}
 
 
+@router.get("/desktop/health")
+async def desktop_health():
+    """
+    Cheap unauthenticated probe for the desktop app's connection indicator.
+    """
+    return {"status": "ok", "time": datetime.utcnow().isoformat()}
+
+
+def _ensure_device_active(supabase, user_id: str, device_id: Optional[str]) -> None:
+    """
+    Reject requests from a device the user revoked in the web app.
+    Requests without a device ID (older desktop versions) are let through.
+    """
+    if not device_id:
+        return
+    result = supabase.table("devices")\
+        .select("revoked_at")\
+        .eq("user_id", user_id)\
+        .eq("device_id", device_id)\
+        .execute()
+    if result.data and result.data[0].get("revoked_at"):
+        raise HTTPException(status_code=401, detail="Device revoked")
+
+
 @router.post("/desktop/sync")
 async def sync_desktop_state(
     request: DesktopSyncRequest,
-    authorization: str = Header(...)
+    authorization: str = Header(...),
+    x_drift_device: Optional[str] = Header(None)
 ):
     """
     Sync desktop app state with server.
@@ -160,6 +285,7 @@ async def sync_desktop_state(
     print(f"[desktop/sync] user_id: {user_id}, org_id: {org_id}")
     
     supabase = get_supabase()
+    _ensure_device_active(supabase, user_id, x_drift_device)
     
     # Try multiple strategies to find briefs
     briefs = []
@@ -236,26 +362,11 @@ async def sync_desktop_state(
     }
 
 
-@router.post("/desktop/session/start")
-async def start_session(
-    request: SessionStartRequest,
-    authorization: str = Header(...)
-):
-    """
-    Start a work session for a brief.
-    Returns session ID for tracking.
-    """
-    token = authorization.replace("Bearer ", "")
-    user_info = await verify_clerk_token(token)
-    user_id = user_info["userId"]
-    org_id = user_info.get("orgId")
-    
-    supabase = get_supabase()
-    
-    # Verify brief exists and user has access
+def _get_accessible_brief(supabase, brief_id: str, user_id: str, org_id: Optional[str]) -> dict:
+    """Load a brief, raising 404/403 unless the user may record against it."""
     brief_result = supabase.table("briefs")\
         .select("id, name, org_id, created_by")\
-        .eq("id", request.briefId)\
+        .eq("id", brief_id)\
         .single()\
         .execute()
     
@@ -278,6 +389,30 @@ async def start_session(
         print(f"[Session] Access denied: user={user_id}, org={org_id}, brief_org={brief.get('org_id')}, brief_creator={brief.get('created_by')}")
         raise HTTPException(status_code=403, detail="Access denied")
     
+    return brief
+
+
+@router.post("/desktop/session/start")
+async def start_session(
+    request: SessionStartRequest,
+    authorization: str = Header(...),
+    x_drift_device: Optional[str] = Header(None)
+):
+    """
+    Start a work session for a brief.
+    Returns session ID for tracking.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    org_id = user_info.get("orgId")
+    
+    supabase = get_supabase()
+    _ensure_device_active(supabase, user_id, x_drift_device)
+    
+    # Verify brief exists and user has access
+    brief = _get_accessible_brief(supabase, request.briefId, user_id, org_id)
+    
     # Create session in DB
     session_data = {
         "user_id": user_id,
@@ -286,6 +421,16 @@ async def start_session(
         "role": request.role,
         "status": "active"
     }
+    if request.continuesSessionId:
+        # Only the caller's own sessions can be continued
+        continued = supabase.table("work_sessions")\
+            .select("id")\
+            .eq("id", request.continuesSessionId)\
+            .eq("user_id", user_id)\
+            .execute()
+        if not continued.data:
+            raise HTTPException(status_code=404, detail="Continued session not found")
+        session_data["continues_session_id"] = request.continuesSessionId
     
     result = supabase.table("work_sessions")\
         .insert(session_data)\
@@ -306,7 +451,7 @@ async def start_session(
                 "sessionId": session_id,
                 "userId": user_id,
                 "briefId": request.briefId,
-                "briefName": brief_result.data["name"]
+                "briefName": brief["name"]
             },
             org_id
         )
@@ -316,11 +461,114 @@ async def start_session(
     return {
         "sessionId": session_id,
         "briefId": request.briefId,
-        "briefName": brief_result.data["name"],
+        "briefName": brief["name"],
         "startedAt": started_at
     }
 
 
+@router.post("/desktop/session/reassign")
+async def reassign_session(
+    request: SessionReassignRequest,
+    authorization: str = Header(...)
+):
+    """
+    Move an active session to a different brief, e.g. when the wrong one was picked at start.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    org_id = user_info.get("orgId")
+    
+    supabase = get_supabase()
+    
+    session_result = supabase.table("work_sessions")\
+        .select("id, user_id")\
+        .eq("id", request.sessionId)\
+        .eq("status", "active")\
+        .single()\
+        .execute()
+    
+    if not session_result.data:
+        raise HTTPException(status_code=404, detail="Session not found or already ended")
+    
+    if session_result.data["user_id"] != user_id:
+        raise HTTPException(status_code=403, detail="Access denied")
+    
+    brief = _get_accessible_brief(supabase, request.briefId, user_id, org_id)
+    
+    supabase.table("work_sessions")\
+        .update({"brief_id": request.briefId})\
+        .eq("id", request.sessionId)\
+        .execute()
+    
+    return {
+        "sessionId": request.sessionId,
+        "briefId": request.briefId,
+        "briefName": brief["name"]
+    }
+
+
+@router.post("/desktop/session/{session_id}/update")
+async def update_session(
+    session_id: str,
+    request: SessionUpdateRequest,
+    authorization: str = Header(...)
+):
+    """
+    Correct a session after the fact: title, description, brief or start time.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    org_id = user_info.get("orgId")
+    
+    supabase = get_supabase()
+    session_result = supabase.table("work_sessions")\
+        .select("id, ended_at")\
+        .eq("id", session_id)\
+        .eq("user_id", user_id)\
+        .execute()
+    if not session_result.data:
+        raise HTTPException(status_code=404, detail="Session not found")
+    session = session_result.data[0]
+    
+    changes: Dict[str, Any] = {}
+    if request.title is not None:
+        changes["title"] = request.title.strip() or None
+    if request.description is not None:
+        changes["description"] = request.description.strip() or None
+    
+    brief_name = None
+    if request.briefId is not None:
+        brief = _get_accessible_brief(supabase, request.briefId, user_id, org_id)
+        changes["brief_id"] = request.briefId
+        brief_name = brief["name"]
+    
+    if request.startedAt is not None:
+        started_at = datetime.utcfromtimestamp(request.startedAt / 1000)
+        if session.get("ended_at"):
+            ended_at = datetime.fromisoformat(session["ended_at"].replace("Z", "+00:00")).replace(tzinfo=None)
+            if started_at >= ended_at:
+                raise HTTPException(status_code=400, detail="Start must be before the end of the session")
+            changes["duration_minutes"] = int((ended_at - started_at).total_seconds() // 60)
+        changes["started_at"] = started_at.isoformat()
+    
+    if not changes:
+        raise HTTPException(status_code=400, detail="Nothing to update")
+    
+    changes["edited_at"] = datetime.utcnow().isoformat()
+    supabase.table("work_sessions")\
+        .update(changes)\
+        .eq("id", session_id)\
+        .execute()
+    
+    return {
+        "sessionId": session_id,
+        "updated": sorted(k for k in changes if k != "edited_at"),
+        "briefName": brief_name
+    }
+
+
 @router.post("/desktop/session/end")
 async def end_session(
     request: SessionEndRequest,
@@ -362,7 +610,8 @@ async def end_session(
         started_at = datetime.fromisoformat(started_at_str)
     
     ended_at = datetime.utcnow()
-    duration_minutes = max(1, int((ended_at - started_at.replace(tzinfo=None)).total_seconds() / 60))
+    elapsed_seconds = (ended_at - started_at.replace(tzinfo=None)).total_seconds()
+    duration_minutes = max(1, int((elapsed_seconds - max(0, request.idleSeconds)) / 60))
     
     # Update session in DB
     supabase.table("work_sessions")\
@@ -832,6 +1081,307 @@ Return ONLY valid JSON, no other text."""
         raise HTTPException(status_code=500, detail=str(e))
 
 
+@router.post("/desktop/crash-report")
+async def submit_crash_report(
+    request: CrashReportRequest,
+    http_request: Request,
+    authorization: Optional[str] = Header(None)
+):
+    """
+    Store a crash report. Signing in is optional so crashes before login can be reported too,
+    but a token that is sent must be valid, and only signed-in users can send minidumps.
+    Reports are rate limited per user, or per IP address when signed out.
+    """
+    user_id = None
+    if authorization:
+        # Raises 401 for an invalid or expired token
+        user_info = await verify_clerk_token(authorization.replace("Bearer ", ""))
+        user_id = user_info["userId"]
+    
+    # The real client, not nginx: uvicorn runs with --proxy-headers and trusts only the proxy's X-Forwarded-For
+    client_ip = http_request.client.host if http_request.client else "unknown"
+    if not _crash_report_allowed(f"user:{user_id}" if user_id else f"ip:{client_ip}"):
+        raise HTTPException(status_code=429, detail="Too many crash reports", headers={"Retry-After": str(CRASH_REPORT_WINDOW_SECONDS)})
+    
+    if request.minidump and not user_id:
+        raise HTTPException(status_code=401, detail="Sign in to send minidumps")
+    if request.minidump and len(request.minidump) > MAX_MINIDUMP_CHARS:
+        raise HTTPException(status_code=413, detail="Minidump too large")
+    
+    supabase = get_supabase()
+    supabase.table("crash_reports").insert({
+        "report_id": request.reportId,
+        "user_id": user_id,
+        "kind": request.kind,
+        "app_version": request.appVersion,
+        "platform": request.platform,
+        "message": (request.message or "")[:2000],
+        "stack": (request.stack or "")[:20000],
+        "minidump": request.minidump,
+        "occurred_at": datetime.utcfromtimestamp(request.occurredAt / 1000).isoformat() if request.occurredAt else None
+    }).execute()
+    
+    return {"received": True}
+
+
+@router.post("/desktop/devices/register")
+async def register_device(
+    request: DeviceRegisterRequest,
+    authorization: str = Header(...)
+):
+    """
+    Record (or refresh) a desktop installation for the signed-in user.
+    A revoked device stays revoked until the user removes it in the web app.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    
+    supabase = get_supabase()
+    result = supabase.table("devices").upsert({
+        "device_id": request.deviceId,
+        "user_id": user_id,
+        "name": request.name,
+        "platform": request.platform,
+        "app_version": request.appVersion,
+        "last_seen_at": datetime.utcnow().isoformat()
+    }, on_conflict="user_id,device_id").execute()
+    
+    device = result.data[0] if result.data else {}
+    return {"registered": True, "revoked": bool(device.get("revoked_at"))}
+
+
+@router.get("/desktop/devices")
+async def list_devices(authorization: str = Header(...)):
+    """
+    The user's desktop installations, most recently seen first.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    
+    supabase = get_supabase()
+    result = supabase.table("devices")\
+        .select("device_id, name, platform, app_version, last_seen_at, revoked_at, created_at")\
+        .eq("user_id", user_info["userId"])\
+        .order("last_seen_at", desc=True)\
+        .execute()
+    
+    return {"devices": result.data or []}
+
+
+@router.post("/desktop/devices/{device_id}/revoke")
+async def revoke_device(device_id: str, authorization: str = Header(...)):
+    """
+    Revoke a desktop installation; its sync and session calls are rejected from now on.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    
+    supabase = get_supabase()
+    result = supabase.table("devices")\
+        .update({"revoked_at": datetime.utcnow().isoformat()})\
+        .eq("user_id", user_info["userId"])\
+        .eq("device_id", device_id)\
+        .execute()
+    
+    if not result.data:
+        raise HTTPException(status_code=404, detail="Device not found")
+    return {"revoked": True}
+
+
+@router.post("/desktop/session/{session_id}/share")
+async def create_share_link(
+    session_id: str,
+    request: ShareLinkRequest,
+    authorization: str = Header(...)
+):
+    """
+    Create a link that shows the session's summary without signing in.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    
+    supabase = get_supabase()
+    session_result = supabase.table("work_sessions")\
+        .select("id")\
+        .eq("id", session_id)\
+        .eq("user_id", user_id)\
+        .execute()
+    if not session_result.data:
+        raise HTTPException(status_code=404, detail="Session not found")
+    
+    expires_at = None
+    if request.expiresInHours:
+        expires_at = (datetime.utcnow() + timedelta(hours=request.expiresInHours)).isoformat()
+    
+    share_token = secrets.token_urlsafe(24)
+    supabase.table("session_shares").insert({
+        "token": share_token,
+        "session_id": session_id,
+        "user_id": user_id,
+        "expires_at": expires_at
+    }).execute()
+    
+    return {"token": share_token, "expiresAt": expires_at}
+
+
+@router.post("/desktop/session/{session_id}/attachments")
+async def upload_attachment(
+    session_id: str,
+    request: AttachmentUploadRequest,
+    authorization: str = Header(...)
+):
+    """
+    Store a file attached to a session. Uploading the same attachment again replaces it.
+    Attachment ids are unique per user, so one user's upload can never replace another's.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    user_id = user_info["userId"]
+    
+    if len(request.data) > MAX_ATTACHMENT_CHARS:
+        raise HTTPException(status_code=413, detail="Attachment too large")
+    try:
+        content = base64.b64decode(request.data, validate=True)
+    except binascii.Error:
+        raise HTTPException(status_code=400, detail="Attachment is not valid base64")
+    if hashlib.sha256(content).hexdigest() != request.sha256.lower():
+        raise HTTPException(status_code=400, detail="Checksum mismatch")
+    
+    supabase = get_supabase()
+    session_result = supabase.table("work_sessions")\
+        .select("id")\
+        .eq("id", session_id)\
+        .eq("user_id", user_id)\
+        .execute()
+    if not session_result.data:
+        raise HTTPException(status_code=404, detail="Session not found")
+    
+    supabase.table("session_attachments").upsert({
+        "attachment_id": request.attachmentId,
+        "session_id": session_id,
+        "user_id": user_id,
+        "name": request.name[:255],
+        "mime_type": request.mimeType,
+        "size_bytes": len(content),
+        "sha256": request.sha256.lower(),
+        "content": request.data
+    }, on_conflict="user_id,attachment_id").execute()
+    
+    return {"stored": True}
+
+
+@router.post("/desktop/session/{session_id}/attachments/{attachment_id}/remove")
+async def remove_attachment(session_id: str, attachment_id: str, authorization: str = Header(...)):
+    """
+    Delete an uploaded attachment. Removing one that is already gone is not an error.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    
+    supabase = get_supabase()
+    supabase.table("session_attachments")\
+        .delete()\
+        .eq("attachment_id", attachment_id)\
+        .eq("session_id", session_id)\
+        .eq("user_id", user_info["userId"])\
+        .execute()
+    
+    return {"removed": True}
+
+
+@router.post("/desktop/share/{share_token}/revoke")
+async def revoke_share_link(share_token: str, authorization: str = Header(...)):
+    """
+    Revoke a share link; it stops working immediately.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    
+    supabase = get_supabase()
+    result = supabase.table("session_shares")\
+        .update({"revoked_at": datetime.utcnow().isoformat()})\
+        .eq("token", share_token)\
+        .eq("user_id", user_info["userId"])\
+        .execute()
+    
+    if not result.data:
+        raise HTTPException(status_code=404, detail="Share link not found")
+    return {"revoked": True}
+
+
+@router.get("/share/{share_token}")
+async def get_shared_session(share_token: str):
+    """
+    Public view of a shared session: brief, role, duration and summary.
+    """
+    supabase = get_supabase()
+    share_result = supabase.table("session_shares")\
+        .select("session_id, expires_at, revoked_at")\
+        .eq("token", share_token)\
+        .execute()
+    
+    share = share_result.data[0] if share_result.data else None
+    if not share or share.get("revoked_at"):
+        raise HTTPException(status_code=404, detail="Share link not found")
+    if share.get("expires_at") and datetime.fromisoformat(share["expires_at"].replace("Z", "+00:00")).replace(tzinfo=None) < datetime.utcnow():
+        raise HTTPException(status_code=410, detail="Share link expired")
+    
+    session_result = supabase.table("work_sessions")\
+        .select("id, brief_id, role, started_at, ended_at, duration_minutes")\
+        .eq("id", share["session_id"])\
+        .execute()
+    if not session_result.data:
+        raise HTTPException(status_code=404, detail="Session not found")
+    session = session_result.data[0]
+    
+    brief_result = supabase.table("briefs")\
+        .select("name")\
+        .eq("id", session["brief_id"])\
+        .execute()
+    submission_result = supabase.table("submissions")\
+        .select("summary_lines")\
+        .eq("session_id", session["id"])\
+        .execute()
+    
+    return {
+        "briefName": brief_result.data[0]["name"] if brief_result.data else None,
+        "role": session["role"],
+        "startedAt": session.get("started_at"),
+        "endedAt": session.get("ended_at"),
+        "durationMinutes": session.get("duration_minutes"),
+        "summaryLines": submission_result.data[0]["summary_lines"] if submission_result.data else []
+    }
+
+
+@router.post("/desktop/command")
+async def send_desktop_command(
+    request: DesktopCommandRequest,
+    authorization: str = Header(...)
+):
+    """
+    Forward a command to the caller's connected desktop apps.
+    Returns how many desktop connections received it.
+    """
+    token = authorization.replace("Bearer ", "")
+    user_info = await verify_clerk_token(token)
+    
+    if request.command not in DESKTOP_COMMANDS:
+        raise HTTPException(status_code=400, detail=f"Unknown command: {request.command}")
+    
+    delivered = await websocket_manager.send_to_desktop(
+        user_info["userId"],
+        {
+            "type": "command",
+            "command": request.command,
+            "sessionId": request.sessionId
+        }
+    )
+    
+    return {"delivered": delivered}
+
+
 @router.websocket("/desktop/ws")
 async def desktop_websocket(websocket: WebSocket):
     """
@@ -841,6 +1391,13 @@ async def desktop_websocket(websocket: WebSocket):
     - authenticate: Auth with Clerk token
     - heartbeat: Keep connection alive
     - activity: Real-time activity tracking
+    - session_event: Live session start/stop/pause/marker, relayed to the org
+    - handoff_request: Another device wants to take over the running session
+    - handoff_ready: The recording device finalized its segment for the requester
+    
+    Server-initiated:
+    - command: e.g. stop_session, sent via POST /desktop/command;
+      handoff and continue_session relay a session handoff between devices
     """
     await websocket.accept()
     user_id: Optional[str] = None
@@ -855,8 +1412,11 @@ async def desktop_websocket(websocket: WebSocket):
                 token = data.get("token", "").replace("Bearer ", "")
                 try:
                     user_info = await verify_clerk_token(token)
+                    if user_id:
+                        websocket_manager.unregister_desktop(websocket, user_id)
                     user_id = user_info["userId"]
                     org_id = user_info.get("orgId")
+                    websocket_manager.register_desktop(websocket, user_id)
                     
                     await websocket.send_json({
                         "type": "authenticated",
@@ -879,6 +1439,44 @@ async def desktop_websocket(websocket: WebSocket):
                     "timestamp": datetime.utcnow().isoformat()
                 })
             
+            elif event_type == "session_event" and user_id:
+                event = data.get("event")
+                if event in SESSION_EVENTS and org_id:
+                    await websocket_manager.broadcast_event(
+                        f"session:live-{event}",
+                        {
+                            "userId": user_id,
+                            "sessionId": data.get("sessionId"),
+                            "briefId": data.get("briefId"),
+                            "text": data.get("text"),
+                            "at": data.get("at")
+                        },
+                        org_id
+                    )
+                await websocket.send_json({
+                    "type": "session_event_ack",
+                    "seq": data.get("seq")
+                })
+            
+            elif event_type == "handoff_request" and user_id:
+                # Every desktop gets it; only the one that is recording reacts
+                await websocket_manager.send_to_desktop(user_id, {
+                    "type": "command",
+                    "command": "handoff",
+                    "requestedBy": data.get("deviceId")
+                })
+            
+            elif event_type == "handoff_ready" and user_id:
+                # The requesting device starts a session that continues this one
+                await websocket_manager.send_to_desktop(user_id, {
+                    "type": "command",
+                    "command": "continue_session",
+                    "requestedBy": data.get("requestedBy"),
+                    "sessionId": data.get("sessionId"),
+                    "briefId": data.get("briefId"),
+                    "role": data.get("role")
+                })
+            
             else:
                 await websocket.send_json({
                     "type": "error",
@@ -889,3 +1487,6 @@ async def desktop_websocket(websocket: WebSocket):
         pass
     except Exception as e:
         print(f"Desktop WebSocket error: {e}")
+    finally:
+        if user_id:
+            websocket_manager.unregister_desktop(websocket, user_id)
@@ -76,7 +76,8 @@ async def verify_clerk_token(token: str) -> dict:
             "orgId": org_id,
             "email": payload.get("email"),
             "name": payload.get("name") or payload.get("username") or user_id,
-            "avatarUrl": payload.get("image_url")
+            "avatarUrl": payload.get("image_url"),
+            "sessionId": payload.get("sid")
         }
         
     except jwt.ExpiredSignatureError:
@@ -136,3 +137,39 @@ async def get_current_user(authorization: str) -> dict:
     token = authorization.replace("Bearer ", "")
     print(f"[AUTH] Token length: {len(token)}, starts with: {token[:20]}...")
     return await verify_clerk_token(token)
+
+
+async def revoke_clerk_session(session_id: str) -> None:
+    """
+    Revoke a Clerk session so its tokens can no longer be refreshed.
+    
+    Args:
+        session_id: Clerk session ID (the `sid` claim)
+        
+    Raises:
+        HTTPException: If Clerk is not configured or rejects the request
+    """
+    if not settings.CLERK_SECRET_KEY:
+        raise HTTPException(
+            status_code=status.HTTP_503_SERVICE_UNAVAILABLE,
+            detail={
+                "code": "REVOCATION_UNAVAILABLE",
+                "message": "Session revocation is not configured"
+            }
+        )
+    
+    async with httpx.AsyncClient() as client:
+        response = await client.post(
+            f"https://api.clerk.com/v1/sessions/{session_id}/revoke",
+            headers={"Authorization": f"Bearer {settings.CLERK_SECRET_KEY}"}
+        )
+    
+    if response.status_code >= 400:
+        print(f"[AUTH] Revocation failed for {session_id}: {response.status_code}")
+        raise HTTPException(
+            status_code=status.HTTP_502_BAD_GATEWAY,
+            detail={
+                "code": "REVOCATION_FAILED",
+                "message": "Could not revoke session"
+            }
+        )
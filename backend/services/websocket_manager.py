@@ -16,6 +16,8 @@ class WebSocketManager:
     def __init__(self):
         # Active connections: org_id -> set of websockets
         self.active_connections: Dict[str, Set[WebSocket]] = {}
+        # Authenticated desktop app connections: user_id -> set of websockets
+        self.desktop_connections: Dict[str, Set[WebSocket]] = {}
     
     async def connect(self, websocket: WebSocket, org_id: str):
         """
@@ -47,6 +49,59 @@ class WebSocketManager:
             if not self.active_connections[org_id]:
                 del self.active_connections[org_id]
     
+    def register_desktop(self, websocket: WebSocket, user_id: str):
+        """
+        Register an authenticated desktop app connection.
+        
+        Args:
+            websocket: WebSocket instance (already accepted)
+            user_id: Clerk user ID
+        """
+        if user_id not in self.desktop_connections:
+            self.desktop_connections[user_id] = set()
+        
+        self.desktop_connections[user_id].add(websocket)
+    
+    def unregister_desktop(self, websocket: WebSocket, user_id: str):
+        """
+        Remove a desktop app connection.
+        
+        Args:
+            websocket: WebSocket instance
+            user_id: Clerk user ID
+        """
+        if user_id in self.desktop_connections:
+            self.desktop_connections[user_id].discard(websocket)
+            
+            if not self.desktop_connections[user_id]:
+                del self.desktop_connections[user_id]
+    
+    async def send_to_desktop(self, user_id: str, message: dict) -> int:
+        """
+        Send a message to every desktop app the user is signed in to.
+        
+        Args:
+            user_id: Clerk user ID
+            message: Message dict to send
+            
+        Returns:
+            Number of connections the message was delivered to
+        """
+        delivered = 0
+        disconnected = set()
+        
+        for websocket in list(self.desktop_connections.get(user_id, set())):
+            try:
+                await websocket.send_json(message)
+                delivered += 1
+            except Exception:
+                disconnected.add(websocket)
+        
+        for websocket in disconnected:
+            self.unregister_desktop(websocket, user_id)
+        
+        return delivered
+    
     async def broadcast_to_org(self, org_id: str, message: dict):
         """
         Broadcast message to all connections in an organization.
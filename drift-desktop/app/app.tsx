@@ -7,16 +7,88 @@ const AI = lazy(() => import('./components/mainbar/AI').then((module) => ({ defa
 // Auth URL - opens in browser for login
 const AUTH_URL = 'https://test.usehavoc.com/auth/desktop'
 
+// Last event seq this window processed; sessionStorage survives a reload of the window
+const LAST_SEQ_KEY = 'drift:last-event-seq'
+
+interface DriftEventEnvelope {
+  seq: number
+  type: string
+  payload: any
+}
+
+function lastEventSeq(): number {
+  return Number(sessionStorage.getItem(LAST_SEQ_KEY)) || 0
+}
+
+/**
+ * Subscribe to the `drift:event` stream, resuming after the last event this
+ * window processed. Each envelope is handled once, in order, and acknowledged
+ * so the main process stops re-sending guaranteed events.
+ */
+function subscribeToEvents(handle: (envelope: DriftEventEnvelope) => void): () => void {
+  let seen = lastEventSeq()
+  const queued: DriftEventEnvelope[] = []
+  let resumed = false
+
+  const apply = (envelope: DriftEventEnvelope) => {
+    if (envelope.seq <= seen) return
+    seen = envelope.seq
+    sessionStorage.setItem(LAST_SEQ_KEY, String(seen))
+    handle(envelope)
+    window.api.invoke('events:ack', seen)
+  }
+
+  // Hold live events until the replay has been applied, so nothing is handled out of order
+  window.api.receive('drift:event', (envelope: DriftEventEnvelope) => {
+    if (resumed) apply(envelope)
+    else queued.push(envelope)
+  })
+
+  window.api
+    .invoke('events:resume', seen)
+    .then((result: { events: DriftEventEnvelope[]; latestSeq: number }) => {
+      // The main process restarted and its seqs started over; take its events from here on
+      if (seen > result.latestSeq) seen = 0
+      for (const envelope of result.events) apply(envelope)
+    })
+    .catch((error: unknown) => console.error('[Events] Resume failed:', error))
+    .finally(() => {
+      resumed = true
+      for (const envelope of queued.sort((a, b) => a.seq - b.seq)) apply(envelope)
+      queued.length = 0
+    })
+
+  return () => window.api.removeAllListeners('drift:event')
+}
+
 // Login Screen Component - intro -> auth flow
 function LoginScreen() {
   const [stage, setStage] = useState<'intro' | 'auth'>('intro')
   const [isLoading, setIsLoading] = useState(false)
+  const [authProblem, setAuthProblem] = useState<string | null>(null)
 
   const handleLogin = () => {
     setIsLoading(true)
+    setAuthProblem(null)
     window.api.send('open-auth-url', AUTH_URL)
   }
 
+  // Reset the button when the browser never calls back or the callback is rejected
+  useEffect(() => {
+    window.api.receive('auth-timeout', () => {
+      setIsLoading(false)
+      setAuthProblem('Login timed out. Click Continue to try again.')
+    })
+    window.api.receive('auth-error', () => {
+      setIsLoading(false)
+      setAuthProblem('Login could not be completed. Click Continue to try again.')
+    })
+    return () => {
+      window.api.removeAllListeners('auth-timeout')
+      window.api.removeAllListeners('auth-error')
+    }
+  }, [])
+
   const handleContinue = () => {
     if (stage !== 'intro') return
     setStage('auth')
@@ -46,7 +118,7 @@ function LoginScreen() {
 
             {stage === 'auth' && (
               <p className="login-helper">
-                Complete login in your browser. The app will update automatically.
+                {authProblem || 'Complete login in your browser. The app will update automatically.'}
               </p>
             )}
           </div>
@@ -126,15 +198,15 @@ export default function App() {
       setIsLoading(false)
     })
 
-    // Listen for auth token from callback
-    window.api.receive('auth-token-received', (data: { token: string; email: string }) => {
-      window.api.invoke('store-auth-token', data.token)
-      setIsAuthenticated(true)
+    // Auth events are guaranteed: replayed after a reload and re-sent until acknowledged
+    return subscribeToEvents((envelope) => {
+      if (envelope.type === 'auth-token-received') {
+        window.api.invoke('store-auth-token', envelope.payload.token)
+        setIsAuthenticated(true)
+      } else if (envelope.type === 'auth-logged-out') {
+        setIsAuthenticated(false)
+      }
     })
-
-    return () => {
-      window.api.removeAllListeners('auth-token-received')
-    }
   }, [])
 
   if (isLoading) {
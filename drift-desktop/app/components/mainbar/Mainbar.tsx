@@ -6,9 +6,9 @@ import { useUIActor } from '../../state/UIStateProvider'
 import { useSelector } from '@xstate/react'
 import { SessionActivity } from './SessionActivity'
 
-type ShortcutAction = 'toggleOverlay' | 'submitChat' | 'toggleSession' | 'toggleVoice' | 'escape'
+type ShortcutAction = 'toggleOverlay' | 'submitChat' | 'toggleSession' | 'toggleVoice' | 'quickCapture' | 'escape'
 type ShortcutConfig = Record<ShortcutAction, string>
-type ShortcutUpdateResult = { ok: boolean; failed: ShortcutAction[]; shortcuts: ShortcutConfig }
+type ShortcutUpdateResult = { ok: boolean; failed: ShortcutAction[]; shortcuts: ShortcutConfig; error?: string }
 
 // Project type from API
 interface Project {
@@ -83,6 +83,17 @@ function VoiceEqualizer({ isActive, onTranscript }: { isActive: boolean; onTrans
   const streamRef = useRef<MediaStream | null>(null)
   const animationRef = useRef<number | null>(null)
   const recognitionRef = useRef<SpeechRecognition | null>(null)
+  const languageRef = useRef<string>(navigator.language || 'de-DE')
+
+  // Dictation language from settings; 'auto' keeps the system language
+  useEffect(() => {
+    window.api.invoke('settings:get').then((settings) => {
+      const language = settings?.transcription?.language
+      if (language && language !== 'auto') {
+        languageRef.current = language
+      }
+    })
+  }, [])
 
   const analyze = useCallback(() => {
     if (!analyserRef.current) return
@@ -126,7 +137,7 @@ function VoiceEqualizer({ isActive, onTranscript }: { isActive: boolean; onTrans
         const recognition = new SpeechRecognitionClass()
         recognition.continuous = true
         recognition.interimResults = false
-        recognition.lang = 'de-DE' // German by default, can be made configurable
+        recognition.lang = languageRef.current
         
         recognition.onresult = (event: SpeechRecognitionEvent) => {
           const last = event.results.length - 1
@@ -256,6 +267,7 @@ export const Mainbar = () => {
     { id: 'submitChat', label: 'Open chat' },
     { id: 'toggleSession', label: 'Start/Stop session' },
     { id: 'toggleVoice', label: 'Toggle voice' },
+    { id: 'quickCapture', label: 'Quick note' },
     { id: 'escape', label: 'Close/Back' }
   ]
 
@@ -448,7 +460,7 @@ export const Mainbar = () => {
         .invoke('shortcuts:set', { [editingShortcut]: accelerator })
         .then((result: ShortcutUpdateResult) => {
           if (!result.ok) {
-            setShortcutError('Shortcut invalid or already in use.')
+            setShortcutError(result.error || 'Shortcut invalid or already in use.')
             return
           }
           setShortcuts(result.shortcuts)
@@ -556,6 +568,28 @@ export const Mainbar = () => {
     return () => window.removeEventListener('toggle-session', handleToggleSession)
   }, [selectedProject, isRecording, projects, userRole])
 
+  // Sessions ended by the main process (e.g. end-of-day wrap-up)
+  useEffect(() => {
+    window.api.receive('session:auto-ended', (result: any) => {
+      setIsRecording(false)
+      if (result?.error) return
+      setSessionEnded(true)
+      setSessionSummary({
+        sessionId: result.sessionId,
+        submissionId: result.submissionId,
+        briefId: result.briefId,
+        briefName: result.briefName,
+        durationMinutes: result.durationMinutes,
+        summaryLines: result.summaryLines || [],
+        activitySummary: result.activitySummary,
+        notes: result.notes
+      })
+    })
+    return () => {
+      window.api.removeAllListeners('session:auto-ended')
+    }
+  }, [])
+
   // Listen for toggle-voice shortcut
   useEffect(() => {
     const handleToggleVoice = () => {
@@ -640,9 +674,9 @@ export const Mainbar = () => {
   }
 
   const handleLogout = async () => {
-    console.log('[Logout] Clearing auth token...')
-    await window.api.invoke('store-auth-token', null)
-    console.log('[Logout] Token cleared, reloading...')
+    console.log('[Logout] Logging out...')
+    await window.api.invoke('auth:logout')
+    console.log('[Logout] Logged out, reloading...')
     window.location.reload()
   }
 
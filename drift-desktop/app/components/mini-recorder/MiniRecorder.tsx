@@ -0,0 +1,78 @@
+import { useEffect, useState } from 'react'
+import { Pause, Play, Square, GripVertical } from 'lucide-react'
+
+interface Heartbeat {
+  sessionId: string
+  elapsedSeconds: number
+  paused: boolean
+}
+
+function formatClock(totalSeconds: number): string {
+  const h = Math.floor(totalSeconds / 3600)
+  const m = Math.floor((totalSeconds % 3600) / 60)
+  const s = totalSeconds % 60
+  return `${h}:${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}`
+}
+
+/**
+ * Compact recorder shown in its own always-on-top window (`#mini-recorder`).
+ */
+export function MiniRecorder() {
+  const [heartbeat, setHeartbeat] = useState<Heartbeat | null>(null)
+  const [stopping, setStopping] = useState(false)
+
+  useEffect(() => {
+    window.api.receive('session:heartbeat', (data: Heartbeat) => setHeartbeat(data))
+    window.api.receive('session:ended', () => setHeartbeat(null))
+    return () => {
+      window.api.removeAllListeners('session:heartbeat')
+      window.api.removeAllListeners('session:ended')
+    }
+  }, [])
+
+  const togglePause = () => {
+    window.api.invoke(heartbeat?.paused ? 'session:resume' : 'session:pause')
+  }
+
+  const stop = async () => {
+    setStopping(true)
+    try {
+      await window.api.invoke('session:end')
+    } finally {
+      setStopping(false)
+    }
+  }
+
+  return (
+    <div
+      className="h-screen w-screen flex items-center gap-2 px-2 bg-[#0a0a0b] text-white select-none"
+      style={{ WebkitAppRegion: 'drag' } as React.CSSProperties}
+    >
+      <GripVertical className="w-4 h-4 text-white/30" />
+      <span
+        className={`w-2 h-2 rounded-full ${heartbeat && !heartbeat.paused ? 'bg-red-500 animate-pulse' : 'bg-white/30'}`}
+      />
+      <span className="flex-1 font-mono text-sm tabular-nums">
+        {heartbeat ? formatClock(heartbeat.elapsedSeconds) : 'Not recording'}
+      </span>
+      <div className="flex items-center gap-1" style={{ WebkitAppRegion: 'no-drag' } as React.CSSProperties}>
+        <button
+          className="p-1.5 rounded-md hover:bg-white/10 disabled:opacity-30"
+          onClick={togglePause}
+          disabled={!heartbeat || stopping}
+          title={heartbeat?.paused ? 'Resume' : 'Pause'}
+        >
+          {heartbeat?.paused ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+        </button>
+        <button
+          className="p-1.5 rounded-md hover:bg-white/10 disabled:opacity-30"
+          onClick={stop}
+          disabled={!heartbeat || stopping}
+          title="Stop and submit"
+        >
+          <Square className="w-4 h-4 text-red-400" />
+        </button>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,52 @@
+import { useEffect, useRef, useState } from 'react'
+import { NotebookPen } from 'lucide-react'
+
+/**
+ * Single-line note input shown in its own window (`#quick-capture`).
+ * Enter files the note, Escape dismisses it.
+ */
+export function QuickCapture() {
+  const [text, setText] = useState('')
+  const [recording, setRecording] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+  const inputRef = useRef<HTMLInputElement>(null)
+
+  useEffect(() => {
+    inputRef.current?.focus()
+    window.api.invoke('session:get-active').then((sessionId: string | null) => setRecording(!!sessionId))
+  }, [])
+
+  const submit = async () => {
+    const result = await window.api.invoke('quick-capture:submit', text)
+    if (result?.error) {
+      setError(result.error)
+    }
+  }
+
+  const onKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === 'Enter') {
+      e.preventDefault()
+      submit()
+    } else if (e.key === 'Escape') {
+      window.api.invoke('quick-capture:hide')
+    }
+  }
+
+  return (
+    <div className="h-screen w-screen flex items-center gap-2 px-3 bg-[#0a0a0b] text-white">
+      <NotebookPen className="w-4 h-4 shrink-0 text-white/40" />
+      <input
+        ref={inputRef}
+        className="flex-1 bg-transparent outline-none text-sm placeholder:text-white/30"
+        placeholder={recording ? 'Note for the current session' : 'Note (not recording, kept as a standalone note)'}
+        value={text}
+        onChange={(e) => {
+          setText(e.target.value)
+          setError(null)
+        }}
+        onKeyDown={onKeyDown}
+      />
+      {error && <span className="text-xs text-red-400 shrink-0">{error}</span>}
+    </div>
+  )
+}
@@ -3,17 +3,29 @@ import ReactDOM from 'react-dom/client'
 import appIcon from '@/resources/build/icon.png'
 import { WindowContextProvider, menuItems } from '@/lib/window'
 import App from './app'
+import { MiniRecorder } from './components/mini-recorder/MiniRecorder'
+import { QuickCapture } from './components/quick-capture/QuickCapture'
 import { UIStateProvider } from './state/UIStateProvider'
 import './styles/app.css'
 
 console.time('react mount')
+// The mini recorder and quick note windows load the same bundle with their own view
+const isMiniRecorder = window.location.hash === '#mini-recorder'
+const isQuickCapture = window.location.hash === '#quick-capture'
+
 ReactDOM.createRoot(document.getElementById('app') as HTMLElement).render(
   <React.StrictMode>
-    <UIStateProvider>
-      <WindowContextProvider titlebar={{ title: 'Orgio', icon: appIcon, menuItems }}>
-        <App />
-      </WindowContextProvider>
-    </UIStateProvider>
+    {isMiniRecorder ? (
+      <MiniRecorder />
+    ) : isQuickCapture ? (
+      <QuickCapture />
+    ) : (
+      <UIStateProvider>
+        <WindowContextProvider titlebar={{ title: 'Orgio', icon: appIcon, menuItems }}>
+          <App />
+        </WindowContextProvider>
+      </UIStateProvider>
+    )}
   </React.StrictMode>
 )
 console.timeEnd('react mount')
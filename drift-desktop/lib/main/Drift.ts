@@ -5,6 +5,40 @@ import { registerIpcHandlers } from './ipc/router'
 import { UIState, appState } from '../state/AppStateMachine'
 import { ShortcutsHelper } from './shortcuts'
 import { windowRegistry } from './windowRegistry'
+import { emitEvent } from './events'
+import { endOfDayScheduler } from './endOfDay'
+import { idleMonitor } from './idle'
+import { batteryMonitor } from './battery'
+import { goalTracker } from './goals'
+import { meetingDetector } from './meetings'
+import { recordingNudge } from './nudge'
+import { diskSpaceMonitor } from './diskSpace'
+import { captureWatchdog } from './captureWatchdog'
+import { sensitiveAppGuard } from './sensitiveApps'
+import { webhookNotifier } from './webhooks'
+import { slackIntegration } from './slack'
+import { vaultExporter } from './vault'
+import { gitBackup } from './gitBackup'
+import { liveSync } from './liveSync'
+import { proxyManager } from './proxy'
+import { calendarService } from './calendar'
+import { sessionManager } from './session'
+import { telemetry } from './telemetry'
+import { trayManager } from './tray'
+import { retentionManager } from './retention'
+import { jobScheduler } from './jobs'
+import { startSessionEditSync } from './sessionEdit'
+import { sessionAttachments } from './attachments'
+import { nativeMessaging } from './nativeMessaging'
+import { authExpiry } from './authExpiry'
+import { quickCapture } from './quickCapture'
+import { backendHealth } from './health'
+import { connectivity } from './connectivity'
+import { uploadQueue } from './uploadQueue'
+import { briefCache } from './briefs'
+import { recordingIndicator } from './recordingIndicator'
+import { cliServer } from './cli'
+import { controlApi } from './controlApi'
 import { join } from 'path'
 
 function registerResourcesProtocol() {
@@ -61,7 +95,11 @@ export class DriftApp {
 
     app.on('activate', () => {
       if (BrowserWindow.getAllWindows().length === 0) {
-        createAppWindow(this.isInvisible, this.t0)
+        this.mainWindow = createAppWindow(this.isInvisible, this.t0)
+        windowRegistry.setMainWindow(this.mainWindow)
+        trayManager.attach(this.mainWindow)
+      } else {
+        trayManager.showWindow()
       }
     })
   }
@@ -79,13 +117,51 @@ export class DriftApp {
     this.shortcutsHelper = new ShortcutsHelper(this.mainWindow)
 
     windowRegistry.setMainWindow(this.mainWindow)
+    trayManager.start()
+    trayManager.attach(this.mainWindow)
 
+    telemetry.instrumentIpc()
     this._registerIpcHandlers()
     this._registerStateMachineHandlers()
 
     this.shortcutsHelper.registerGlobalShortcuts()
+    this.shortcutsHelper.loadSaved()
+    this._registerMediaKeys()
+    this.shortcutsHelper.setQuickCaptureHandler(() => quickCapture.show())
     ;(global as any).appState = appState
 
+    proxyManager.start()
+    jobScheduler.start()
+    startSessionEditSync()
+    sessionAttachments.start()
+    endOfDayScheduler.start()
+    idleMonitor.start()
+    batteryMonitor.start()
+    goalTracker.start()
+    meetingDetector.start()
+    recordingNudge.start()
+    diskSpaceMonitor.start()
+    captureWatchdog.start()
+    sensitiveAppGuard.start()
+    webhookNotifier.start()
+    slackIntegration.start()
+    vaultExporter.start()
+    gitBackup.start()
+    liveSync.start()
+    backendHealth.start()
+    authExpiry.start()
+    connectivity.start()
+    uploadQueue.start()
+    briefCache.start()
+    calendarService.start()
+    recordingIndicator.start()
+    sessionManager.restore()
+    retentionManager.start()
+    cliServer.start()
+    controlApi.start()
+    nativeMessaging.start()
+    telemetry.start()
+
     this.mainWindow.webContents.on('did-finish-load', () => {
       if (this.mainWindow && !this.mainWindow.isDestroyed()) {
         this.mainWindow.webContents.send('invisibility-state-changed', this.isInvisible)
@@ -108,6 +184,7 @@ export class DriftApp {
       setMainWindow: (win: BrowserWindow) => {
         this.mainWindow = win
         windowRegistry.setMainWindow(win)
+        trayManager.attach(win)
       },
       getIsInvisible: () => this.isInvisible,
       setIsInvisible: (val: boolean) => {
@@ -119,6 +196,22 @@ export class DriftApp {
     })
   }
 
+  /**
+   * Maps the play/pause media key to pausing and resuming the session while one is running.
+   */
+  private _registerMediaKeys(): void {
+    const togglePause = () => {
+      if (sessionManager.getPauseReason()) {
+        sessionManager.resume()
+      } else {
+        sessionManager.pause('manual')
+      }
+    }
+    sessionManager.on('started', () => this.shortcutsHelper.setMediaPlayPauseHandler(togglePause))
+    sessionManager.on('restored', () => this.shortcutsHelper.setMediaPlayPauseHandler(togglePause))
+    sessionManager.on('ended', () => this.shortcutsHelper.setMediaPlayPauseHandler(null))
+  }
+
   /**
    * Registers the main state machine handler.
    */
@@ -131,7 +224,7 @@ export class DriftApp {
       }
 
       // Broadcast the state change to all windows
-      windowRegistry.broadcast('state-changed', { prev, next })
+      emitEvent('state-changed', { prev, next })
     })
   }
 }
@@ -0,0 +1,84 @@
+import { getStore } from './store'
+
+// A signed-in Drift account
+export interface Account {
+  name: string
+  email: string
+  token: string
+}
+
+/**
+ * Named accounts (e.g. work and personal) with exactly one active at a time.
+ * A token stored before multi-account support is moved into a "default" account by store migration 1.
+ */
+class AccountStore {
+  private async load() {
+    const s = await getStore()
+    const accounts: Record<string, Account> = s.get('accounts') || {}
+    const active: string | null = s.get('activeAccount') || null
+    return { s, accounts, active }
+  }
+
+  async getActive(): Promise<Account | null> {
+    const { accounts, active } = await this.load()
+    return (active && accounts[active]) || null
+  }
+
+  async list(): Promise<Array<{ name: string; email: string; active: boolean }>> {
+    const { accounts, active } = await this.load()
+    return Object.values(accounts).map((a) => ({ name: a.name, email: a.email, active: a.name === active }))
+  }
+
+  /**
+   * Add or update an account and make it the active one.
+   */
+  async upsert(name: string, email: string, token: string): Promise<void> {
+    const { s, accounts } = await this.load()
+    accounts[name] = { name, email, token }
+    s.set('accounts', accounts)
+    s.set('activeAccount', name)
+  }
+
+  /**
+   * Replace the active account's token, creating a default account if there is none.
+   */
+  async setActiveToken(token: string): Promise<void> {
+    const { s, accounts, active } = await this.load()
+    if (active && accounts[active]) {
+      accounts[active].token = token
+      s.set('accounts', accounts)
+    } else {
+      await this.upsert('default', 'user@drift.app', token)
+    }
+  }
+
+  async switchTo(name: string): Promise<boolean> {
+    const { s, accounts } = await this.load()
+    if (!accounts[name]) return false
+    s.set('activeAccount', name)
+    return true
+  }
+
+  /**
+   * Remove an account. If it was active, the next remaining account becomes active.
+   */
+  async remove(name: string): Promise<boolean> {
+    const { s, accounts, active } = await this.load()
+    if (!accounts[name]) return false
+
+    delete accounts[name]
+    s.set('accounts', accounts)
+    if (active === name) {
+      const next = Object.keys(accounts)[0]
+      if (next) {
+        s.set('activeAccount', next)
+      } else {
+        s.delete('activeAccount')
+      }
+    }
+    return true
+  }
+}
+
+// Singleton instance
+export const accounts = new AccountStore()
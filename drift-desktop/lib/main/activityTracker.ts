@@ -1,10 +1,14 @@
-import { BrowserWindow, desktopCapturer, screen } from 'electron'
+import { BrowserWindow } from 'electron'
+import { screenCapture, CaptureQuality } from './capture'
+import { captureAllowed } from './privacy'
+import { browserTabs } from './browserTabs'
 
 // Activity entry
 export interface ActivityEntry {
   app: string
   title: string
   file?: string
+  url?: string // front browser tab
   duration: number
   timestamp: number
   screenshot?: string // base64 encoded
@@ -64,13 +68,17 @@ const BROWSER_PATTERNS: Record<string, RegExp> = {
 
 class ActivityTracker {
   private activities: ActivityEntry[] = []
-  private currentActivity: { app: string; title: string; startTime: number; isRelevant: boolean } | null = null
+  private currentActivity: { app: string; title: string; url?: string; startTime: number; isRelevant: boolean } | null = null
   private intervalId: NodeJS.Timeout | null = null
   private isTracking = false
+  private isPaused = false
   private role: string = 'dev'
   private manualNotes: ManualNote[] = []
   private onActivityUpdate: ((activity: ActivityEntry) => void) | null = null
   private screenshotEnabled = true
+  private sessionScreenshots = true // off when the session's preset records activity only
+  private lastSampleAt = 0
+  private quality: CaptureQuality = { sampleSeconds: 3, scale: 0.5, quality: 60 }
 
   /**
    * Start tracking activities
@@ -79,20 +87,71 @@ class ActivityTracker {
     if (this.isTracking) return
     
     this.isTracking = true
+    this.isPaused = false
     this.activities = []
     this.currentActivity = null
     this.manualNotes = []
     this.role = role
     this.onActivityUpdate = onUpdate || null
+    this.lastSampleAt = Date.now()
     
     console.log(`[ActivityTracker] Started tracking for role: ${role}`)
     
     // Check active window every 3 seconds
-    this.intervalId = setInterval(() => this.captureActivity(), 3000)
+    this.intervalId = setInterval(() => this.captureActivity(), this.quality.sampleSeconds * 1000)
     
     // Capture immediately
     this.captureActivity()
   }
+
+  /**
+   * Pause tracking, closing the current activity at the given time
+   */
+  pause(at: number = Date.now()): void {
+    if (!this.isTracking || this.isPaused) return
+
+    this.isPaused = true
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+    this.closeCurrentActivity(at)
+    console.log('[ActivityTracker] Paused')
+  }
+
+  /**
+   * Resume tracking after a pause
+   */
+  resume(): void {
+    if (!this.isTracking || !this.isPaused) return
+
+    this.isPaused = false
+    this.lastSampleAt = Date.now()
+    this.intervalId = setInterval(() => this.captureActivity(), this.quality.sampleSeconds * 1000)
+    this.captureActivity()
+    console.log('[ActivityTracker] Resumed')
+  }
+
+  /**
+   * When the last sample completed; a stale value means sampling has stalled
+   */
+  getLastSampleAt(): number {
+    return this.lastSampleAt
+  }
+
+  /**
+   * Replace the sampling loop, e.g. after it stalled
+   */
+  restartSampling(): void {
+    if (!this.isTracking || this.isPaused) return
+
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+    }
+    this.intervalId = setInterval(() => this.captureActivity(), this.quality.sampleSeconds * 1000)
+    this.captureActivity()
+    console.log('[ActivityTracker] Sampling restarted')
+  }
   
   /**
    * Add a manual note
@@ -144,24 +203,41 @@ class ActivityTracker {
   }
   
   /**
-   * Capture screenshot of the active display
+   * Change how often windows are sampled and how screenshots are scaled.
+   * Takes effect immediately when tracking.
+   */
+  setCaptureQuality(quality: CaptureQuality): void {
+    const intervalChanged = quality.sampleSeconds !== this.quality.sampleSeconds
+    this.quality = { ...quality }
+    if (intervalChanged && this.intervalId) {
+      this.restartSampling()
+    }
+  }
+
+  /**
+   * Turn screenshots on or off without interrupting tracking, e.g. to save power
+   */
+  setScreenshotsEnabled(enabled: boolean): void {
+    this.screenshotEnabled = enabled
+  }
+
+  /**
+   * Whether this session takes screenshots at all. Separate from setScreenshotsEnabled,
+   * so a temporary pause (low battery) can't turn them back on.
+   */
+  setSessionScreenshots(enabled: boolean): void {
+    this.sessionScreenshots = enabled
+  }
+
+  /**
+   * Capture screenshot of the capture target (primary display by default)
    */
-  private async captureScreenshot(): Promise<string | undefined> {
-    if (!this.screenshotEnabled) return undefined
+  private async captureScreenshot(app: string): Promise<string | undefined> {
+    if (!this.screenshotEnabled || !this.sessionScreenshots || !(await captureAllowed(app))) return undefined
     
     try {
-      const primaryDisplay = screen.getPrimaryDisplay()
-      const { width, height } = primaryDisplay.size
-      
-      const sources = await desktopCapturer.getSources({
-        types: ['screen'],
-        thumbnailSize: { width: Math.floor(width / 2), height: Math.floor(height / 2) } // Half resolution for smaller file
-      })
-      
-      if (sources.length > 0) {
-        const thumbnail = sources[0].thumbnail
-        // Convert to JPEG base64 for smaller size
-        const jpegBuffer = thumbnail.toJPEG(60) // 60% quality
+      const jpegBuffer = await screenCapture.captureFrame(this.quality.scale, this.quality.quality)
+      if (jpegBuffer) {
         return jpegBuffer.toString('base64')
       }
       
@@ -181,6 +257,7 @@ class ActivityTracker {
     }
     
     this.isTracking = false
+    this.isPaused = false
     this.onActivityUpdate = null
     
     if (this.intervalId) {
@@ -189,19 +266,7 @@ class ActivityTracker {
     }
     
     // Finalize current activity
-    if (this.currentActivity) {
-      const duration = Math.floor((Date.now() - this.currentActivity.startTime) / 1000)
-      if (duration > 0) {
-        this.activities.push({
-          app: this.currentActivity.app,
-          title: this.currentActivity.title,
-          file: this.extractFile(this.currentActivity.app, this.currentActivity.title),
-          duration,
-          timestamp: this.currentActivity.startTime,
-          isRelevant: this.currentActivity.isRelevant
-        })
-      }
-    }
+    this.closeCurrentActivity(Date.now())
     
     // Filter to only relevant activities
     const relevantActivities = this.activities.filter(a => a.isRelevant)
@@ -212,19 +277,48 @@ class ActivityTracker {
     return { activities: relevantActivities, summary, notes: this.manualNotes }
   }
   
+  /**
+   * Record the current activity as ending at the given time
+   */
+  private closeCurrentActivity(at: number): void {
+    if (!this.currentActivity) return
+
+    const duration = Math.floor((at - this.currentActivity.startTime) / 1000)
+    if (duration > 0) {
+      this.activities.push({
+        app: this.currentActivity.app,
+        title: this.currentActivity.title,
+        file: this.extractFile(this.currentActivity.app, this.currentActivity.title),
+        url: this.currentActivity.url,
+        duration,
+        timestamp: this.currentActivity.startTime,
+        isRelevant: this.currentActivity.isRelevant
+      })
+    }
+    this.currentActivity = null
+  }
+
   /**
    * Get current tracking status
    */
-  getStatus(): { isTracking: boolean; role: string; activityCount: number; relevantCount: number } {
+  getStatus(): { isTracking: boolean; isPaused: boolean; role: string; activityCount: number; relevantCount: number } {
     const relevantCount = this.activities.filter(a => a.isRelevant).length
     return {
       isTracking: this.isTracking,
+      isPaused: this.isPaused,
       role: this.role,
       activityCount: this.activities.length,
       relevantCount
     }
   }
 
+  /**
+   * App in the foreground as of the last sample, if tracking
+   */
+  getCurrentApp(): string | null {
+    return this.currentActivity?.app ?? null
+  }
+
   /**
    * Get current activities without stopping
    */
@@ -246,6 +340,10 @@ class ActivityTracker {
       
       // Try to get active window from OS
       const activeWindow = await this.getActiveWindow()
+      const tab = activeWindow ? await browserTabs.activeTab(activeWindow.app) : null
+
+      // Tracking may have been paused or stopped while we were waiting
+      if (!this.isTracking || this.isPaused) return
       
       if (activeWindow) {
         activeApp = activeWindow.app
@@ -263,7 +361,8 @@ class ActivityTracker {
       if (this.currentActivity) {
         const sameActivity = 
           this.currentActivity.app === activeApp && 
-          this.currentActivity.title === activeTitle
+          this.currentActivity.title === activeTitle &&
+          this.currentActivity.url === tab?.url
         
         if (!sameActivity) {
           // Save previous activity
@@ -273,6 +372,7 @@ class ActivityTracker {
               app: this.currentActivity.app,
               title: this.currentActivity.title,
               file: this.extractFile(this.currentActivity.app, this.currentActivity.title),
+              url: this.currentActivity.url,
               duration,
               timestamp: this.currentActivity.startTime,
               isRelevant: this.currentActivity.isRelevant
@@ -281,17 +381,17 @@ class ActivityTracker {
           }
           
           // Start new activity
-          this.currentActivity = { app: activeApp, title: activeTitle, startTime: now, isRelevant }
+          this.currentActivity = { app: activeApp, title: activeTitle, url: tab?.url, startTime: now, isRelevant }
         }
       } else {
         // First activity
-        this.currentActivity = { app: activeApp, title: activeTitle, startTime: now, isRelevant }
+        this.currentActivity = { app: activeApp, title: activeTitle, url: tab?.url, startTime: now, isRelevant }
       }
       
       // Take screenshot ONLY if activity is relevant
       let screenshot: string | undefined
       if (isRelevant) {
-        screenshot = await this.captureScreenshot()
+        screenshot = await this.captureScreenshot(activeApp)
       }
       
       // Notify callback with current state
@@ -300,6 +400,7 @@ class ActivityTracker {
           app: activeApp,
           title: activeTitle,
           file: this.extractFile(activeApp, activeTitle),
+          url: tab?.url,
           duration: Math.floor((now - this.currentActivity.startTime) / 1000),
           timestamp: this.currentActivity.startTime,
           screenshot,
@@ -307,16 +408,17 @@ class ActivityTracker {
         }
         this.onActivityUpdate(currentEntry)
       }
-      
+
+      this.lastSampleAt = Date.now()
     } catch (error) {
       console.error('[ActivityTracker] Error capturing activity:', error)
     }
   }
 
   /**
-   * Get active window using platform-specific methods
+   * Get active window using platform-specific methods. Works whether or not tracking runs.
    */
-  private async getActiveWindow(): Promise<{ app: string; title: string } | null> {
+  async getActiveWindow(): Promise<{ app: string; title: string } | null> {
     try {
       // Use platform-specific method directly (more reliable)
       if (process.platform === 'win32') {
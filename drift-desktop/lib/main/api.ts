@@ -0,0 +1,464 @@
+import { net } from 'electron'
+import { performance } from 'node:perf_hooks'
+import axios, { AxiosError, AxiosInstance, AxiosRequestConfig, InternalAxiosRequestConfig } from 'axios'
+import { accounts } from './accounts'
+import { getDeviceId, DeviceInfo } from './device'
+import { getSettings, Settings } from './settings'
+import type { Brief } from './briefs'
+
+// Drift Backend API URL - use environment variable or default to GCP server
+const PRODUCTION_API_URL = process.env.DRIFT_API_URL || 'https://test.usehavoc.com/api'
+
+// Staging has no fixed host; it is only available when configured
+const STAGING_API_URL = process.env.DRIFT_STAGING_API_URL || null
+
+export interface RetryOptions {
+  retries: number // attempts after the first
+  baseDelayMs: number
+  maxDelayMs: number // cap for both backoff and Retry-After
+}
+
+// Attempt metadata attached to a failed request's error as `error.retry`
+export interface RetryMetadata {
+  attempts: number
+  retryable: boolean
+  totalDelayMs: number
+}
+
+declare module 'axios' {
+  interface AxiosRequestConfig {
+    // true/options enable retries, false disables them. Default: only idempotent methods are retried
+    retry?: boolean | Partial<RetryOptions>
+    retryState?: { attempts: number; totalDelayMs: number }
+  }
+  interface AxiosError {
+    retry?: RetryMetadata
+  }
+}
+
+const DEFAULT_RETRY: RetryOptions = { retries: 3, baseDelayMs: 500, maxDelayMs: 30 * 1000 }
+
+// Safe to send twice; other methods only retry when a request opts in
+const IDEMPOTENT_METHODS = ['get', 'head', 'options', 'put', 'delete']
+
+// Server states that may clear up on their own
+const RETRYABLE_STATUSES = [408, 425, 429, 500, 502, 503, 504]
+
+/**
+ * Resolve the API base URL for an environment, or null if it isn't configured.
+ */
+export function resolveApiBaseUrl(environment: Settings['environment']): string | null {
+  switch (environment.name) {
+    case 'staging':
+      return STAGING_API_URL
+    case 'custom':
+      return environment.customUrl ? environment.customUrl.replace(/\/+$/, '') : null
+    default:
+      return PRODUCTION_API_URL
+  }
+}
+
+/**
+ * Base URL of the selected backend, e.g. https://test.usehavoc.com/api.
+ * Throws when the selected environment has no URL; requests never fall back
+ * to production.
+ */
+export async function getApiBaseUrl(): Promise<string> {
+  const { environment } = await getSettings()
+  const url = resolveApiBaseUrl(environment)
+  if (!url) throw new DriftError('unconfigured', `No API URL configured for ${environment.name}`)
+  return url
+}
+
+/**
+ * Base URL of the web app that belongs to the selected backend.
+ */
+export async function getWebBaseUrl(): Promise<string> {
+  return (await getApiBaseUrl()).replace(/\/api$/, '')
+}
+
+/**
+ * Returns the active account's auth token, or null when signed out.
+ */
+export async function getAuthToken(): Promise<string | null> {
+  return (await accounts.getActive())?.token ?? null
+}
+
+// On Electron's network stack instead of Node's, so requests honor the proxy configuration
+function createClient() {
+  return axios.create({
+    adapter: 'fetch',
+    env: { fetch: (input: any, init?: any) => net.fetch(input, init) }
+  })
+}
+
+/**
+ * Axios instance for all backend calls. Replacing `http.defaults.adapter`
+ * swaps the transport, e.g. for a fake backend.
+ */
+export const http = createClient()
+
+/**
+ * For third-party URLs (webhooks, Slack): same network stack and retry policy,
+ * but nothing identifying this installation is added.
+ */
+export const externalHttp = createClient()
+
+// Identify this installation on every request
+http.interceptors.request.use(async (config) => {
+  config.headers.set('X-Drift-Device', await getDeviceId())
+  return config
+})
+
+function retryOptions(config: InternalAxiosRequestConfig): RetryOptions | null {
+  const { retry } = config
+  if (retry === false) return null
+  if (retry === undefined && !IDEMPOTENT_METHODS.includes((config.method || 'get').toLowerCase())) return null
+  return { ...DEFAULT_RETRY, ...(typeof retry === 'object' ? retry : {}) }
+}
+
+/**
+ * Whether a failed request may succeed if sent again: timeouts, network
+ * errors and transient server statuses. Client errors and cancellations are fatal.
+ */
+export function isRetryable(error: AxiosError): boolean {
+  if (axios.isCancel(error)) return false
+  if (error.response) return RETRYABLE_STATUSES.includes(error.response.status)
+  return true
+}
+
+/**
+ * Milliseconds to wait from a Retry-After header (seconds or HTTP date), or null.
+ */
+export function parseRetryAfter(value: unknown): number | null {
+  if (typeof value !== 'string' || !value.trim()) return null
+  const seconds = Number(value)
+  if (!Number.isNaN(seconds)) return Math.max(0, seconds * 1000)
+  const date = Date.parse(value)
+  return Number.isNaN(date) ? null : Math.max(0, date - Date.now())
+}
+
+/**
+ * Delay before retry number `attempt` (0-based): Retry-After if the server sent one,
+ * otherwise capped exponential backoff with full jitter.
+ */
+export function retryDelayMs(attempt: number, options: RetryOptions, retryAfter?: unknown): number {
+  const requested = parseRetryAfter(retryAfter)
+  if (requested !== null) return Math.min(requested, options.maxDelayMs)
+  return Math.random() * Math.min(options.baseDelayMs * 2 ** attempt, options.maxDelayMs)
+}
+
+const retryFailed = (client: AxiosInstance) => async (error: AxiosError) => {
+  const config = error.config
+  if (!config) throw error
+
+  const state = config.retryState ?? { attempts: 1, totalDelayMs: 0 }
+  const options = retryOptions(config)
+  const retryable = isRetryable(error)
+
+  if (options && retryable && state.attempts <= options.retries) {
+    const delay = retryDelayMs(state.attempts - 1, options, error.response?.headers?.['retry-after'])
+    console.warn(`[API] ${config.method?.toUpperCase()} ${config.url} failed (${error.response?.status ?? error.code}), retry ${state.attempts} in ${Math.round(delay)}ms`)
+    await new Promise((resolve) => setTimeout(resolve, delay))
+    return client.request({ ...config, retryState: { attempts: state.attempts + 1, totalDelayMs: state.totalDelayMs + delay } })
+  }
+
+  error.retry = { attempts: state.attempts, retryable, totalDelayMs: Math.round(state.totalDelayMs) }
+  throw error
+}
+
+http.interceptors.response.use(undefined, retryFailed(http))
+externalHttp.interceptors.response.use(undefined, retryFailed(externalHttp))
+
+export type DriftErrorKind =
+  | 'unauthenticated' // no token to send
+  | 'rejected' // 401/403: token expired, revoked or missing a scope
+  | 'not-found'
+  | 'http' // any other error status
+  | 'timeout'
+  | 'network' // no response at all
+  | 'cancelled'
+  | 'unconfigured' // the selected environment has no API URL
+
+/**
+ * A failed backend call. `status` is null when the server was never reached.
+ */
+export class DriftError extends Error {
+  constructor(
+    readonly kind: DriftErrorKind,
+    message: string,
+    readonly status: number | null = null,
+    readonly data?: unknown,
+    readonly retry?: RetryMetadata
+  ) {
+    super(message)
+    this.name = 'DriftError'
+  }
+
+  // The server answered, so sending the same request again later won't change the outcome
+  get reachedServer(): boolean {
+    return this.status !== null
+  }
+}
+
+/**
+ * Map anything thrown by `http` to a DriftError.
+ */
+export function toDriftError(error: unknown): DriftError {
+  if (error instanceof DriftError) return error
+  if (!axios.isAxiosError(error)) {
+    return new DriftError('network', error instanceof Error ? error.message : String(error))
+  }
+  if (axios.isCancel(error) || error.code === AxiosError.ERR_CANCELED) {
+    return new DriftError('cancelled', 'Request cancelled', null, undefined, error.retry)
+  }
+  if (error.response) {
+    const { status, data } = error.response
+    const kind: DriftErrorKind = status === 401 || status === 403 ? 'rejected' : status === 404 ? 'not-found' : 'http'
+    return new DriftError(kind, `API Error: ${status} - ${JSON.stringify(data)}`, status, data, error.retry)
+  }
+  if (error.code === AxiosError.ECONNABORTED || error.code === AxiosError.ETIMEDOUT) {
+    return new DriftError('timeout', 'Request timeout', null, undefined, error.retry)
+  }
+  return new DriftError('network', error.message || 'No connection to server', null, undefined, error.retry)
+}
+
+// One finished backend call, including its retries
+export interface ApiCallTrace {
+  method: string
+  path: string
+  status: number | null // null when the server was never reached
+  error: DriftErrorKind | null
+  attempts: number
+  durationMs: number
+  at: number // when the call started
+}
+
+// Traces kept for recentApiCalls()
+const API_TRACE_LIMIT = 100
+
+const apiTraces: ApiCallTrace[] = []
+const traceListeners = new Set<(trace: ApiCallTrace) => void>()
+
+/**
+ * The most recent backend calls, oldest first.
+ */
+export function recentApiCalls(): ApiCallTrace[] {
+  return [...apiTraces]
+}
+
+/**
+ * Subscribe to every finished backend call. Returns an unsubscribe function.
+ */
+export function onApiCall(listener: (trace: ApiCallTrace) => void): () => void {
+  traceListeners.add(listener)
+  return () => traceListeners.delete(listener)
+}
+
+function trace(entry: ApiCallTrace): void {
+  apiTraces.push(entry)
+  if (apiTraces.length > API_TRACE_LIMIT) apiTraces.shift()
+  if (entry.error) {
+    console.warn(`[API] ${entry.method} ${entry.path} failed (${entry.status ?? entry.error}) after ${entry.attempts} attempt(s), ${entry.durationMs}ms`)
+  }
+  for (const listener of traceListeners) {
+    try {
+      listener(entry)
+    } catch (error) {
+      console.warn('[API] Trace listener failed:', error)
+    }
+  }
+}
+
+interface CallOptions {
+  // 'required' fails without a token, 'optional' sends one if signed in, 'none' never does
+  auth?: 'required' | 'optional' | 'none'
+  token?: string // sent instead of the active account's
+  timeout?: number
+  retry?: AxiosRequestConfig['retry']
+  signal?: AbortSignal
+}
+
+async function call<T>(method: 'get' | 'post', path: string, body: unknown, options: CallOptions = {}): Promise<T> {
+  const auth = options.auth ?? 'required'
+  const token = auth === 'none' ? null : options.token ?? (await getAuthToken())
+  if (!token && auth === 'required') {
+    throw new DriftError('unauthenticated', 'Not authenticated')
+  }
+
+  const at = Date.now()
+  const started = performance.now()
+  const finished = (status: number | null, error: DriftErrorKind | null, attempts: number) =>
+    trace({ method: method.toUpperCase(), path, status, error, attempts, durationMs: Math.round(performance.now() - started), at })
+
+  try {
+    const response = await http.request<T>({
+      method,
+      url: `${await getApiBaseUrl()}${path}`,
+      data: method === 'post' ? body ?? {} : undefined,
+      headers: {
+        'Content-Type': 'application/json',
+        ...(token ? { Authorization: `Bearer ${token}` } : {})
+      },
+      timeout: options.timeout ?? 10000,
+      retry: options.retry,
+      signal: options.signal
+    })
+    finished(response.status, null, response.config.retryState?.attempts ?? 1)
+    return response.data
+  } catch (error) {
+    const failure = toDriftError(error)
+    finished(failure.status, failure.kind, failure.retry?.attempts ?? 1)
+    throw failure
+  }
+}
+
+export interface SyncResponse {
+  briefs?: Brief[]
+  role?: string
+}
+
+export interface SessionStartRequest {
+  briefId: string
+  role: string
+  continuesSessionId?: string
+}
+
+export interface SessionStartResponse {
+  sessionId: string
+  briefId?: string
+  briefName?: string
+  [key: string]: unknown
+}
+
+export interface SessionEndResponse {
+  submissionId: string
+  summaryLines?: string[]
+  [key: string]: unknown
+}
+
+export interface ScreenAnalysisRequest {
+  screenshot: string // base64 JPEG
+  projectName: string | null
+  projectDescription: string | null
+  previousInsights: string[]
+}
+
+export interface ScreenAnalysisResponse {
+  bullets?: string[]
+  skip?: boolean
+}
+
+export interface LiveInsightRequest {
+  activities: Array<{ app: string; title: string; file?: string; duration: number }>
+  notes: string[]
+  totalDuration: number // seconds
+}
+
+export interface SessionAnalysisRequest {
+  sessionId: string
+  submissionId?: string
+  briefId?: string
+  activities?: Array<{ app: string; totalDuration: number; files: string[] }>
+  notes?: Array<{ text: string; timestamp: number }>
+  summaryLines: string[]
+  durationMinutes: number
+}
+
+// Fields left out stay unchanged; an empty title or description clears it
+export interface SessionUpdateRequest {
+  title?: string
+  description?: string
+  briefId?: string
+  startedAt?: number
+}
+
+export interface AttachmentUploadRequest {
+  attachmentId: string
+  name: string
+  mimeType: string
+  size: number
+  sha256: string // hex
+  data: string // base64
+}
+
+export interface ShareLinkResponse {
+  token: string
+  expiresAt: string | null // ISO date, UTC
+}
+
+export interface CrashReportRequest {
+  reportId: string
+  kind: string
+  appVersion: string
+  platform: string
+  message: string
+  stack?: string
+  minidump?: string // base64
+  occurredAt: number
+}
+
+/**
+ * Typed calls to the Drift backend. Each sends the active account's token
+ * unless told otherwise and throws a DriftError on failure.
+ */
+export const driftApi = {
+  health: (timeout: number) => call<unknown>('get', '/desktop/health', undefined, { auth: 'none', timeout, retry: false }),
+
+  // Briefs and role of the signed-in user; read-only despite the POST, so it is retried
+  sync: (options: Pick<CallOptions, 'token' | 'timeout' | 'retry'> = {}) =>
+    call<SyncResponse>('post', '/desktop/sync', { userId: 'from-token' }, { retry: true, ...options }),
+
+  registerDevice: (token: string, device: DeviceInfo) =>
+    call<void>('post', '/desktop/devices/register', device, { token, retry: true }), // upsert
+
+  logout: (token: string) => call<void>('post', '/auth/logout', {}, { token, timeout: 5000 }),
+
+  startSession: (request: SessionStartRequest) => call<SessionStartResponse>('post', '/desktop/session/start', request),
+
+  reassignSession: (sessionId: string, briefId: string) =>
+    call<{ briefName?: string }>('post', '/desktop/session/reassign', { sessionId, briefId }),
+
+  updateSession: (sessionId: string, changes: SessionUpdateRequest) =>
+    call<{ updated: string[]; briefName: string | null }>('post', `/desktop/session/${encodeURIComponent(sessionId)}/update`, changes),
+
+  endSession: (manifest: Record<string, any>) =>
+    call<SessionEndResponse>('post', '/desktop/session/end', manifest, { timeout: 15000 }),
+
+  processNote: (note: string, projectName: string | null) =>
+    call<{ bullet?: string }>('post', '/desktop/session/process-note', { note, projectName }, { timeout: 5000 }),
+
+  analyzeScreen: (request: ScreenAnalysisRequest) =>
+    call<ScreenAnalysisResponse>('post', '/desktop/session/analyze-screen', request, { timeout: 30000 }),
+
+  liveInsight: (request: LiveInsightRequest) =>
+    call<{ insight: string | null }>('post', '/desktop/session/live-insight', request, { timeout: 30000 }),
+
+  analyzeSession: (request: SessionAnalysisRequest) =>
+    call<Record<string, any>>('post', '/desktop/session/analyze', request, { timeout: 60000 }),
+
+  chat: (message: string, screenshot: string, signal?: AbortSignal) =>
+    call<{ response?: string; message?: string }>('post', '/chat', { message, screenshot }, { auth: 'optional', timeout: 30000, signal }),
+
+  createShareLink: (sessionId: string, expiresInHours: number | null) =>
+    call<ShareLinkResponse>('post', `/desktop/session/${encodeURIComponent(sessionId)}/share`, { expiresInHours }),
+
+  uploadAttachment: (sessionId: string, attachment: AttachmentUploadRequest) =>
+    call<void>('post', `/desktop/session/${encodeURIComponent(sessionId)}/attachments`, attachment, { timeout: 120000, retry: true }),
+
+  removeAttachment: (sessionId: string, attachmentId: string) =>
+    call<void>(
+      'post',
+      `/desktop/session/${encodeURIComponent(sessionId)}/attachments/${encodeURIComponent(attachmentId)}/remove`,
+      {},
+      { retry: true }
+    ),
+
+  // Revoking twice is harmless
+  revokeShareLink: (token: string) =>
+    call<void>('post', `/desktop/share/${encodeURIComponent(token)}/revoke`, {}, { retry: true }),
+
+  // The backend refuses invalid tokens, so an expired one must not be sent along
+  submitCrashReport: (report: CrashReportRequest, withToken: boolean) =>
+    call<void>('post', '/desktop/crash-report', report, { auth: withToken ? 'required' : 'none', timeout: 30000 })
+}
@@ -0,0 +1,251 @@
+import { createHash, randomUUID } from 'crypto'
+import { promises as fs } from 'fs'
+import { basename, extname, join, resolve, sep } from 'path'
+import { getStore } from './store'
+import { profiles } from './profiles'
+import { sessionHistory } from './sessionHistory'
+import { sessionManager } from './session'
+import { uploadQueue } from './uploadQueue'
+import { jobScheduler } from './jobs'
+import { driftApi, DriftError } from './api'
+import { encryptFileStream, readEncryptedStreamFile } from './crypto'
+
+// Largest file that can be attached; uploads are sent in one request
+const MAX_ATTACHMENT_BYTES = 20 * 1024 * 1024
+
+// By extension; anything else is sent as application/octet-stream
+const MIME_TYPES: Record<string, string> = {
+  '.pdf': 'application/pdf',
+  '.json': 'application/json',
+  '.zip': 'application/zip',
+  '.txt': 'text/plain',
+  '.md': 'text/markdown',
+  '.csv': 'text/csv',
+  '.html': 'text/html',
+  '.log': 'text/plain',
+  '.png': 'image/png',
+  '.jpg': 'image/jpeg',
+  '.jpeg': 'image/jpeg',
+  '.gif': 'image/gif',
+  '.webp': 'image/webp',
+  '.svg': 'image/svg+xml',
+  '.mp3': 'audio/mpeg',
+  '.wav': 'audio/wav',
+  '.mp4': 'video/mp4',
+  '.mov': 'video/quicktime',
+  '.webm': 'video/webm',
+  '.docx': 'application/vnd.openxmlformats-officedocument.wordprocessingml.document',
+  '.xlsx': 'application/vnd.openxmlformats-officedocument.spreadsheetml.sheet',
+  '.pptx': 'application/vnd.openxmlformats-officedocument.presentationml.presentation'
+}
+
+export interface Attachment {
+  id: string
+  sessionId: string
+  name: string // original file name
+  file: string // encrypted copy in the attachments directory
+  size: number
+  sha256: string
+  mimeType: string
+  addedAt: number
+  uploaded: boolean
+  uploadError?: string // why the server refused it
+}
+
+function attachmentsDir(): string {
+  return join(profiles.dataDir(), 'attachments')
+}
+
+// The stored path, unless it points outside the attachments directory (e.g. from an edited store)
+function fileOf(attachment: Attachment): string | null {
+  const file = resolve(attachment.file)
+  return file.startsWith(attachmentsDir() + sep) ? file : null
+}
+
+// Server answered and won't accept it on a retry either
+function refused(error: unknown): error is DriftError {
+  return error instanceof DriftError && error.kind !== 'rejected' && error.status !== null && error.status < 500
+}
+
+/**
+ * Files attached to a session, e.g. a design export or a test log. Each is
+ * copied into the profile's attachments directory, encrypted with the data key,
+ * so later changes to the original don't matter, and uploaded in the background once its session has
+ * been submitted.
+ */
+class SessionAttachments {
+  start(): void {
+    jobScheduler.register('attachment-upload', (payload) => this.upload((payload as { id: string }).id))
+    jobScheduler.register('attachment-remove', async (payload) => {
+      const { sessionId, id } = payload as { sessionId: string; id: string }
+      await driftApi.removeAttachment(sessionId, id)
+    })
+
+    const submitted = (sessionId: string) => void this.scheduleUploads(sessionId)
+    sessionManager.on('uploaded', submitted)
+    uploadQueue.on('uploaded', submitted)
+
+    // Sessions purged from the trash take their files with them
+    sessionHistory.on('purged', (ids: string[]) => {
+      for (const id of ids) void this.removeAll(id)
+    })
+  }
+
+  /**
+   * Copy a file into the session and record its checksum and MIME type.
+   * Works for the active session and for completed ones.
+   */
+  async attach(sessionId: string, path: string): Promise<{ ok: true; attachment: Attachment } | { error: string }> {
+    const isActive = sessionManager.getActive()?.sessionId === sessionId
+    const record = isActive ? null : await sessionHistory.get(sessionId)
+    if (!isActive && !record) return { error: `Unknown session: ${sessionId}` }
+
+    const stat = await fs.stat(path).catch(() => null)
+    if (!stat?.isFile()) return { error: `Not a file: ${path}` }
+    if (stat.size > MAX_ATTACHMENT_BYTES) {
+      return { error: `File is too large to attach (max ${MAX_ATTACHMENT_BYTES / 1024 / 1024} MB)` }
+    }
+
+    const id = randomUUID()
+    const name = basename(path)
+    const dir = join(attachmentsDir(), sessionId)
+    const file = join(dir, `${id}${extname(name)}`)
+    let sha256: string
+    let size: number
+    try {
+      await fs.mkdir(dir, { recursive: true })
+      await encryptFileStream(path, file)
+      // Of the copy, in case the original changed while it was being read
+      const content = await readEncryptedStreamFile(file)
+      sha256 = createHash('sha256').update(content).digest('hex')
+      size = content.length
+    } catch (error: any) {
+      await fs.rm(file, { force: true })
+      return { error: `Could not attach file: ${error.message}` }
+    }
+
+    const attachment: Attachment = {
+      id,
+      sessionId,
+      name,
+      file,
+      size,
+      sha256,
+      mimeType: MIME_TYPES[extname(name).toLowerCase()] ?? 'application/octet-stream',
+      addedAt: Date.now(),
+      uploaded: false
+    }
+    const s = await getStore()
+    s.set('attachments', [...((s.get('attachments') || []) as Attachment[]), attachment])
+    console.log('[Attachments] Attached', name, 'to', sessionId)
+
+    if (record?.uploaded) await this.scheduleUpload(attachment)
+    return { ok: true, attachment }
+  }
+
+  async list(sessionId: string): Promise<Attachment[]> {
+    const s = await getStore()
+    return ((s.get('attachments') || []) as Attachment[])
+      .filter((a) => a.sessionId === sessionId)
+      .sort((a, b) => a.addedAt - b.addedAt)
+  }
+
+  /**
+   * Delete an attachment locally and, if it was uploaded, on the server.
+   */
+  async remove(id: string): Promise<{ ok: true } | { error: string }> {
+    const s = await getStore()
+    const all: Attachment[] = s.get('attachments') || []
+    const attachment = all.find((a) => a.id === id)
+    if (!attachment) return { error: `Unknown attachment: ${id}` }
+
+    s.set('attachments', all.filter((a) => a.id !== id))
+    const file = fileOf(attachment)
+    if (file) await fs.rm(file, { force: true })
+    await jobScheduler.cancel(`attachment:${id}`)
+    if (attachment.uploaded) {
+      await jobScheduler.schedule('attachment-remove', { at: Date.now() }, {
+        id: `attachment-remove:${id}`,
+        payload: { sessionId: attachment.sessionId, id },
+        maxAttempts: 10
+      })
+    }
+    console.log('[Attachments] Removed', attachment.name, 'from', attachment.sessionId)
+    return { ok: true }
+  }
+
+  /**
+   * Move attachments to another session, e.g. when sessions are merged.
+   */
+  async reassign(fromIds: string[], toId: string): Promise<void> {
+    const s = await getStore()
+    const all: Attachment[] = s.get('attachments') || []
+    if (!all.some((a) => fromIds.includes(a.sessionId))) return
+    s.set('attachments', all.map((a) => (fromIds.includes(a.sessionId) ? { ...a, sessionId: toId, uploaded: false } : a)))
+    await this.scheduleUploads(toId)
+  }
+
+  private async removeAll(sessionId: string): Promise<void> {
+    const s = await getStore()
+    const all: Attachment[] = s.get('attachments') || []
+    s.set('attachments', all.filter((a) => a.sessionId !== sessionId))
+    await fs.rm(join(attachmentsDir(), sessionId), { recursive: true, force: true })
+  }
+
+  // Queue everything not yet uploaded, once the session itself is on the server
+  private async scheduleUploads(sessionId: string): Promise<void> {
+    const record = await sessionHistory.get(sessionId)
+    if (!record?.uploaded) return
+    for (const attachment of await this.list(sessionId)) {
+      if (!attachment.uploaded && !attachment.uploadError) await this.scheduleUpload(attachment)
+    }
+  }
+
+  private scheduleUpload(attachment: Attachment): Promise<unknown> {
+    return jobScheduler.schedule('attachment-upload', { at: Date.now() }, {
+      id: `attachment:${attachment.id}`,
+      payload: { id: attachment.id },
+      maxAttempts: 10
+    })
+  }
+
+  private async upload(id: string): Promise<void> {
+    const attachment = (await this.listAll()).find((a) => a.id === id)
+    if (!attachment || attachment.uploaded) return
+
+    let outcome: Partial<Attachment> = { uploaded: true }
+    try {
+      const file = fileOf(attachment)
+      if (!file) throw Object.assign(new Error('Attachment is outside the attachments directory'), { code: 'ENOENT' })
+      const data = await readEncryptedStreamFile(file)
+      await driftApi.uploadAttachment(attachment.sessionId, {
+        attachmentId: attachment.id,
+        name: attachment.name,
+        mimeType: attachment.mimeType,
+        size: attachment.size,
+        sha256: attachment.sha256,
+        data: data.toString('base64')
+      })
+    } catch (error: any) {
+      if (error.code === 'ENOENT') {
+        outcome = { uploadError: 'File is missing' }
+      } else if (refused(error)) {
+        outcome = { uploadError: error.message }
+      } else {
+        throw error
+      }
+      console.warn('[Attachments] Not uploading', attachment.name, ':', outcome.uploadError)
+    }
+
+    const s = await getStore()
+    s.set('attachments', ((s.get('attachments') || []) as Attachment[]).map((a) => (a.id === id ? { ...a, ...outcome } : a)))
+  }
+
+  private async listAll(): Promise<Attachment[]> {
+    const s = await getStore()
+    return s.get('attachments') || []
+  }
+}
+
+// Singleton instance
+export const sessionAttachments = new SessionAttachments()
@@ -0,0 +1,57 @@
+import { emitEvent } from './events'
+import { getAuthInfo } from './authInfo'
+import { notify } from './notifications'
+import { windowRegistry } from './windowRegistry'
+
+// Warn this long before the token expires
+const WARN_BEFORE_MS = 10 * 60 * 1000
+
+const CHECK_INTERVAL_MS = 60 * 1000
+
+/**
+ * Warns before the active account's token expires, so the user can sign in
+ * again before uploads start failing. Emits `auth-expiring-soon` once per
+ * token, plus a notification when the window is hidden.
+ */
+class AuthExpiryWatcher {
+  private interval: NodeJS.Timeout | null = null
+  private warnedFor: string | null = null // account and expiry of the token already warned about
+
+  start(): void {
+    if (this.interval) return
+    this.interval = setInterval(() => this.check(), CHECK_INTERVAL_MS)
+    this.check()
+  }
+
+  stop(): void {
+    if (this.interval) {
+      clearInterval(this.interval)
+      this.interval = null
+    }
+  }
+
+  private async check(): Promise<void> {
+    const info = await getAuthInfo()
+    if (!info?.expiresAt) return
+
+    const remaining = info.expiresAt - Date.now()
+    const key = `${info.account}:${info.expiresAt}`
+    if (remaining > WARN_BEFORE_MS || this.warnedFor === key) return
+    this.warnedFor = key
+
+    const payload = { account: info.account, expiresAt: info.expiresAt, secondsLeft: Math.max(0, Math.floor(remaining / 1000)) }
+    emitEvent('auth-expiring-soon', payload)
+    console.log('[Auth] Token of', info.account, remaining > 0 ? `expires in ${payload.secondsLeft}s` : 'has expired')
+
+    const main = windowRegistry.getMainWindow()
+    if (!main || main.isDestroyed() || !main.isVisible()) {
+      const body = remaining > 0
+        ? `Your sign-in expires in ${Math.ceil(remaining / 60000)} minutes. Sign in again to keep recording and syncing.`
+        : 'Your sign-in has expired. Sign in again to keep recording and syncing.'
+      await notify('authExpiring', 'Drift · Sign-in expiring', body)
+    }
+  }
+}
+
+// Singleton instance
+export const authExpiry = new AuthExpiryWatcher()
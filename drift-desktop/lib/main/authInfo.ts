@@ -0,0 +1,92 @@
+import { accounts } from './accounts'
+
+// Scopes the desktop app checks before calling the backend
+export type Scope = 'sessions:write' | 'briefs:read' | 'chat'
+
+// What the UI needs to know about the signed-in account
+export interface AuthInfo {
+  account: string
+  email: string
+  userId: string | null
+  orgId: string | null
+  scopes: Scope[] | 'all' // 'all' when the token carries no scope claim
+  expiresAt: number | null
+}
+
+// Returned instead of calling the backend when the token lacks a scope
+export interface MissingScopeError {
+  error: string
+  code: 'missing-scope'
+  scope: Scope
+}
+
+// Returned instead of starting work that needs the backend when the token is already dead
+export interface AuthRequiredError {
+  error: string
+  code: 'auth-required'
+  expiredAt: number | null // null: not signed in
+}
+
+/**
+ * Claims of a JWT, without verifying the signature. Only used to decide what
+ * to offer in the UI; the backend still verifies every request.
+ */
+export function decodeTokenClaims(token: string): Record<string, any> | null {
+  const payload = token.split('.')[1]
+  if (!payload) return null
+  try {
+    return JSON.parse(Buffer.from(payload, 'base64url').toString('utf8'))
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Scopes from a space-separated `scope` claim or a `scp` array. Session tokens
+ * issued before scoped tokens carry neither and keep full access.
+ */
+export function grantedScopes(claims: Record<string, any>): Scope[] | 'all' {
+  if (typeof claims.scope === 'string') return claims.scope.split(' ').filter(Boolean) as Scope[]
+  if (Array.isArray(claims.scp)) return claims.scp as Scope[]
+  return 'all'
+}
+
+export async function getAuthInfo(): Promise<AuthInfo | null> {
+  const active = await accounts.getActive()
+  if (!active) return null
+
+  const claims = decodeTokenClaims(active.token) ?? {}
+  return {
+    account: active.name,
+    email: active.email,
+    userId: claims.sub ?? null,
+    orgId: claims.org_id ?? null,
+    scopes: grantedScopes(claims),
+    expiresAt: typeof claims.exp === 'number' ? claims.exp * 1000 : null
+  }
+}
+
+/**
+ * Null when signed in with a token that hasn't expired. Tokens without an
+ * `exp` claim count as valid; the backend has the final say.
+ */
+export async function requireLiveToken(): Promise<AuthRequiredError | null> {
+  const info = await getAuthInfo()
+  if (!info) return { error: 'Not authenticated', code: 'auth-required', expiredAt: null }
+  if (info.expiresAt !== null && info.expiresAt <= Date.now()) {
+    return { error: 'Your sign-in has expired. Sign in again to record.', code: 'auth-required', expiredAt: info.expiresAt }
+  }
+  return null
+}
+
+/**
+ * Null when the active token grants `scope` (or when signed out, which the
+ * caller reports on its own).
+ */
+export async function requireScope(scope: Scope): Promise<MissingScopeError | null> {
+  const info = await getAuthInfo()
+  if (!info || info.scopes === 'all' || info.scopes.includes(scope)) return null
+
+  console.warn('[Auth] Token is missing scope', scope)
+  return { error: `Your sign-in does not allow this (missing "${scope}" permission)`, code: 'missing-scope', scope }
+}
@@ -0,0 +1,211 @@
+import { app } from 'electron'
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { getWebBaseUrl } from './api'
+
+export type AuthPage = 'success' | 'error' | 'timeout'
+
+interface PageStrings {
+  title: string
+  message: string
+}
+
+type Locale = 'en' | 'de' | 'fr' | 'es'
+
+const STRINGS: Record<Locale, Record<AuthPage, PageStrings>> = {
+  en: {
+    success: { title: '✓ Connected to Drift!', message: 'You can close this tab and return to the app.' },
+    error: { title: 'Sign-in failed', message: 'Drift could not complete the sign-in. Return to the app and click “Continue” to try again.' },
+    timeout: { title: 'Sign-in expired', message: 'This sign-in link is no longer valid. Return to the app and start the sign-in again.' }
+  },
+  de: {
+    success: { title: '✓ Mit Drift verbunden!', message: 'Du kannst diesen Tab schließen und zur App zurückkehren.' },
+    error: { title: 'Anmeldung fehlgeschlagen', message: 'Drift konnte die Anmeldung nicht abschließen. Kehre zur App zurück und klicke erneut auf „Continue“.' },
+    timeout: { title: 'Anmeldung abgelaufen', message: 'Dieser Anmeldelink ist nicht mehr gültig. Starte die Anmeldung in der App erneut.' }
+  },
+  fr: {
+    success: { title: '✓ Connecté à Drift !', message: 'Vous pouvez fermer cet onglet et revenir à l’application.' },
+    error: { title: 'Échec de la connexion', message: 'Drift n’a pas pu terminer la connexion. Revenez à l’application et cliquez à nouveau sur « Continue ».' },
+    timeout: { title: 'Connexion expirée', message: 'Ce lien de connexion n’est plus valide. Relancez la connexion depuis l’application.' }
+  },
+  es: {
+    success: { title: '✓ ¡Conectado a Drift!', message: 'Puedes cerrar esta pestaña y volver a la aplicación.' },
+    error: { title: 'Error al iniciar sesión', message: 'Drift no pudo completar el inicio de sesión. Vuelve a la aplicación y pulsa «Continue» de nuevo.' },
+    timeout: { title: 'Inicio de sesión caducado', message: 'Este enlace ya no es válido. Inicia sesión de nuevo desde la aplicación.' }
+  }
+}
+
+/** Why a callback was turned away, shown under the error page's message */
+export type AuthErrorReason = 'origin' | 'nonce' | 'missing-token' | 'rejected' | 'unconfigured'
+
+const ERROR_DETAILS: Record<Locale, Record<AuthErrorReason, string>> = {
+  en: {
+    origin: 'The request did not come from the Drift sign-in page.',
+    nonce: 'This sign-in link belongs to an earlier attempt.',
+    'missing-token': 'The sign-in page did not send a token.',
+    rejected: 'The server did not accept the sign-in token.',
+    unconfigured: 'No server is configured for the selected environment.'
+  },
+  de: {
+    origin: 'Die Anfrage kam nicht von der Drift-Anmeldeseite.',
+    nonce: 'Dieser Anmeldelink gehört zu einem früheren Versuch.',
+    'missing-token': 'Die Anmeldeseite hat kein Token gesendet.',
+    rejected: 'Der Server hat das Anmeldetoken nicht akzeptiert.',
+    unconfigured: 'Für die gewählte Umgebung ist kein Server eingerichtet.'
+  },
+  fr: {
+    origin: 'La requête ne provient pas de la page de connexion Drift.',
+    nonce: 'Ce lien de connexion appartient à une tentative précédente.',
+    'missing-token': 'La page de connexion n’a pas envoyé de jeton.',
+    rejected: 'Le serveur n’a pas accepté le jeton de connexion.',
+    unconfigured: 'Aucun serveur n’est configuré pour l’environnement choisi.'
+  },
+  es: {
+    origin: 'La solicitud no procede de la página de inicio de sesión de Drift.',
+    nonce: 'Este enlace pertenece a un intento anterior.',
+    'missing-token': 'La página de inicio de sesión no envió ningún token.',
+    rejected: 'El servidor no aceptó el token de inicio de sesión.',
+    unconfigured: 'No hay ningún servidor configurado para el entorno seleccionado.'
+  }
+}
+
+const DEFAULT_LOCALE: Locale = 'en'
+
+function isLocale(value: string): value is Locale {
+  return value in STRINGS
+}
+
+/**
+ * Pick the page language: the configured one unless it is 'auto', otherwise
+ * the browser's first supported Accept-Language entry, falling back to English.
+ */
+export function resolveLocale(setting: string, acceptLanguage?: string): Locale {
+  const configured = setting.toLowerCase().split('-')[0]
+  if (setting !== 'auto' && isLocale(configured)) return configured
+
+  const preferred = (acceptLanguage || '')
+    .split(',')
+    .map((part) => {
+      const [tag, q] = part.trim().split(';q=')
+      return { language: tag.toLowerCase().split('-')[0], q: q ? Number(q) : 1 }
+    })
+    .filter((entry) => entry.language && !Number.isNaN(entry.q))
+    .sort((a, b) => b.q - a.q)
+    .find((entry) => isLocale(entry.language))
+
+  return preferred ? (preferred.language as Locale) : DEFAULT_LOCALE
+}
+
+function escapeHtml(text: string): string {
+  return text.replace(/[&<>"']/g, (c) => `&#${c.charCodeAt(0)};`)
+}
+
+/** Values a white-label build can set in auth-pages/branding.json */
+interface AuthBranding {
+  appName: string
+  accentColor: string
+  errorColor: string
+  background: string
+  redirectUrl?: string
+}
+
+const DEFAULT_BRANDING: AuthBranding = {
+  appName: 'Drift',
+  accentColor: '#4ade80',
+  errorColor: '#f87171',
+  background: 'linear-gradient(135deg, #1a1a2e 0%, #16213e 100%)'
+}
+
+// Templates (success.html, error.html, timeout.html) and branding.json live here
+function templateDir(): string {
+  return join(app.getPath('userData'), 'auth-pages')
+}
+
+async function readOptional(path: string): Promise<string | null> {
+  try {
+    return await fs.readFile(path, 'utf8')
+  } catch {
+    return null
+  }
+}
+
+async function loadBranding(): Promise<AuthBranding> {
+  const raw = await readOptional(join(templateDir(), 'branding.json'))
+  if (!raw) return DEFAULT_BRANDING
+  try {
+    const parsed = JSON.parse(raw)
+    const branding = { ...DEFAULT_BRANDING }
+    for (const key of Object.keys(DEFAULT_BRANDING).concat('redirectUrl') as (keyof AuthBranding)[]) {
+      if (typeof parsed[key] === 'string') branding[key] = parsed[key]
+    }
+    return branding
+  } catch (error) {
+    console.warn('[Auth] Ignoring invalid branding.json:', error)
+    return DEFAULT_BRANDING
+  }
+}
+
+function builtInPage(vars: Record<string, string>): string {
+  return `
+    <html lang="{{lang}}">
+      <head>
+        <meta charset="utf-8">
+        <title>{{appName}}</title>
+        <style>
+          body {
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            height: 100vh;
+            margin: 0;
+            background: {{background}};
+            color: white;
+          }
+          .container { text-align: center; max-width: 480px; padding: 0 24px; }
+          h1 { color: {{color}}; }
+          .detail { opacity: 0.6; font-size: 13px; }
+        </style>
+      </head>
+      <body>
+        <div class="container">
+          <h1>{{title}}</h1>
+          <p>{{message}}</p>
+          ${vars.detail ? '<p class="detail">{{detail}}</p>' : ''}
+        </div>
+      </body>
+    </html>
+  `
+}
+
+/**
+ * HTML for the page the browser shows after the auth callback.
+ * `reason` adds a line explaining what went wrong.
+ *
+ * A `<page>.html` file in the auth-pages folder replaces the built-in page. It may use
+ * {{appName}}, {{title}}, {{message}}, {{detail}}, {{color}}, {{accentColor}},
+ * {{errorColor}}, {{background}}, {{redirectUrl}} and {{lang}}; values are HTML-escaped.
+ */
+export async function renderAuthPage(page: AuthPage, locale: Locale, reason?: AuthErrorReason): Promise<string> {
+  const { title, message } = STRINGS[locale][page]
+  const detail = reason ? ERROR_DETAILS[locale][reason] : ''
+  const branding = await loadBranding()
+
+  const vars: Record<string, string> = {
+    appName: branding.appName,
+    title: branding.appName === DEFAULT_BRANDING.appName ? title : title.replace('Drift', branding.appName),
+    message,
+    detail,
+    color: page === 'success' ? branding.accentColor : branding.errorColor,
+    accentColor: branding.accentColor,
+    errorColor: branding.errorColor,
+    background: branding.background,
+    redirectUrl: branding.redirectUrl || (await getWebBaseUrl()),
+    lang: locale
+  }
+
+  const template = (await readOptional(join(templateDir(), `${page}.html`))) ?? builtInPage(vars)
+  return template.replace(/\{\{(\w+)\}\}/g, (match, name: string) =>
+    name in vars ? escapeHtml(vars[name]) : match
+  )
+}
@@ -0,0 +1,114 @@
+import { powerMonitor } from 'electron'
+import { execFile } from 'child_process'
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { promisify } from 'util'
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { activityTracker } from './activityTracker'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+const execFileAsync = promisify(execFile)
+
+// How often the charge is read while a session runs on battery
+const POLL_SECONDS = 60
+
+/**
+ * Battery charge in percent, or null on machines without a battery
+ * (or where it can't be read).
+ */
+export async function readBatteryPercent(): Promise<number | null> {
+  try {
+    if (process.platform === 'darwin') {
+      const { stdout } = await execFileAsync('pmset', ['-g', 'batt'], { timeout: 2000 })
+      const match = stdout.match(/(\d+)%/)
+      return match ? Number(match[1]) : null
+    }
+    if (process.platform === 'win32') {
+      const { stdout } = await execFileAsync(
+        'powershell',
+        ['-NoProfile', '-Command', '(Get-CimInstance Win32_Battery).EstimatedChargeRemaining'],
+        { timeout: 2000, windowsHide: true }
+      )
+      const percent = parseInt(stdout.trim(), 10)
+      return Number.isNaN(percent) ? null : percent
+    }
+    const supplies = '/sys/class/power_supply'
+    const battery = (await fs.readdir(supplies)).find((name) => name.startsWith('BAT'))
+    if (!battery) return null
+    return parseInt(await fs.readFile(join(supplies, battery, 'capacity'), 'utf8'), 10)
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Watches the battery while a session records on battery power: warns once
+ * below the warning level, optionally stops screenshots to save power, and
+ * ends the session at the critical level so it is submitted before the
+ * machine shuts down. Screenshots come back when the charger is plugged in.
+ */
+class BatteryMonitor {
+  private intervalId: NodeJS.Timeout | null = null
+  private warned = false
+  private downgraded = false
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), POLL_SECONDS * 1000)
+
+    powerMonitor.on('on-ac', () => this.restore())
+    sessionManager.on('ended', () => this.restore())
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  private async check(): Promise<void> {
+    if (!sessionManager.getActive() || !powerMonitor.isOnBatteryPower()) return
+
+    const percent = await readBatteryPercent()
+    if (percent === null) return
+
+    const { battery } = await getSettings()
+
+    if (battery.stopPercent > 0 && percent <= battery.stopPercent) {
+      console.warn('[Battery] Critical level', percent, '%, ending session')
+      await sessionManager.end(undefined, 'low-battery')
+      return
+    }
+
+    if (percent > battery.warnPercent || this.warned) return
+    this.warned = true
+
+    if (battery.lowPowerCapture && !this.downgraded) {
+      this.downgraded = true
+      activityTracker.setScreenshotsEnabled(false)
+      console.log('[Battery] Low battery, screenshots paused')
+    }
+
+    emitEvent('battery:low', { percent, captureDowngraded: this.downgraded })
+    await notify(
+      'lowBattery',
+      'Drift · Low battery',
+      this.downgraded ? `${percent}% left. Screenshots are paused until you plug in.` : `${percent}% left.`
+    )
+  }
+
+  private restore(): void {
+    this.warned = false
+    if (this.downgraded) {
+      this.downgraded = false
+      activityTracker.setScreenshotsEnabled(true)
+      console.log('[Battery] Screenshots resumed')
+    }
+  }
+}
+
+// Singleton instance
+export const batteryMonitor = new BatteryMonitor()
@@ -0,0 +1,254 @@
+import { EventEmitter } from 'events'
+import { getStore } from './store'
+import { accounts } from './accounts'
+import { emitEvent } from './events'
+import { sessionHistory } from './sessionHistory'
+import { driftApi } from './api'
+import { connectivity } from './connectivity'
+import { getSettings, updateSettings } from './settings'
+
+// How long a fetched brief list counts as fresh
+const CACHE_TTL_MS = 5 * 60 * 1000
+
+// Number of briefs returned by recent() unless asked otherwise
+const RECENT_LIMIT = 5
+
+export interface Brief {
+  id: string
+  name: string
+  description?: string
+  status?: string
+}
+
+export interface RecentBrief extends Brief {
+  lastRecordedAt: number
+}
+
+/**
+ * Score how well `query` matches `text` as an in-order subsequence.
+ * Returns null when it doesn't match; higher is better. Consecutive
+ * characters and matches at word starts score extra, and a plain
+ * substring match always beats a scattered one.
+ */
+export function fuzzyScore(query: string, text: string): number | null {
+  const q = query.toLowerCase().trim()
+  const t = text.toLowerCase()
+  if (!q) return 0
+
+  const substring = t.indexOf(q)
+  if (substring !== -1) {
+    return 1000 - substring
+  }
+
+  let score = 0
+  let streak = 0
+  let ti = 0
+  for (const ch of q) {
+    if (ch === ' ') continue
+    const found = t.indexOf(ch, ti)
+    if (found === -1) return null
+    streak = found === ti ? streak + 1 : 0
+    score += 1 + streak * 2
+    if (found === 0 || /[\s\-_/.]/.test(t[found - 1])) score += 5
+    ti = found + 1
+  }
+  return score
+}
+
+interface CacheEntry {
+  account: string | null
+  fetchedAt: number
+  briefs: Brief[]
+  role: string
+}
+
+export interface BriefsResult {
+  briefs: Brief[]
+  role: string
+  fetchedAt: number
+  stale: boolean
+  offline?: boolean
+}
+
+/**
+ * The user's briefs, cached on disk per account.
+ * Fresh entries are served directly; stale entries are served immediately
+ * while a background refresh runs, so the picker also works offline.
+ * Emits 'updated' whenever a new list is stored.
+ */
+class BriefCache extends EventEmitter {
+  private refreshing: Promise<CacheEntry> | null = null
+
+  /**
+   * Refresh as soon as the connection returns, so the picker isn't left on an offline list.
+   */
+  start(): void {
+    connectivity.on('online', () => {
+      this.refresh()
+        .then((entry) => emitEvent('briefs:updated', this.toResult(entry, false)))
+        .catch((error) => console.warn('[Briefs] Refresh after reconnect failed:', error.message))
+    })
+  }
+
+  /**
+   * Get briefs, fetching only when there is no usable cache or `force` is set.
+   */
+  async fetch(options: { force?: boolean } = {}): Promise<BriefsResult | { error: string }> {
+    const cached = await this.read()
+
+    // Offline: serve the cache without trying the network
+    if (!connectivity.isOnline()) {
+      if (cached) {
+        return { ...this.toResult(cached, Date.now() - cached.fetchedAt > CACHE_TTL_MS), offline: true }
+      }
+      return { error: 'Offline' }
+    }
+
+    if (cached && !options.force) {
+      const stale = Date.now() - cached.fetchedAt > CACHE_TTL_MS
+      if (stale) {
+        this.refresh()
+          .then((entry) => emitEvent('briefs:updated', this.toResult(entry, false)))
+          .catch((error) => console.warn('[Briefs] Background refresh failed:', error.message))
+      }
+      return this.toResult(cached, stale)
+    }
+
+    try {
+      return this.toResult(await this.refresh(), false)
+    } catch (error: any) {
+      if (cached) {
+        return { ...this.toResult(cached, true), offline: true }
+      }
+      if (error.response?.status === 401 || error.response?.status === 403) {
+        return { error: '401 - Not authenticated or token expired' }
+      }
+      return { error: error.message || String(error) }
+    }
+  }
+
+  /**
+   * Fuzzy-search the cached briefs by name and description, best match first.
+   */
+  async search(query: string): Promise<Brief[]> {
+    const briefs = await this.cachedBriefs()
+    return briefs
+      .map((brief) => {
+        const name = fuzzyScore(query, brief.name)
+        const description = brief.description ? fuzzyScore(query, brief.description) : null
+        // Name matches rank above description matches
+        const score = name !== null ? name + 2000 : description
+        return { brief, score }
+      })
+      .filter((m): m is { brief: Brief; score: number } => m.score !== null)
+      .sort((a, b) => b.score - a.score)
+      .map((m) => m.brief)
+  }
+
+  /**
+   * Briefs most recently recorded against by the active account, newest first.
+   */
+  async recent(limit: number = RECENT_LIMIT): Promise<RecentBrief[]> {
+    const account = (await accounts.getActive())?.name ?? null
+    const briefs = await this.cachedBriefs()
+    const byId = new Map(briefs.map((b) => [b.id, b]))
+
+    const lastRecorded = new Map<string, { name: string; at: number }>()
+    for (const r of await sessionHistory.list()) {
+      if (r.account !== account) continue
+      const prev = lastRecorded.get(r.briefId)
+      if (!prev || r.startedAt > prev.at) {
+        lastRecorded.set(r.briefId, { name: r.briefName, at: r.startedAt })
+      }
+    }
+
+    return [...lastRecorded.entries()]
+      // Skip briefs that no longer exist, unless we have no list to check against
+      .filter(([id]) => briefs.length === 0 || byId.has(id))
+      .sort((a, b) => b[1].at - a[1].at)
+      .slice(0, limit)
+      .map(([id, { name, at }]) => ({ ...(byId.get(id) || { id, name }), lastRecordedAt: at }))
+  }
+
+  /**
+   * Pinned briefs that still exist, in the order they were pinned.
+   */
+  async pinned(): Promise<Brief[]> {
+    const { pinnedBriefs } = await getSettings()
+    const byId = new Map((await this.cachedBriefs()).map((b) => [b.id, b]))
+    return pinnedBriefs.map((id) => byId.get(id)).filter((b): b is Brief => !!b)
+  }
+
+  async setPinned(briefId: string, pinned: boolean): Promise<string[]> {
+    const { pinnedBriefs } = await getSettings()
+    const next = pinnedBriefs.filter((id) => id !== briefId)
+    if (pinned) next.push(briefId)
+    return (await updateSettings({ pinnedBriefs: next })).pinnedBriefs
+  }
+
+  /**
+   * Store a brief list fetched elsewhere (e.g. by drift:sync).
+   */
+  async store(data: { briefs?: Brief[]; role?: string }): Promise<void> {
+    await this.write({
+      account: (await accounts.getActive())?.name ?? null,
+      fetchedAt: Date.now(),
+      briefs: data.briefs || [],
+      role: data.role || 'dev'
+    })
+  }
+
+  async clear(): Promise<void> {
+    const s = await getStore()
+    s.delete('briefCache')
+  }
+
+  private async cachedBriefs(): Promise<Brief[]> {
+    const result = await this.fetch()
+    return 'error' in result ? [] : result.briefs
+  }
+
+  private refresh(): Promise<CacheEntry> {
+    // Collapse concurrent refreshes into one request
+    if (!this.refreshing) {
+      this.refreshing = this.fetchRemote().finally(() => {
+        this.refreshing = null
+      })
+    }
+    return this.refreshing
+  }
+
+  private async fetchRemote(): Promise<CacheEntry> {
+    const data = await driftApi.sync()
+
+    const entry: CacheEntry = {
+      account: (await accounts.getActive())?.name ?? null,
+      fetchedAt: Date.now(),
+      briefs: data.briefs || [],
+      role: data.role || 'dev'
+    }
+    await this.write(entry)
+    return entry
+  }
+
+  private async read(): Promise<CacheEntry | null> {
+    const s = await getStore()
+    const entry: CacheEntry | undefined = s.get('briefCache')
+    const account = (await accounts.getActive())?.name ?? null
+    // Never serve another account's briefs
+    return entry && entry.account === account ? entry : null
+  }
+
+  private async write(entry: CacheEntry): Promise<void> {
+    const s = await getStore()
+    s.set('briefCache', entry)
+    this.emit('updated')
+  }
+
+  private toResult(entry: CacheEntry, stale: boolean): BriefsResult {
+    return { briefs: entry.briefs, role: entry.role, fetchedAt: entry.fetchedAt, stale }
+  }
+}
+
+// Singleton instance
+export const briefCache = new BriefCache()
@@ -0,0 +1,88 @@
+import { execFile } from 'child_process'
+import { promisify } from 'util'
+import { getSettings } from './settings'
+
+const execFileAsync = promisify(execFile)
+
+export interface BrowserTab {
+  url: string
+  title: string
+}
+
+// AppleScript that prints the front tab's URL and title on two lines, by macOS app name
+const CHROMIUM_SCRIPT = (app: string) => `tell application "${app}"
+  if (count of windows) is 0 then return ""
+  if mode of front window is "incognito" then return ""
+  set t to active tab of front window
+  return (URL of t) & linefeed & (title of t)
+end tell`
+
+const MAC_SCRIPTS: Record<string, string> = {
+  'Google Chrome': CHROMIUM_SCRIPT('Google Chrome'),
+  'Brave Browser': CHROMIUM_SCRIPT('Brave Browser'),
+  'Microsoft Edge': CHROMIUM_SCRIPT('Microsoft Edge'),
+  'Chromium': CHROMIUM_SCRIPT('Chromium'),
+  'Vivaldi': CHROMIUM_SCRIPT('Vivaldi'),
+  'Arc': `tell application "Arc"
+  if (count of windows) is 0 then return ""
+  set t to active tab of front window
+  return (URL of t) & linefeed & (title of t)
+end tell`,
+  'Safari': `tell application "Safari"
+  if (count of documents) is 0 then return ""
+  return (URL of front document) & linefeed & (name of front document)
+end tell`
+}
+
+// Foreground apps the browser extension's tab can belong to
+const BROWSER_APPS = /chrome|chromium|firefox|msedge|microsoft edge|brave|vivaldi|opera|arc|safari/i
+
+// Only pages are worth recording, not new tabs or browser internals
+function isWebUrl(url: string): boolean {
+  return /^https?:\/\//i.test(url)
+}
+
+/**
+ * Resolves the URL of the frontmost browser tab for the session timeline.
+ * On macOS the browser is asked via AppleScript (needs the Automation
+ * permission; Firefox has no scripting support). Elsewhere, and for
+ * Firefox, the tab last reported by the companion browser extension is
+ * used. Tabs in private windows are never read.
+ */
+class BrowserTabs {
+  private extensionTab: BrowserTab | null = null
+
+  /**
+   * Tab the browser extension says is active; null when it has none or disconnected.
+   */
+  reportFromExtension(tab: BrowserTab | null): void {
+    this.extensionTab = tab && isWebUrl(tab.url) ? { url: tab.url, title: tab.title || '' } : null
+  }
+
+  /**
+   * The front tab of `app` if it is a supported browser and the setting is on, otherwise null.
+   */
+  async activeTab(app: string): Promise<BrowserTab | null> {
+    const { timeline } = await getSettings()
+    if (!timeline.enabled || !timeline.browserUrls) return null
+
+    if (process.platform === 'darwin' && MAC_SCRIPTS[app]) {
+      return this.fromAppleScript(MAC_SCRIPTS[app])
+    }
+    return BROWSER_APPS.test(app) ? this.extensionTab : null
+  }
+
+  private async fromAppleScript(script: string): Promise<BrowserTab | null> {
+    try {
+      const { stdout } = await execFileAsync('osascript', ['-e', script], { timeout: 2000 })
+      const [url = '', ...title] = stdout.trim().split('\n')
+      return isWebUrl(url) ? { url: url.trim(), title: title.join(' ').trim() } : null
+    } catch {
+      // Browser quit, or Automation permission denied
+      return null
+    }
+  }
+}
+
+// Singleton instance
+export const browserTabs = new BrowserTabs()
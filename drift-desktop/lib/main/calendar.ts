@@ -0,0 +1,173 @@
+import { externalHttp } from './api'
+import { getSettings, onSettingsChanged } from './settings'
+import { briefCache } from './briefs'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+
+// How long fetched feeds are reused
+const FEED_TTL_MS = 15 * 60 * 1000
+
+// How often to look for meetings that just started
+const CHECK_INTERVAL_MS = 60 * 1000
+
+export interface CalendarEvent {
+  uid: string
+  title: string
+  start: number
+  end: number
+  allDay: boolean
+  suggestedBriefId: string | null
+}
+
+/**
+ * Parse an iCalendar date. Floating and TZID times are taken as local time.
+ */
+function parseIcsDate(value: string, params: string): { time: number; allDay: boolean } | null {
+  if (params.includes('VALUE=DATE') || /^\d{8}$/.test(value)) {
+    const m = /^(\d{4})(\d{2})(\d{2})/.exec(value)
+    if (!m) return null
+    return { time: new Date(+m[1], +m[2] - 1, +m[3]).getTime(), allDay: true }
+  }
+  const m = /^(\d{4})(\d{2})(\d{2})T(\d{2})(\d{2})(\d{2})(Z?)$/.exec(value)
+  if (!m) return null
+  const [, y, mo, d, h, mi, se, utc] = m
+  const time = utc
+    ? Date.UTC(+y, +mo - 1, +d, +h, +mi, +se)
+    : new Date(+y, +mo - 1, +d, +h, +mi, +se).getTime()
+  return { time, allDay: false }
+}
+
+/**
+ * Extract single events from an iCalendar feed. Recurring events only
+ * contribute their first occurrence.
+ */
+export function parseIcs(text: string): Array<Omit<CalendarEvent, 'suggestedBriefId'>> {
+  // Unfold continuation lines
+  const lines = text.replace(/\r?\n[ \t]/g, '').split(/\r?\n/)
+  const events: Array<Omit<CalendarEvent, 'suggestedBriefId'>> = []
+  let current: Record<string, { value: string; params: string }> | null = null
+
+  for (const line of lines) {
+    if (line === 'BEGIN:VEVENT') {
+      current = {}
+    } else if (line === 'END:VEVENT' && current) {
+      const start = current.DTSTART && parseIcsDate(current.DTSTART.value, current.DTSTART.params)
+      const end = current.DTEND && parseIcsDate(current.DTEND.value, current.DTEND.params)
+      if (start && current.STATUS?.value !== 'CANCELLED') {
+        events.push({
+          uid: current.UID?.value || `${start.time}-${current.SUMMARY?.value}`,
+          title: (current.SUMMARY?.value || 'Untitled').replace(/\\([,;\\])/g, '$1').replace(/\\n/gi, ' '),
+          start: start.time,
+          end: end ? end.time : start.time + (start.allDay ? 24 * 60 * 60 * 1000 : 0),
+          allDay: start.allDay
+        })
+      }
+      current = null
+    } else if (current) {
+      const colon = line.indexOf(':')
+      if (colon === -1) continue
+      const [name, ...params] = line.slice(0, colon).split(';')
+      current[name.toUpperCase()] = { value: line.slice(colon + 1), params: params.join(';').toUpperCase() }
+    }
+  }
+  return events
+}
+
+/**
+ * Meetings from the user's calendar feeds, each with the brief it most likely belongs to.
+ * Explicit title mappings win; otherwise a brief whose name appears in the title is suggested.
+ */
+class CalendarService {
+  private cache: { fetchedAt: number; events: Array<Omit<CalendarEvent, 'suggestedBriefId'>> } | null = null
+  private intervalId: NodeJS.Timeout | null = null
+  private announced = new Set<string>()
+
+  start(): void {
+    if (this.intervalId) return
+    onSettingsChanged((next, prev) => {
+      if (JSON.stringify(next.calendar.icsUrls) !== JSON.stringify(prev.calendar.icsUrls)) {
+        this.cache = null
+      }
+    })
+    this.intervalId = setInterval(() => this.checkMeetingStart(), CHECK_INTERVAL_MS)
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  /**
+   * Events that haven't ended yet and start within the next `hours`.
+   */
+  async upcomingEvents(hours: number = 12): Promise<CalendarEvent[]> {
+    const now = Date.now()
+    const until = now + hours * 60 * 60 * 1000
+    const events = (await this.events()).filter((e) => e.end > now && e.start < until && !e.allDay)
+    events.sort((a, b) => a.start - b.start)
+    return Promise.all(events.map(async (e) => ({ ...e, suggestedBriefId: await this.suggestBrief(e.title) })))
+  }
+
+  /**
+   * The meeting happening right now, if any.
+   */
+  async currentEvent(): Promise<CalendarEvent | null> {
+    const now = Date.now()
+    return (await this.upcomingEvents(0)).find((e) => e.start <= now) || null
+  }
+
+  async suggestBrief(title: string): Promise<string | null> {
+    const { calendar } = await getSettings()
+    const lower = title.toLowerCase()
+    const mapped = calendar.briefMappings.find((m) => m.match && lower.includes(m.match.toLowerCase()))
+    if (mapped) return mapped.briefId
+
+    const result = await briefCache.fetch()
+    if ('error' in result) return null
+    const named = result.briefs
+      .filter((b) => b.name && lower.includes(b.name.toLowerCase()))
+      .sort((a, b) => b.name.length - a.name.length)[0]
+    return named?.id ?? null
+  }
+
+  private async events(): Promise<Array<Omit<CalendarEvent, 'suggestedBriefId'>>> {
+    if (this.cache && Date.now() - this.cache.fetchedAt < FEED_TTL_MS) {
+      return this.cache.events
+    }
+
+    const { calendar } = await getSettings()
+    const events: Array<Omit<CalendarEvent, 'suggestedBriefId'>> = []
+    for (const url of calendar.icsUrls) {
+      try {
+        // webcal:// is the same feed over https
+        const response = await externalHttp.get(url.replace(/^webcal:/i, 'https:'), { responseType: 'text', timeout: 10000 })
+        events.push(...parseIcs(String(response.data)))
+      } catch (error: any) {
+        console.warn('[Calendar] Feed failed:', error.message)
+      }
+    }
+    this.cache = { fetchedAt: Date.now(), events }
+    return events
+  }
+
+  /**
+   * Tell the UI when a meeting starts so the session picker can be pre-filled.
+   */
+  private async checkMeetingStart(): Promise<void> {
+    const { calendar } = await getSettings()
+    if (calendar.icsUrls.length === 0 || sessionManager.getActive()) return
+
+    const event = await this.currentEvent()
+    if (!event || this.announced.has(event.uid)) return
+    // Only announce meetings that started within the last check interval
+    if (Date.now() - event.start > CHECK_INTERVAL_MS * 2) return
+
+    this.announced.add(event.uid)
+    emitEvent('calendar:meeting-started', event)
+  }
+}
+
+// Singleton instance
+export const calendarService = new CalendarService()
@@ -0,0 +1,52 @@
+import { app, safeStorage } from 'electron'
+
+// `reason` says why a feature is unavailable, for a tooltip or log line
+export interface Capability {
+  supported: boolean
+  reason?: string
+}
+
+export interface Capabilities {
+  platform: NodeJS.Platform
+  appVersion: string
+  screenCapture: Capability
+  systemAudioCapture: Capability
+  hardwareEncoding: Capability
+  keychain: Capability
+  deepLinks: Capability
+  dndControl: Capability
+  trayTitle: Capability
+}
+
+function supported(): Capability {
+  return { supported: true }
+}
+
+function unsupported(reason: string): Capability {
+  return { supported: false, reason }
+}
+
+/**
+ * What this build and platform can do, so the UI can hide features instead
+ * of calling handlers that fail at runtime. Permission state is reported
+ * separately by `permissions:check`.
+ */
+export function getCapabilities(): Capabilities {
+  const platform = process.platform
+
+  return {
+    platform,
+    appVersion: app.getVersion(),
+    screenCapture: supported(),
+    systemAudioCapture: unsupported('This build has no system audio loopback'),
+    hardwareEncoding: app.isHardwareAccelerationEnabled()
+      ? supported()
+      : unsupported('Hardware acceleration is disabled'),
+    keychain: safeStorage.isEncryptionAvailable()
+      ? supported()
+      : unsupported('No OS keychain available; data is encrypted with a local key'),
+    deepLinks: unsupported('Sign-in uses a local callback server instead of a URL scheme'),
+    dndControl: unsupported('Do Not Disturb cannot be controlled by the app'),
+    trayTitle: platform === 'darwin' ? supported() : unsupported('Only the macOS menu bar shows tray text')
+  }
+}
@@ -0,0 +1,254 @@
+import { desktopCapturer, Display, NativeImage, nativeImage, Rectangle, screen } from 'electron'
+import { getSettings, updateSettings } from './settings'
+
+// A display or window that can be captured
+export interface CaptureTarget {
+  id: string
+  kind: 'screen' | 'window'
+  name: string
+  displayId?: string
+  thumbnail: string // data URL
+  appIcon?: string // data URL, windows only
+}
+
+// Smallest region worth capturing, in DIP
+const MIN_REGION_SIZE = 50
+
+// How often windows are sampled (and screenshots taken) and how frames are scaled
+export interface CaptureQuality {
+  sampleSeconds: number
+  scale: number // of the display size, 0.1-1
+  quality: number // JPEG quality, 10-100
+}
+
+// desktopCapturer thumbnails take too long to produce more than about one per second
+export const SAMPLE_INTERVALS = [1, 2, 3, 5, 10]
+
+/**
+ * Check capture settings against what the capturer supports.
+ * Returns an error message, or null when they are usable.
+ */
+export function validateCaptureQuality(quality: CaptureQuality): string | null {
+  if (!SAMPLE_INTERVALS.includes(quality.sampleSeconds)) {
+    return `Sample interval must be one of ${SAMPLE_INTERVALS.join(', ')} seconds`
+  }
+  if (!(quality.scale >= 0.1 && quality.scale <= 1)) {
+    return 'Scale must be between 0.1 and 1'
+  }
+  if (!Number.isInteger(quality.quality) || quality.quality < 10 || quality.quality > 100) {
+    return 'JPEG quality must be a whole number between 10 and 100'
+  }
+  return null
+}
+
+/**
+ * Check that a region lies entirely on one display.
+ * Returns an error message, or null when the region is usable.
+ */
+export function validateRegion(region: Rectangle): string | null {
+  const { x, y, width, height } = region
+  if (![x, y, width, height].every(Number.isFinite)) {
+    return 'Region must have numeric x, y, width and height'
+  }
+  if (width < MIN_REGION_SIZE || height < MIN_REGION_SIZE) {
+    return `Region must be at least ${MIN_REGION_SIZE}x${MIN_REGION_SIZE}`
+  }
+
+  const { bounds } = screen.getDisplayMatching(region)
+  const inside =
+    x >= bounds.x && y >= bounds.y && x + width <= bounds.x + bounds.width && y + height <= bounds.y + bounds.height
+  return inside ? null : 'Region must lie within a single display'
+}
+
+/**
+ * Black out redaction zones (global DIP) in a capture of `display`.
+ */
+function maskZones(image: NativeImage, display: Display, zones: Rectangle[]): NativeImage {
+  if (zones.length === 0) return image
+
+  const { width, height } = image.getSize()
+  const sx = width / display.bounds.width
+  const sy = height / display.bounds.height
+  const bitmap = image.toBitmap() // BGRA
+
+  for (const zone of zones) {
+    const x0 = Math.max(0, Math.floor((zone.x - display.bounds.x) * sx))
+    const y0 = Math.max(0, Math.floor((zone.y - display.bounds.y) * sy))
+    const x1 = Math.min(width, Math.ceil((zone.x + zone.width - display.bounds.x) * sx))
+    const y1 = Math.min(height, Math.ceil((zone.y + zone.height - display.bounds.y) * sy))
+    for (let y = y0; y < y1; y++) {
+      for (let i = (y * width + x0) * 4; i < (y * width + x1) * 4; i += 4) {
+        bitmap[i] = bitmap[i + 1] = bitmap[i + 2] = 0
+        bitmap[i + 3] = 255
+      }
+    }
+  }
+  return nativeImage.createFromBitmap(bitmap, { width, height })
+}
+
+/**
+ * Replace the redaction zones of one display. Zones must lie on that display;
+ * an empty list removes them.
+ */
+export async function setRedactionZones(displayId: string, zones: Rectangle[]): Promise<{ error: string } | { ok: true }> {
+  const display = screen.getAllDisplays().find((d) => String(d.id) === displayId)
+  if (!display) {
+    return { error: `Unknown display: ${displayId}` }
+  }
+
+  const { x: bx, y: by, width: bw, height: bh } = display.bounds
+  for (const { x, y, width, height } of zones) {
+    if (![x, y, width, height].every(Number.isFinite) || width <= 0 || height <= 0) {
+      return { error: 'Zones must have numeric x, y and a positive width and height' }
+    }
+    if (x < bx || y < by || x + width > bx + bw || y + height > by + bh) {
+      return { error: 'Zones must lie within the display' }
+    }
+  }
+
+  const { redaction } = await getSettings()
+  const next = { ...redaction.zones }
+  if (zones.length > 0) {
+    next[displayId] = zones.map(({ x, y, width, height }) => ({ x, y, width, height }))
+  } else {
+    delete next[displayId]
+  }
+  await updateSettings({ redaction: { zones: next } })
+  return { ok: true }
+}
+
+async function zonesFor(display: Display): Promise<Rectangle[]> {
+  const { redaction } = await getSettings()
+  return redaction.zones[String(display.id)] ?? []
+}
+
+/**
+ * Screen capture for the active session. Captures the primary display
+ * unless a single display or window has been selected as the target.
+ * A region, when set, takes precedence over the target.
+ * Redaction zones are blacked out in every display capture; window captures
+ * are left as they are, since the window's position on screen isn't known.
+ */
+class ScreenCapture {
+  private targetId: string | null = null
+  private region: Rectangle | null = null
+
+  getTarget(): string | null {
+    return this.targetId
+  }
+
+  setTarget(id: string | null): void {
+    this.targetId = id
+  }
+
+  getRegion(): Rectangle | null {
+    return this.region ? { ...this.region } : null
+  }
+
+  /**
+   * Restrict capture to a screen rectangle in global DIP coordinates.
+   */
+  setRegion(region: Rectangle | null): { error: string } | { ok: true } {
+    if (region) {
+      const error = validateRegion(region)
+      if (error) return { error }
+    }
+    this.region = region ? { ...region } : null
+    return { ok: true }
+  }
+
+  /**
+   * Screen area being captured: the region, the target display, or the primary display.
+   * Window targets resolve to the primary display since their bounds aren't exposed.
+   */
+  async getCaptureBounds(): Promise<Rectangle> {
+    if (this.region) return { ...this.region }
+
+    const primary = screen.getPrimaryDisplay()
+    if (!this.targetId?.startsWith('screen:')) return primary.bounds
+
+    const sources = await desktopCapturer.getSources({ types: ['screen'], thumbnailSize: { width: 0, height: 0 } })
+    const displayId = sources.find((s) => s.id === this.targetId)?.display_id
+    const display = screen.getAllDisplays().find((d) => String(d.id) === displayId)
+    return (display ?? primary).bounds
+  }
+
+  /**
+   * Open windows and displays, with thumbnails for a picker.
+   */
+  async listTargets(): Promise<CaptureTarget[]> {
+    const sources = await desktopCapturer.getSources({
+      types: ['screen', 'window'],
+      thumbnailSize: { width: 320, height: 200 },
+      fetchWindowIcons: true
+    })
+
+    return sources.map((source) => ({
+      id: source.id,
+      kind: source.id.startsWith('window:') ? 'window' : 'screen',
+      name: source.name,
+      displayId: source.display_id || undefined,
+      thumbnail: source.thumbnail.toDataURL(),
+      appIcon: source.appIcon && !source.appIcon.isEmpty() ? source.appIcon.toDataURL() : undefined
+    }))
+  }
+
+  /**
+   * Capture the target as JPEG, scaled relative to the primary display.
+   * Falls back to the primary display when the target window has closed.
+   */
+  async captureFrame(scale: number, quality: number): Promise<Buffer | null> {
+    if (this.region) {
+      return this.captureRegion(this.region, scale, quality)
+    }
+
+    const primaryDisplay = screen.getPrimaryDisplay()
+    const { width, height } = primaryDisplay.size
+    const isWindow = this.targetId?.startsWith('window:') ?? false
+
+    const sources = await desktopCapturer.getSources({
+      types: isWindow ? ['window', 'screen'] : ['screen'],
+      thumbnailSize: { width: Math.floor(width * scale), height: Math.floor(height * scale) }
+    })
+
+    const source =
+      sources.find((s) => s.id === this.targetId) ||
+      sources.find((s) => s.display_id === String(primaryDisplay.id)) ||
+      sources.find((s) => s.id.startsWith('screen:'))
+
+    if (!source || source.thumbnail.isEmpty()) return null
+    if (source.id.startsWith('window:')) return source.thumbnail.toJPEG(quality)
+
+    const display = screen.getAllDisplays().find((d) => String(d.id) === source.display_id) ?? primaryDisplay
+    return maskZones(source.thumbnail, display, await zonesFor(display)).toJPEG(quality)
+  }
+
+  private async captureRegion(region: Rectangle, scale: number, quality: number): Promise<Buffer | null> {
+    // Displays may have changed since the region was set
+    if (validateRegion(region)) return null
+
+    const display = screen.getDisplayMatching(region)
+    const sources = await desktopCapturer.getSources({
+      types: ['screen'],
+      thumbnailSize: { width: Math.floor(display.size.width * scale), height: Math.floor(display.size.height * scale) }
+    })
+    const source = sources.find((s) => s.display_id === String(display.id))
+    if (!source || source.thumbnail.isEmpty()) return null
+
+    // The thumbnail may not match the requested size exactly
+    const thumb = source.thumbnail.getSize()
+    const sx = thumb.width / display.bounds.width
+    const sy = thumb.height / display.bounds.height
+    return maskZones(source.thumbnail, display, await zonesFor(display))
+      .crop({
+        x: Math.round((region.x - display.bounds.x) * sx),
+        y: Math.round((region.y - display.bounds.y) * sy),
+        width: Math.round(region.width * sx),
+        height: Math.round(region.height * sy)
+      })
+      .toJPEG(quality)
+  }
+}
+
+// Singleton instance
+export const screenCapture = new ScreenCapture()
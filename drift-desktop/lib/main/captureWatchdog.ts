@@ -0,0 +1,60 @@
+import { activityTracker } from './activityTracker'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+
+// How often the watchdog looks at the sampling loop
+const CHECK_SECONDS = 10
+
+// Samples run every 3s; this long without one counts as a stall
+const STALL_SECONDS = 30
+
+/**
+ * Supervises activity sampling while a session records. If no sample has
+ * completed for a while, the loop is restarted and `capture-degraded` is
+ * emitted; once samples arrive again the stalled stretch is recorded as a
+ * capture gap in the session instead of silently missing from it.
+ */
+class CaptureWatchdog {
+  private intervalId: NodeJS.Timeout | null = null
+  private stalledSince: number | null = null
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), CHECK_SECONDS * 1000)
+    sessionManager.on('ended', () => (this.stalledSince = null))
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  private check(): void {
+    const status = activityTracker.getStatus()
+    if (!sessionManager.getActive() || !status.isTracking || status.isPaused) return
+
+    const lastSampleAt = activityTracker.getLastSampleAt()
+
+    if (this.stalledSince !== null && lastSampleAt > this.stalledSince) {
+      sessionManager.markCaptureGap({ start: this.stalledSince, end: lastSampleAt, component: 'activity' })
+      emitEvent('capture-recovered', { component: 'activity', gapSeconds: Math.round((lastSampleAt - this.stalledSince) / 1000) })
+      console.log('[Watchdog] Activity sampling recovered')
+      this.stalledSince = null
+      return
+    }
+
+    if (Date.now() - lastSampleAt < STALL_SECONDS * 1000) return
+
+    if (this.stalledSince === null) {
+      this.stalledSince = lastSampleAt
+      console.warn('[Watchdog] Activity sampling stalled, restarting')
+      emitEvent('capture-degraded', { component: 'activity', stalledSince: lastSampleAt })
+    }
+    activityTracker.restartSampling()
+  }
+}
+
+// Singleton instance
+export const captureWatchdog = new CaptureWatchdog()
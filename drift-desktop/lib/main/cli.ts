@@ -0,0 +1,218 @@
+import { app } from 'electron'
+import { randomBytes, timingSafeEqual } from 'crypto'
+import { createConnection, createServer, Server, Socket } from 'net'
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { userInfo } from 'os'
+import { sessionManager } from './session'
+import { briefCache } from './briefs'
+import { getAppSnapshot } from './snapshot'
+import { quickCapture } from './quickCapture'
+import { browserTabs, BrowserTab } from './browserTabs'
+
+export type CliCommand =
+  | { command: 'record-start'; briefId: string; role?: string }
+  | { command: 'record-stop'; summary?: string }
+  | { command: 'status' }
+  | { command: 'marker'; text: string }
+  | { command: 'note'; text: string }
+  | { command: 'browser-tab'; tab: BrowserTab | null } // from the browser extension; null when it has none
+
+const USAGE = 'Usage: drift record start --brief <id> [--role <role>] | drift record stop [--summary <text>] | drift note <text> | drift status'
+
+// Give up on a running instance that doesn't answer
+const CLIENT_TIMEOUT_MS = 30 * 1000
+
+function socketPath(): string {
+  return process.platform === 'win32'
+    ? `\\\\.\\pipe\\drift-cli-${userInfo().username}`
+    : join(app.getPath('userData'), 'cli.sock')
+}
+
+// Secret every request must carry. Node can't put an ACL on the Windows named
+// pipe, so any local user could connect to it; the key is in userData, which
+// only this user can read. Rewritten on every start.
+function keyPath(): string {
+  return join(app.getPath('userData'), 'cli.key')
+}
+
+function keyMatches(expected: string, given: unknown): boolean {
+  if (typeof given !== 'string' || given.length !== expected.length) return false
+  return timingSafeEqual(Buffer.from(given), Buffer.from(expected))
+}
+
+function option(args: string[], name: string): string | undefined {
+  const index = args.indexOf(name)
+  return index >= 0 ? args[index + 1] : undefined
+}
+
+/**
+ * Recognize a CLI invocation in the process arguments. Returns null for a
+ * normal launch, or `{ error }` when the arguments look like a command but are invalid.
+ */
+export function parseCliCommand(argv: string[]): CliCommand | { error: string } | null {
+  // Packaged: [exe, ...args]; development: [electron, main.js, ...args]
+  const args = argv.slice(app.isPackaged ? 1 : 2).filter((a) => !a.startsWith('--inspect') && a !== '--portable')
+  const [first, second] = args
+
+  if (first === 'status') return { command: 'status' }
+  if (first === 'note') {
+    const text = args.slice(1).join(' ').trim()
+    return text ? { command: 'note', text } : { error: USAGE }
+  }
+  if (first !== 'record') return null
+
+  if (second === 'start') {
+    const briefId = option(args, '--brief')
+    if (!briefId) return { error: `Missing --brief. ${USAGE}` }
+    return { command: 'record-start', briefId, role: option(args, '--role') }
+  }
+  if (second === 'stop') {
+    return { command: 'record-stop', summary: option(args, '--summary') }
+  }
+  return { error: USAGE }
+}
+
+/**
+ * Run a scripting command against this instance. Shared by the CLI and the local control API.
+ */
+export async function executeCommand(command: CliCommand): Promise<any> {
+  switch (command.command) {
+    case 'record-start': {
+      // Default to the role the backend reported for this user
+      const briefs = await briefCache.fetch()
+      const role = command.role || ('role' in briefs ? briefs.role : 'dev')
+      return sessionManager.start(command.briefId, role)
+    }
+    case 'record-stop':
+      return sessionManager.end(command.summary)
+    case 'status': {
+      const snapshot = await getAppSnapshot()
+      return {
+        running: true,
+        session: snapshot.session,
+        pendingUploads: snapshot.pendingUploads.length,
+        signedIn: snapshot.auth.signedIn,
+        liveSync: snapshot.liveSync
+      }
+    }
+    case 'marker':
+      return sessionManager.appendNote(command.text)
+    case 'note':
+      return quickCapture.capture(command.text)
+    case 'browser-tab':
+      browserTabs.reportFromExtension(command.tab)
+      return { ok: true }
+  }
+}
+
+/**
+ * Print one JSON line and exit: 0 on success, 1 when the result is an error.
+ */
+function finish(result: any): void {
+  process.stdout.write(JSON.stringify(result) + '\n')
+  app.exit(result && typeof result === 'object' && 'error' in result ? 1 : 0)
+}
+
+/**
+ * Send a command to the running instance over the CLI socket and resolve with its result.
+ */
+export async function sendToRunningInstance(command: CliCommand): Promise<any> {
+  const key = await fs.readFile(keyPath(), 'utf8').catch(() => null)
+  return new Promise((resolve) => {
+    const socket = createConnection(socketPath())
+    let response = ''
+    socket.setTimeout(CLIENT_TIMEOUT_MS, () => {
+      socket.destroy()
+      resolve({ error: 'Drift did not respond' })
+    })
+    socket.on('connect', () => socket.write(JSON.stringify({ ...command, key }) + '\n'))
+    socket.on('data', (chunk) => {
+      response += chunk.toString()
+    })
+    socket.on('end', () => {
+      try {
+        resolve(JSON.parse(response))
+      } catch {
+        resolve({ error: 'Invalid response from Drift' })
+      }
+    })
+    socket.on('error', () => {
+      // No running instance
+      resolve(command.command === 'status' ? { running: false } : { error: 'Drift is not running' })
+    })
+  })
+}
+
+/**
+ * Forward a CLI command to the running instance and exit with its JSON result.
+ * Runs instead of the app; no window is ever created.
+ */
+export function runCliCommand(command: CliCommand | { error: string }): void {
+  if ('error' in command) {
+    finish(command)
+    return
+  }
+  sendToRunningInstance(command).then(finish)
+}
+
+/**
+ * Listens for CLI invocations on a per-user socket (a named pipe on Windows)
+ * and runs them against this instance. One command per connection: the first
+ * line is run once and answered, and the connection is closed.
+ */
+class CliServer {
+  private server: Server | null = null
+  private key = ''
+
+  async start(): Promise<void> {
+    if (this.server) return
+
+    this.key = randomBytes(32).toString('hex')
+    await fs.writeFile(keyPath(), this.key, { mode: 0o600 })
+
+    const path = socketPath()
+    if (process.platform !== 'win32') {
+      // Left behind by an instance that didn't shut down cleanly
+      await fs.unlink(path).catch(() => {})
+    }
+
+    this.server = createServer((socket) => this.handle(socket))
+    this.server.on('error', (error) => console.warn('[CLI] Server error:', error.message))
+    this.server.listen(path, () => {
+      if (process.platform !== 'win32') fs.chmod(path, 0o600).catch(() => {})
+      console.log('[CLI] Listening on', path)
+    })
+    app.on('will-quit', () => this.server?.close())
+  }
+
+  private handle(socket: Socket): void {
+    let buffer = ''
+    const onData = async (chunk: Buffer) => {
+      buffer += chunk.toString()
+      const newline = buffer.indexOf('\n')
+      if (newline === -1) return
+      // Anything after the first line is ignored
+      socket.off('data', onData)
+
+      let result: any
+      try {
+        const { key, ...command } = JSON.parse(buffer.slice(0, newline)) as CliCommand & { key?: unknown }
+        if (!keyMatches(this.key, key)) {
+          console.warn('[CLI] Refused a command without the CLI key')
+          return socket.end(JSON.stringify({ error: 'Not allowed' }) + '\n')
+        }
+        console.log('[CLI] Command:', command.command)
+        result = (await executeCommand(command as CliCommand)) ?? { error: 'Unknown command' }
+      } catch (error: any) {
+        result = { error: error.message || String(error) }
+      }
+      socket.end(JSON.stringify(result) + '\n')
+    }
+    socket.on('data', onData)
+    socket.on('error', () => {})
+  }
+}
+
+// Singleton instance
+export const cliServer = new CliServer()
@@ -0,0 +1,87 @@
+import { clipboard } from 'electron'
+import { activityTracker } from './activityTracker'
+import { Settings } from './settings'
+
+// A text snippet copied during a session
+export interface Clip {
+  text: string
+  app: string | null // app in the foreground when it was copied
+  timestamp: number
+  truncated: boolean
+}
+
+// How often to check the clipboard
+const POLL_MS = 1000
+
+/**
+ * Collects text copied while a session runs. Clipboard contents that were
+ * already there when the session started are ignored, as is anything
+ * copied from a denied app (e.g. a password manager).
+ */
+class ClipboardWatcher {
+  private intervalId: NodeJS.Timeout | null = null
+  private clips: Clip[] = []
+  private lastText = ''
+  private paused = false
+  private config: Settings['clipboard'] | null = null
+
+  start(config: Settings['clipboard']): void {
+    if (this.intervalId) return
+    this.config = config
+    this.clips = []
+    this.paused = false
+    this.lastText = clipboard.readText()
+    this.intervalId = setInterval(() => this.check(), POLL_MS)
+  }
+
+  pause(): void {
+    this.paused = true
+  }
+
+  resume(): void {
+    this.paused = false
+    // Whatever was copied while away doesn't belong to the session
+    this.lastText = clipboard.readText()
+  }
+
+  /**
+   * Stop watching and return the clips collected.
+   */
+  stop(): Clip[] {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+    const clips = this.clips
+    this.clips = []
+    this.config = null
+    return clips
+  }
+
+  getClips(): Clip[] {
+    return this.clips.map((c) => ({ ...c }))
+  }
+
+  private check(): void {
+    if (this.paused || !this.config) return
+
+    const text = clipboard.readText()
+    if (!text || text === this.lastText) return
+    this.lastText = text
+
+    const app = activityTracker.getCurrentApp()
+    const denied = this.config.denyApps.some((d) => app && app.toLowerCase().includes(d.toLowerCase()))
+    if (denied || !text.trim() || this.clips.length >= this.config.maxClips) return
+
+    const truncated = text.length > this.config.maxChars
+    this.clips.push({
+      text: truncated ? text.slice(0, this.config.maxChars) : text,
+      app,
+      timestamp: Date.now(),
+      truncated
+    })
+  }
+}
+
+// Singleton instance
+export const clipboardWatcher = new ClipboardWatcher()
@@ -0,0 +1,62 @@
+import { net } from 'electron'
+import { EventEmitter } from 'events'
+import { emitEvent } from './events'
+import { backendHealth, BackendStatus } from './health'
+
+// How often the OS network state is sampled
+const POLL_MS = 5000
+
+export interface Connectivity {
+  online: boolean
+  network: boolean // the OS reports a network connection
+  backend: BackendStatus | null // last health probe, null before the first one
+  since: number // when `online` last changed
+}
+
+/**
+ * Combines the OS network state with backend reachability into a single
+ * online/offline state. Emits `online` and `offline` on transitions so
+ * uploads and the brief cache can switch between queueing and live mode.
+ */
+class ConnectivityMonitor extends EventEmitter {
+  private intervalId: NodeJS.Timeout | null = null
+  private state: Connectivity = { online: true, network: true, backend: null, since: Date.now() }
+
+  start(): void {
+    if (this.intervalId) return
+    backendHealth.on('checked', () => this.update())
+    this.intervalId = setInterval(() => this.update(), POLL_MS)
+    this.update()
+  }
+
+  get(): Connectivity {
+    return { ...this.state }
+  }
+
+  isOnline(): boolean {
+    return this.state.online
+  }
+
+  private update(): void {
+    const network = net.isOnline()
+    const backend = backendHealth.getLast()?.status ?? null
+
+    // Probe right away when the network comes back instead of waiting for the next round
+    if (network && !this.state.network) {
+      backendHealth.check()
+    }
+
+    const online = network && backend !== 'offline'
+    const changed = online !== this.state.online
+    this.state = { online, network, backend, since: changed ? Date.now() : this.state.since }
+
+    if (changed) {
+      console.log('[Connectivity]', online ? 'Online' : 'Offline', `(network: ${network}, backend: ${backend})`)
+      emitEvent('connectivity:changed', this.get())
+      this.emit(online ? 'online' : 'offline')
+    }
+  }
+}
+
+// Singleton instance
+export const connectivity = new ConnectivityMonitor()
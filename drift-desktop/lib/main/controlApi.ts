@@ -0,0 +1,152 @@
+import { createServer, IncomingMessage, Server, ServerResponse } from 'http'
+import { randomBytes, timingSafeEqual } from 'crypto'
+import { executeCommand, CliCommand } from './cli'
+import { getSettings, onSettingsChanged, updateSettings } from './settings'
+
+// Largest request body accepted
+const MAX_BODY_BYTES = 64 * 1024
+
+function send(res: ServerResponse, status: number, body: unknown): void {
+  res.writeHead(status, { 'Content-Type': 'application/json' })
+  res.end(JSON.stringify(body))
+}
+
+function readJson(req: IncomingMessage): Promise<any> {
+  return new Promise((resolve, reject) => {
+    let body = ''
+    req.on('data', (chunk) => {
+      body += chunk
+      if (body.length > MAX_BODY_BYTES) {
+        reject(new Error('Body too large'))
+        req.destroy()
+      }
+    })
+    req.on('end', () => {
+      try {
+        resolve(body ? JSON.parse(body) : {})
+      } catch {
+        reject(new Error('Invalid JSON'))
+      }
+    })
+    req.on('error', reject)
+  })
+}
+
+/**
+ * Optional HTTP API on 127.0.0.1 for Stream Deck buttons, scripts and other apps:
+ *
+ *   GET  /status
+ *   POST /start   { briefId, role? }
+ *   POST /stop    { summary? }
+ *   POST /marker  { text }
+ *   POST /note    { text }
+ *
+ * Every request needs `Authorization: Bearer <token>`. Requests from web pages
+ * (with an Origin header or a foreign Host) are refused.
+ */
+class ControlApi {
+  private server: Server | null = null
+  private token = ''
+
+  start(): void {
+    onSettingsChanged((next, prev) => {
+      if (JSON.stringify(next.controlApi) !== JSON.stringify(prev.controlApi)) {
+        this.restart()
+      }
+    })
+    this.restart()
+  }
+
+  /**
+   * Connection details for the settings UI.
+   */
+  async getInfo(): Promise<{ enabled: boolean; url: string; token: string }> {
+    const { controlApi } = await getSettings()
+    return { enabled: controlApi.enabled, url: `http://127.0.0.1:${controlApi.port}`, token: controlApi.token }
+  }
+
+  /**
+   * Replace the token, invalidating every configured client.
+   */
+  async regenerateToken(): Promise<string> {
+    const token = randomBytes(24).toString('hex')
+    await updateSettings({ controlApi: { token } })
+    return token
+  }
+
+  private async restart(): Promise<void> {
+    this.stop()
+
+    const { controlApi } = await getSettings()
+    if (!controlApi.enabled) return
+    if (!controlApi.token) {
+      // Saving the new token triggers another restart
+      await this.regenerateToken()
+      return
+    }
+    this.token = controlApi.token
+
+    const server = createServer((req, res) => this.handle(req, res, controlApi.port))
+    server.on('error', (error) => console.warn('[ControlAPI] Server error:', error.message))
+    server.listen(controlApi.port, '127.0.0.1', () => console.log('[ControlAPI] Listening on port', controlApi.port))
+    this.server = server
+  }
+
+  private stop(): void {
+    this.server?.close()
+    this.server = null
+  }
+
+  private authorized(req: IncomingMessage): boolean {
+    const header = req.headers.authorization || ''
+    const given = Buffer.from(header.replace(/^Bearer\s+/i, ''))
+    const expected = Buffer.from(this.token)
+    return given.length === expected.length && timingSafeEqual(given, expected)
+  }
+
+  private async handle(req: IncomingMessage, res: ServerResponse, port: number): Promise<void> {
+    // Browsers always send Origin on cross-site requests; a foreign Host means DNS rebinding
+    const host = req.headers.host || ''
+    if (req.headers.origin || (host !== `127.0.0.1:${port}` && host !== `localhost:${port}`)) {
+      return send(res, 403, { error: 'Forbidden' })
+    }
+    if (!this.authorized(req)) {
+      return send(res, 401, { error: 'Invalid token' })
+    }
+
+    let command: CliCommand | null = null
+    try {
+      const path = new URL(req.url || '/', 'http://localhost').pathname
+      if (req.method === 'GET' && path === '/status') {
+        command = { command: 'status' }
+      } else if (req.method === 'POST') {
+        const body = await readJson(req)
+        if (path === '/start' && typeof body.briefId === 'string') {
+          command = { command: 'record-start', briefId: body.briefId, role: body.role }
+        } else if (path === '/stop') {
+          command = { command: 'record-stop', summary: body.summary }
+        } else if (path === '/marker' && typeof body.text === 'string') {
+          command = { command: 'marker', text: body.text }
+        } else if (path === '/note' && typeof body.text === 'string') {
+          command = { command: 'note', text: body.text }
+        }
+      }
+    } catch (error: any) {
+      return send(res, 400, { error: error.message })
+    }
+
+    if (!command) {
+      return send(res, 404, { error: 'Not found' })
+    }
+
+    try {
+      const result = await executeCommand(command)
+      send(res, result && typeof result === 'object' && 'error' in result ? 409 : 200, result)
+    } catch (error: any) {
+      send(res, 500, { error: error.message || String(error) })
+    }
+  }
+}
+
+// Singleton instance
+export const controlApi = new ControlApi()
@@ -0,0 +1,190 @@
+import { app, crashReporter } from 'electron'
+import { randomUUID } from 'crypto'
+import { writeFileSync, mkdirSync, promises as fs } from 'fs'
+import { basename, join } from 'path'
+import { driftApi, toDriftError, CrashReportRequest } from './api'
+import { requireLiveToken } from './authInfo'
+import { encryptBuffer, loadedDataKey, readEncryptedFile } from './crypto'
+import { getStore } from './store'
+import { sessionManager } from './session'
+
+// Largest minidump sent; the backend refuses more than 4 MB of base64
+const MAX_MINIDUMP_BYTES = 3 * 1024 * 1024
+
+export type CrashKind = 'exception' | 'rejection' | 'renderer' | 'native'
+
+export interface CrashReport {
+  id: string
+  kind: CrashKind
+  at: number
+  appVersion: string
+  platform: string
+  message: string
+  stack?: string
+  sessionId?: string // session that was running, if any
+  file: string
+  submitted: boolean
+}
+
+function reportsDir(): string {
+  return join(app.getPath('userData'), 'crash-reports')
+}
+
+/**
+ * Minidumps written by Crashpad, which keeps them in nested folders.
+ */
+async function findMinidumps(dir: string, depth = 2): Promise<string[]> {
+  const entries = await fs.readdir(dir, { withFileTypes: true }).catch(() => [])
+  const found: string[] = []
+  for (const entry of entries) {
+    const path = join(dir, entry.name)
+    if (entry.isDirectory() && depth > 0) {
+      found.push(...(await findMinidumps(path, depth - 1)))
+    } else if (entry.name.endsWith('.dmp')) {
+      found.push(path)
+    }
+  }
+  return found
+}
+
+/**
+ * Crash reporting for long unattended sessions. Native crashes are written
+ * as minidumps by Electron's crash reporter; main-process exceptions and
+ * renderer crashes as JSON reports, encrypted with the data key once it is
+ * loaded. Nothing is uploaded until the user submits a report.
+ */
+class CrashReporter {
+  install(): void {
+    crashReporter.start({ uploadToServer: false, compress: true })
+    mkdirSync(reportsDir(), { recursive: true })
+
+    process.on('uncaughtException', (error) => {
+      console.error('[Crash] Uncaught exception:', error)
+      this.write('exception', error)
+    })
+    process.on('unhandledRejection', (reason) => {
+      console.error('[Crash] Unhandled rejection:', reason)
+      this.write('rejection', reason)
+    })
+    app.on('render-process-gone', (_event, _webContents, details) => {
+      if (details.reason !== 'clean-exit') {
+        this.write('renderer', new Error(`Renderer ${details.reason} (exit code ${details.exitCode})`))
+      }
+    })
+  }
+
+  async list(): Promise<CrashReport[]> {
+    const s = await getStore()
+    const submitted: string[] = s.get('submittedCrashReports') || []
+    const reports: CrashReport[] = []
+
+    for (const name of await fs.readdir(reportsDir()).catch(() => [] as string[])) {
+      if (!name.endsWith('.json')) continue
+      try {
+        const report = JSON.parse((await readEncryptedFile(join(reportsDir(), name))).toString('utf8'))
+        reports.push({ ...report, file: join(reportsDir(), name), submitted: submitted.includes(report.id) })
+      } catch {
+        // Half-written report from a crash during the crash
+      }
+    }
+
+    for (const file of await findMinidumps(app.getPath('crashDumps'))) {
+      const { mtimeMs } = await fs.stat(file)
+      const id = basename(file, '.dmp')
+      reports.push({
+        id,
+        kind: 'native',
+        at: mtimeMs,
+        appVersion: app.getVersion(),
+        platform: process.platform,
+        message: 'Native crash',
+        file,
+        submitted: submitted.includes(id)
+      })
+    }
+
+    return reports.sort((a, b) => b.at - a.at)
+  }
+
+  /**
+   * Send a report to the backend. Signing in is optional, but minidumps are only
+   * sent when signed in; too large ones are left out and the report goes without.
+   * An expired or revoked sign-in sends the report anonymously.
+   */
+  async submit(id: string): Promise<{ ok: true } | { error: string }> {
+    const report = (await this.list()).find((r) => r.id === id)
+    if (!report) {
+      return { error: `Unknown crash report: ${id}` }
+    }
+
+    const signedIn = !(await requireLiveToken())
+    let minidump: string | undefined
+    if (report.kind === 'native' && signedIn) {
+      const { size } = await fs.stat(report.file)
+      if (size <= MAX_MINIDUMP_BYTES) {
+        minidump = (await fs.readFile(report.file)).toString('base64')
+      } else {
+        console.warn('[Crash] Minidump too large to send:', size, 'bytes')
+      }
+    }
+
+    const request: CrashReportRequest = {
+      reportId: report.id,
+      kind: report.kind,
+      appVersion: report.appVersion,
+      platform: report.platform,
+      message: report.message,
+      stack: report.stack,
+      minidump,
+      occurredAt: report.at
+    }
+    try {
+      try {
+        await driftApi.submitCrashReport(request, signedIn)
+      } catch (error) {
+        // Token revoked before it expired: anonymous reports can't carry a minidump
+        if (!signedIn || toDriftError(error).kind !== 'rejected') throw error
+        await driftApi.submitCrashReport({ ...request, minidump: undefined }, false)
+      }
+    } catch (error) {
+      const failure = toDriftError(error)
+      if (failure.status === 429) {
+        return { error: 'Too many crash reports sent recently. Try again later.' }
+      }
+      return { error: failure.reachedServer ? `Server error: ${failure.status}` : failure.message }
+    }
+
+    const s = await getStore()
+    const submitted: string[] = s.get('submittedCrashReports') || []
+    s.set('submittedCrashReports', [...submitted, id])
+    return { ok: true }
+  }
+
+  /**
+   * Written synchronously: the process may be about to die.
+   */
+  private write(kind: CrashKind, error: unknown): void {
+    try {
+      const id = randomUUID()
+      const report = {
+        id,
+        kind,
+        at: Date.now(),
+        appVersion: app.getVersion(),
+        platform: process.platform,
+        message: String((error as any)?.message ?? error),
+        stack: (error as any)?.stack,
+        sessionId: sessionManager.getActive()?.sessionId
+      }
+      // Plaintext only for a crash before the key is loaded at startup
+      const json = Buffer.from(JSON.stringify(report, null, 2))
+      const key = loadedDataKey()
+      writeFileSync(join(reportsDir(), `${id}.json`), key ? encryptBuffer(key, json) : json, { mode: 0o600 })
+    } catch (writeError) {
+      console.error('[Crash] Could not write report:', writeError)
+    }
+  }
+}
+
+// Singleton instance
+export const crashReports = new CrashReporter()
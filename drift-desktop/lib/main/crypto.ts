@@ -0,0 +1,131 @@
+import { safeStorage } from 'electron'
+import { createCipheriv, createDecipheriv, randomBytes } from 'crypto'
+import { promises as fs, createReadStream, createWriteStream } from 'fs'
+import { pipeline } from 'stream/promises'
+import { join } from 'path'
+import { isPortable } from './portable'
+import { profiles } from './profiles'
+
+// Encrypted payloads start with this marker so plaintext written by older versions can still be read
+const MAGIC = Buffer.from('DRE1')
+const IV_BYTES = 12
+const TAG_BYTES = 16
+
+let keyPromise: Promise<Buffer> | null = null
+let loadedKey: Buffer | null = null
+
+/**
+ * The per-install data key. It is generated on first use and kept on disk
+ * wrapped by the OS keychain (safeStorage). Without a keychain it is kept
+ * owner-readable only, which still keeps it out of backups of the data itself.
+ * Each profile has its own key.
+ */
+export function getDataKey(): Promise<Buffer> {
+  if (!keyPromise) {
+    keyPromise = loadOrCreateKey().then((key) => (loadedKey = key))
+  }
+  return keyPromise
+}
+
+/**
+ * The data key if it has been loaded already, for writers that can't wait,
+ * like a crash report written while the process dies.
+ */
+export function loadedDataKey(): Buffer | null {
+  return loadedKey
+}
+
+profiles.on('switched', () => {
+  keyPromise = null
+  loadedKey = null
+})
+
+async function loadOrCreateKey(): Promise<Buffer> {
+  const path = join(profiles.dataDir(), 'data.key')
+  // A portable copy moves between machines, and the keychain doesn't travel with it
+  const canWrap = safeStorage.isEncryptionAvailable() && !isPortable()
+
+  try {
+    const stored = await fs.readFile(path)
+    if (stored.subarray(0, 4).equals(MAGIC)) {
+      return stored.subarray(4)
+    }
+    return Buffer.from(safeStorage.decryptString(stored), 'base64')
+  } catch (error: any) {
+    if (error.code !== 'ENOENT') throw error
+  }
+
+  const key = randomBytes(32)
+  if (canWrap) {
+    await fs.writeFile(path, safeStorage.encryptString(key.toString('base64')), { mode: 0o600 })
+  } else {
+    console.warn('[Crypto] OS keychain unavailable, storing data key unwrapped')
+    await fs.writeFile(path, Buffer.concat([MAGIC, key]), { mode: 0o600 })
+  }
+  return key
+}
+
+export function isEncrypted(data: Buffer): boolean {
+  return data.subarray(0, MAGIC.length).equals(MAGIC)
+}
+
+/**
+ * AES-256-GCM encrypt. Layout: MAGIC | iv | tag | ciphertext.
+ */
+export function encryptBuffer(key: Buffer, plaintext: Buffer): Buffer {
+  const iv = randomBytes(IV_BYTES)
+  const cipher = createCipheriv('aes-256-gcm', key, iv)
+  const ciphertext = Buffer.concat([cipher.update(plaintext), cipher.final()])
+  return Buffer.concat([MAGIC, iv, cipher.getAuthTag(), ciphertext])
+}
+
+export function decryptBuffer(key: Buffer, data: Buffer): Buffer {
+  const iv = data.subarray(MAGIC.length, MAGIC.length + IV_BYTES)
+  const tag = data.subarray(MAGIC.length + IV_BYTES, MAGIC.length + IV_BYTES + TAG_BYTES)
+  const decipher = createDecipheriv('aes-256-gcm', key, iv)
+  decipher.setAuthTag(tag)
+  return Buffer.concat([decipher.update(data.subarray(MAGIC.length + IV_BYTES + TAG_BYTES)), decipher.final()])
+}
+
+/**
+ * Write a file encrypted with the data key.
+ */
+export async function writeEncryptedFile(path: string, data: Buffer | string): Promise<void> {
+  const key = await getDataKey()
+  await fs.writeFile(path, encryptBuffer(key, Buffer.from(data)), { mode: 0o600 })
+}
+
+/**
+ * Read a file written by writeEncryptedFile. Plaintext files are returned as-is.
+ */
+export async function readEncryptedFile(path: string): Promise<Buffer> {
+  const data = await fs.readFile(path)
+  if (!isEncrypted(data)) return data
+  return decryptBuffer(await getDataKey(), data)
+}
+
+/**
+ * Stream-encrypt a (possibly large) file without loading it into memory.
+ * Layout: MAGIC | iv | ciphertext | tag.
+ */
+export async function encryptFileStream(src: string, dest: string): Promise<void> {
+  const key = await getDataKey()
+  const iv = randomBytes(IV_BYTES)
+  const cipher = createCipheriv('aes-256-gcm', key, iv)
+
+  const out = createWriteStream(dest, { mode: 0o600 })
+  out.write(Buffer.concat([MAGIC, iv]))
+  await pipeline(createReadStream(src), cipher, out, { end: false })
+  await new Promise<void>((resolve, reject) => out.end(cipher.getAuthTag(), (err?: Error | null) => (err ? reject(err) : resolve())))
+}
+
+/**
+ * Read a file written by encryptFileStream into memory. Plaintext files are returned as-is.
+ */
+export async function readEncryptedStreamFile(path: string): Promise<Buffer> {
+  const data = await fs.readFile(path)
+  if (!isEncrypted(data)) return data
+  const decipher = createDecipheriv('aes-256-gcm', await getDataKey(), data.subarray(MAGIC.length, MAGIC.length + IV_BYTES))
+  decipher.setAuthTag(data.subarray(data.length - TAG_BYTES))
+  return Buffer.concat([decipher.update(data.subarray(MAGIC.length + IV_BYTES, data.length - TAG_BYTES)), decipher.final()])
+}
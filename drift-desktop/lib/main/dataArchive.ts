@@ -0,0 +1,153 @@
+import { app } from 'electron'
+import { promises as fs } from 'fs'
+import { randomUUID } from 'crypto'
+import { sessionHistory, SessionRecord } from './sessionHistory'
+import { focusTimer, FocusBlock } from './focus'
+import { getSettings, Settings } from './settings'
+
+// Bump when the archive layout changes; imports reject versions they don't know
+export const ARCHIVE_VERSION = 1
+
+// What to do with an archived session whose id already exists locally
+export type ImportConflictStrategy = 'skip' | 'replace' | 'duplicate'
+
+export interface ImportSummary {
+  imported: number // new sessions
+  replaced: number
+  duplicated: number // conflicting sessions stored under a new id
+  skipped: number
+  conflicts: string[] // ids that already existed locally
+  invalid: number // entries that are not sessions
+  focusBlocks: number
+}
+
+// A complete export of the data Drift keeps on this machine
+export interface DataArchive {
+  format: 'drift-archive'
+  version: number
+  exportedAt: number
+  appVersion: string
+  sessions: SessionRecord[] // including notes, timelines and clips
+  focusBlocks: FocusBlock[]
+  settings: Settings
+}
+
+/**
+ * Settings without credentials: proxy login, control API token and the secret calendar addresses.
+ */
+export function withoutSecrets(settings: Settings): Settings {
+  return {
+    ...settings,
+    calendar: { ...settings.calendar, icsUrls: [] },
+    controlApi: { ...settings.controlApi, token: '' },
+    proxy: { ...settings.proxy, username: '', password: '' },
+    webhook: { ...settings.webhook, secret: '' },
+    slack: { ...settings.slack, token: '' }
+  }
+}
+
+/**
+ * Write all local sessions, focus blocks and settings to a JSON archive.
+ * Account tokens and other secrets are never included.
+ */
+export async function exportData(path: string): Promise<{ path: string; sessions: number } | { error: string }> {
+  const archive: DataArchive = {
+    format: 'drift-archive',
+    version: ARCHIVE_VERSION,
+    exportedAt: Date.now(),
+    appVersion: app.getVersion(),
+    sessions: await sessionHistory.list(),
+    focusBlocks: await focusTimer.listBlocks(),
+    settings: withoutSecrets(await getSettings())
+  }
+
+  try {
+    await fs.writeFile(path, JSON.stringify(archive, null, 2), { mode: 0o600 })
+  } catch (error: any) {
+    return { error: `Could not write archive: ${error.message}` }
+  }
+  console.log('[Archive] Exported', archive.sessions.length, 'sessions to', path)
+  return { path, sessions: archive.sessions.length }
+}
+
+// Enough of a session record to store and display it
+function isSessionRecord(value: any): value is SessionRecord {
+  return (
+    !!value &&
+    typeof value.id === 'string' &&
+    typeof value.briefId === 'string' &&
+    typeof value.startedAt === 'number' &&
+    typeof value.endedAt === 'number' &&
+    typeof value.durationSeconds === 'number' &&
+    Array.isArray(value.idleGaps) &&
+    Array.isArray(value.notes)
+  )
+}
+
+/**
+ * Merge the sessions and focus blocks of an archive into local storage.
+ * Settings in the archive are not applied.
+ */
+export async function importData(
+  path: string,
+  strategy: ImportConflictStrategy = 'skip'
+): Promise<ImportSummary | { error: string }> {
+  let archive: DataArchive
+  try {
+    archive = JSON.parse(await fs.readFile(path, 'utf-8'))
+  } catch (error: any) {
+    return { error: `Could not read archive: ${error.message}` }
+  }
+
+  if (archive?.format !== 'drift-archive' || typeof archive.version !== 'number') {
+    return { error: 'Not a Drift archive' }
+  }
+  if (archive.version > ARCHIVE_VERSION) {
+    return { error: `Archive version ${archive.version} is newer than this app supports (${ARCHIVE_VERSION})` }
+  }
+
+  const summary: ImportSummary = {
+    imported: 0,
+    replaced: 0,
+    duplicated: 0,
+    skipped: 0,
+    conflicts: [],
+    invalid: 0,
+    focusBlocks: 0
+  }
+
+  const existing = new Set((await sessionHistory.list()).map((r) => r.id))
+  const toStore: SessionRecord[] = []
+
+  for (const record of Array.isArray(archive.sessions) ? archive.sessions : []) {
+    if (!isSessionRecord(record)) {
+      summary.invalid++
+      continue
+    }
+    if (!existing.has(record.id)) {
+      toStore.push(record)
+      summary.imported++
+      continue
+    }
+
+    summary.conflicts.push(record.id)
+    if (strategy === 'replace') {
+      toStore.push(record)
+      summary.replaced++
+    } else if (strategy === 'duplicate') {
+      // A copy is a local record only; it was never submitted under this id
+      toStore.push({ ...record, id: randomUUID(), submissionId: undefined, uploaded: false })
+      summary.duplicated++
+    } else {
+      summary.skipped++
+    }
+  }
+
+  await sessionHistory.upsertMany(toStore)
+
+  const blocks = Array.isArray(archive.focusBlocks) ? archive.focusBlocks.filter((b) => typeof b?.startedAt === 'number') : []
+  summary.focusBlocks = await focusTimer.importBlocks(blocks)
+
+  console.log('[Archive] Imported from', path, summary)
+  return summary
+}
@@ -0,0 +1,35 @@
+import { app } from 'electron'
+import { randomUUID } from 'crypto'
+import { hostname } from 'os'
+import { getStore } from './store'
+
+// What the backend records about this installation
+export interface DeviceInfo {
+  deviceId: string
+  name: string
+  platform: NodeJS.Platform
+  appVersion: string
+}
+
+let deviceId: string | null = null
+
+/**
+ * Stable ID of this installation, generated on first run. Sent with every
+ * backend call so the web app can list and revoke devices.
+ */
+export async function getDeviceId(): Promise<string> {
+  if (deviceId) return deviceId
+  const s = await getStore()
+  deviceId = (s.get('deviceId') as string | undefined) ?? randomUUID()
+  s.set('deviceId', deviceId)
+  return deviceId
+}
+
+export async function getDeviceInfo(): Promise<DeviceInfo> {
+  return {
+    deviceId: await getDeviceId(),
+    name: hostname(),
+    platform: process.platform,
+    appVersion: app.getVersion()
+  }
+}
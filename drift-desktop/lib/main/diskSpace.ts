@@ -0,0 +1,87 @@
+import { getFreeBytes } from './storage'
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+// How often free space is checked while a session runs
+const POLL_SECONDS = 60
+
+// Returned instead of starting a session when the disk is nearly full
+export interface LowDiskError {
+  error: string
+  code: 'low-disk'
+  freeBytes: number
+  requiredBytes: number
+}
+
+/**
+ * Refuse to record when free space is below the configured floor.
+ * Unknown free space (e.g. statfs unsupported) does not block recording.
+ */
+export async function checkDiskSpace(): Promise<LowDiskError | null> {
+  const { disk } = await getSettings()
+  const freeBytes = await getFreeBytes()
+  const requiredBytes = disk.minFreeMB * 1024 * 1024
+  if (freeBytes === null || freeBytes >= requiredBytes) return null
+
+  return {
+    error: `Not enough disk space: ${Math.floor(freeBytes / 1024 / 1024)} MB free, ${disk.minFreeMB} MB needed`,
+    code: 'low-disk',
+    freeBytes,
+    requiredBytes
+  }
+}
+
+/**
+ * Checks free space during long sessions. Below twice the floor it warns
+ * once with `low-disk`; below the floor it ends and submits the session
+ * while there is still room to write it.
+ */
+class DiskSpaceMonitor {
+  private intervalId: NodeJS.Timeout | null = null
+  private warned = false
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), POLL_SECONDS * 1000)
+    sessionManager.on('started', () => (this.warned = false))
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  private async check(): Promise<void> {
+    const active = sessionManager.getActive()
+    if (!active) return
+
+    const { disk } = await getSettings()
+    const freeBytes = await getFreeBytes()
+    if (freeBytes === null) return
+    const requiredBytes = disk.minFreeMB * 1024 * 1024
+
+    if (freeBytes < requiredBytes) {
+      console.warn('[Disk] Free space below floor, ending session')
+      emitEvent('low-disk', { freeBytes, requiredBytes, stopped: true })
+      await sessionManager.end(undefined, 'low-disk')
+      return
+    }
+
+    if (freeBytes < requiredBytes * 2 && !this.warned) {
+      this.warned = true
+      emitEvent('low-disk', { freeBytes, requiredBytes, stopped: false })
+      await notify(
+        'lowDisk',
+        'Drift · Disk almost full',
+        `${Math.floor(freeBytes / 1024 / 1024)} MB left. ${active.briefName} will be stopped below ${disk.minFreeMB} MB.`
+      )
+    }
+  }
+}
+
+// Singleton instance
+export const diskSpaceMonitor = new DiskSpaceMonitor()
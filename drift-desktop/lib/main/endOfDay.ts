@@ -0,0 +1,70 @@
+import { getSettings, onSettingsChanged } from './settings'
+import { sessionManager } from './session'
+import { sessionHistory, DailySummary, formatDuration } from './sessionHistory'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+import { jobScheduler } from './jobs'
+
+function recapText(summary: DailySummary): string {
+  if (summary.sessionCount === 0) return 'No sessions recorded today.'
+  const briefs = summary.byBrief
+    .slice(0, 3)
+    .map((b) => `${b.briefName} (${formatDuration(b.seconds)})`)
+    .join(', ')
+  const sessions = summary.sessionCount === 1 ? '1 session' : `${summary.sessionCount} sessions`
+  return `${sessions} · ${formatDuration(summary.totalSeconds)} · ${briefs}`
+}
+
+/**
+ * Runs the configured end-of-day routine: stops a forgotten session,
+ * submits it, and shows a recap of the day.
+ */
+class EndOfDayScheduler {
+  /**
+   * Schedule from current settings and reschedule whenever they change.
+   */
+  start(): void {
+    jobScheduler.register('end-of-day', async () => {
+      await this.run()
+    })
+    onSettingsChanged((next, prev) => {
+      if (next.endOfDay.enabled !== prev.endOfDay.enabled || next.endOfDay.time !== prev.endOfDay.time) {
+        this.schedule()
+      }
+    })
+    this.schedule()
+  }
+
+  async schedule(): Promise<void> {
+    const { endOfDay } = await getSettings()
+    if (!endOfDay.enabled) {
+      await jobScheduler.cancel('end-of-day')
+      return
+    }
+    await jobScheduler.schedule('end-of-day', { dailyAt: endOfDay.time })
+  }
+
+  /**
+   * Run the wrap-up immediately.
+   */
+  async run(): Promise<DailySummary> {
+    console.log('[EndOfDay] Running wrap-up')
+
+    if (sessionManager.getActive()) {
+      const result = await sessionManager.end(undefined, 'end-of-day')
+      if (result.error) {
+        console.error('[EndOfDay] Failed to submit session:', result.error)
+      }
+    }
+
+    const summary = await sessionHistory.summarizeDay()
+    emitEvent('session:daily-summary', summary)
+
+    await notify('dailyRecap', 'Drift · Day wrapped up', recapText(summary))
+
+    return summary
+  }
+}
+
+// Singleton instance
+export const endOfDayScheduler = new EndOfDayScheduler()
@@ -0,0 +1,260 @@
+import { webContents, WebContents } from 'electron'
+import { windowRegistry } from './windowRegistry'
+import type { ActivityEntry } from './activityTracker'
+import type { ActiveSession, CaptureGap, EndReason, IdleGap, PauseReason } from './session'
+import type { DailySummary } from './sessionHistory'
+import type { BriefsResult } from './briefs'
+import type { FocusBlock, FocusStatus } from './focus'
+import type { LiveSyncStatus } from './liveSync'
+import type { CalendarEvent } from './calendar'
+import type { PermissionKind } from './permissions'
+import type { PurgeResult } from './retention'
+import type { Job } from './jobs'
+import type { Profile } from './profiles'
+import type { BackendHealth } from './health'
+import type { Connectivity } from './connectivity'
+import type { GoalPeriod, GoalStatus } from './goals'
+import type { DetectedMeeting } from './meetings'
+import type { AuthErrorReason } from './authPages'
+import type { LoopbackBindError } from './loopbackServer'
+import type { UIState } from '../state/AppStateMachine'
+import type { RecordingState } from '../state/RecordingStateMachine'
+
+// Bump when a payload changes incompatibly
+export const EVENT_SCHEMA_VERSION = 1
+
+// Envelope sent on the `drift:event` channel for every event
+export interface DriftEventEnvelope<E extends DriftEvent = DriftEvent> {
+  v: typeof EVENT_SCHEMA_VERSION
+  seq: number // increases by one per event for the life of the main process
+  type: E
+  at: number
+  payload: DriftEventMap[E]
+}
+
+// Recent events kept for replayEvents()
+const RECENT_EVENT_LIMIT = 200
+
+// Superseded by the next one within seconds; not worth keeping for replay
+const TRANSIENT_EVENTS: DriftEvent[] = ['session:heartbeat', 'session:countdown-tick', 'focus:tick', 'chat:chunk']
+
+// Carry credentials; never kept for replay, which any window can request
+const SENSITIVE_EVENTS: DriftEvent[] = ['auth-token-received']
+
+// Kept and re-sent to subscribed windows until they acknowledge them; losing one leaves the UI wrong
+const GUARANTEED_EVENTS: DriftEvent[] = [
+  'auth-token-received',
+  'auth-logged-out',
+  'auth:account-switched',
+  'session:ended',
+  'session:auto-ended',
+  'uploads:flushed'
+]
+
+// Re-send an unacknowledged guaranteed event after this long
+const REDELIVERY_MS = 5000
+
+// Bound on guaranteed events held for a window that stopped acknowledging
+const UNACKED_LIMIT = 500
+
+/**
+ * Every event the main process sends to the renderer, with its payload.
+ * Backend responses that are passed through unchanged are typed loosely.
+ */
+export interface DriftEventMap {
+  // Auth
+  'auth-token-received': { token: string; email: string }
+  'auth-timeout': { timeoutSeconds: number }
+  'auth-error': { reason: AuthErrorReason }
+  'auth-server-listening': { port: number; hosts: string[] }
+  'auth-server-error': LoopbackBindError
+  'auth-expiring-soon': { account: string; expiresAt: number; secondsLeft: number }
+  'auth-logged-out': { account: string; revoked: boolean }
+  'auth:account-switched': { name: string | null }
+  'profile:switched': Profile
+
+  // Session lifecycle
+  'session:started': any
+  'session:ended': any
+  'session:restored': ActiveSession
+  'session:auto-ended': { reason: EndReason; briefId: string; briefName: string; error?: string; [key: string]: any }
+  'session:brief-switched': {
+    mode: 'reassign' | 'split'
+    from: { sessionId: string; briefId: string }
+    to: { sessionId: string; briefId: string }
+  }
+  'session:paused': { reason: PauseReason; at: number }
+  'session:resumed': IdleGap
+  'session:wake-prompt': { sessionId: string; sleptSeconds: number }
+  'battery:low': { percent: number; captureDowngraded: boolean }
+  'goal-reached': GoalStatus & { period: GoalPeriod; streakDays: number }
+  'low-disk': { freeBytes: number; requiredBytes: number; stopped: boolean }
+  'capture-degraded': { component: CaptureGap['component']; stalledSince: number }
+  'capture-recovered': { component: CaptureGap['component']; gapSeconds: number }
+  'privacy:capture-paused': { app: string }
+  'privacy:capture-resumed': { app: string }
+  'session:heartbeat': { sessionId: string; elapsedSeconds: number; paused: boolean }
+  'session:countdown-tick': { remainingSeconds: number }
+  'session:countdown-cancelled': void
+  'session:state-changed': { prev: RecordingState; next: RecordingState }
+  'session:activity': ActivityEntry
+  'session:screen-insight': { bullets: string[]; timestamp: number }
+  'session:note-processed': { original: string; bullet: string }
+  'session:daily-summary': DailySummary
+  'permissions:missing': PermissionKind[]
+
+  // Sync and uploads
+  'drift:synced': any
+  'briefs:updated': BriefsResult
+  'workspace:updated': any
+  'live:status': LiveSyncStatus
+  'live:command': { command: string; sessionId?: string }
+  'session:handoff': {
+    status: 'requested' | 'handed-off' | 'continued' | 'failed'
+    sessionId?: string
+    continuesSessionId?: string
+    error?: string
+  }
+  'backend-status': BackendHealth
+  'connectivity:changed': Connectivity
+  'uploads:flushed': { sent: number; remaining: number }
+
+  // Local storage
+  'retention:purged': PurgeResult
+  'job:status': Job
+
+  // Focus blocks
+  'focus:started': FocusStatus
+  'focus:tick': { remainingSeconds: number }
+  'focus:completed': FocusBlock
+  'focus:cancelled': void
+
+  // Calendar and settings
+  'calendar:meeting-started': CalendarEvent
+  'meeting-detected': DetectedMeeting
+  'nudge:not-recording': { activeMinutes: number }
+  'quick-capture:saved': { text: string; sessionId: string | null } // null: kept as a standalone note
+  'settings:environment-changed': { name: string; apiUrl: string }
+
+  // Chat and window state
+  'api-success': void
+  'api-error': string
+  'chat:chunk': { text: string }
+  'invisibility-state-changed': boolean
+  'state-changed': { prev: UIState; next: UIState }
+}
+
+export type DriftEvent = keyof DriftEventMap
+
+let lastSeq = 0
+const recent: DriftEventEnvelope[] = []
+let evictedThrough = 0 // highest seq dropped from `recent`
+
+export function latestEventSeq(): number {
+  return lastSeq
+}
+
+/**
+ * Events after `sinceSeq`, oldest first, so a reloaded window can catch up on
+ * what it missed. Transient events (heartbeats, ticks, chat chunks) are not
+ * kept. `complete` is false when older events have already been dropped; the
+ * caller should then start from a fresh app snapshot.
+ */
+export function replayEvents(sinceSeq: number): { events: DriftEventEnvelope[]; latestSeq: number; complete: boolean } {
+  return {
+    events: recent.filter((e) => e.seq > sinceSeq),
+    latestSeq: lastSeq,
+    complete: sinceSeq >= evictedThrough
+  }
+}
+
+// Windows that acknowledge events, by webContents id, with the highest seq each has acknowledged
+const subscribers = new Map<number, number>()
+// Guaranteed events some subscriber hasn't acknowledged yet
+let unacked: DriftEventEnvelope[] = []
+let redeliveryTimer: NodeJS.Timeout | null = null
+
+function pruneAcked(): void {
+  const lowest = Math.min(...subscribers.values())
+  unacked = unacked.filter((e) => e.seq > lowest)
+  if (unacked.length === 0 && redeliveryTimer) {
+    clearInterval(redeliveryTimer)
+    redeliveryTimer = null
+  }
+}
+
+function armRedelivery(): void {
+  if (redeliveryTimer || unacked.length === 0) return
+  redeliveryTimer = setInterval(() => {
+    for (const [id, acked] of subscribers) {
+      const contents = webContents.fromId(id)
+      if (!contents || contents.isDestroyed()) continue
+      for (const envelope of unacked) {
+        if (envelope.seq > acked) contents.send('drift:event', envelope)
+      }
+    }
+  }, REDELIVERY_MS)
+}
+
+/**
+ * Subscribe a window to guaranteed delivery, resuming after the last event
+ * it processed (0 for a fresh window). Returns what it missed: the replay
+ * plus any guaranteed events older than the replay buffer that it never
+ * acknowledged. From then on, guaranteed events are re-sent to it until it
+ * acknowledges them with ackEvents(). Envelopes may arrive more than once;
+ * consumers skip seqs they have already seen.
+ */
+export function resumeEvents(sender: WebContents, sinceSeq: number): ReturnType<typeof replayEvents> {
+  if (!subscribers.has(sender.id)) {
+    sender.once('destroyed', () => {
+      subscribers.delete(sender.id)
+      pruneAcked()
+    })
+  }
+  subscribers.set(sender.id, sinceSeq)
+
+  const replay = replayEvents(sinceSeq)
+  const pinned = unacked.filter((e) => e.seq > sinceSeq && !replay.events.some((r) => r.seq === e.seq))
+  return { ...replay, events: [...pinned, ...replay.events].sort((a, b) => a.seq - b.seq) }
+}
+
+/**
+ * Acknowledge every event up to and including `seq`.
+ */
+export function ackEvents(sender: WebContents, seq: number): void {
+  const acked = subscribers.get(sender.id)
+  if (acked === undefined || seq <= acked) return
+  subscribers.set(sender.id, Math.min(seq, lastSeq))
+  pruneAcked()
+}
+
+type PayloadArgs<E extends DriftEvent> = DriftEventMap[E] extends void ? [] : [DriftEventMap[E]]
+
+/**
+ * Send an event to the renderer. The payload goes out on the event's own
+ * channel, as existing listeners expect, and wrapped in a versioned
+ * envelope on `drift:event` for consumers that want one stream.
+ */
+export function emitEvent<E extends DriftEvent>(event: E, ...args: PayloadArgs<E>): void {
+  const payload = args[0] as DriftEventMap[E]
+  if (args.length > 0) {
+    windowRegistry.broadcast(event, payload)
+  } else {
+    windowRegistry.broadcast(event)
+  }
+
+  const envelope: DriftEventEnvelope<E> = { v: EVENT_SCHEMA_VERSION, seq: ++lastSeq, type: event, at: Date.now(), payload }
+  if (!TRANSIENT_EVENTS.includes(event) && !SENSITIVE_EVENTS.includes(event)) {
+    recent.push(envelope)
+    if (recent.length > RECENT_EVENT_LIMIT) evictedThrough = recent.shift()!.seq
+  }
+  if (GUARANTEED_EVENTS.includes(event) && subscribers.size > 0) {
+    unacked.push(envelope)
+    if (unacked.length > UNACKED_LIMIT) {
+      const dropped = unacked.shift()!
+      console.warn(`[Events] Dropping unacknowledged ${dropped.type} #${dropped.seq}; a window stopped acknowledging events`)
+    }
+    armRedelivery()
+  }
+  windowRegistry.broadcast('drift:event', envelope)
+}
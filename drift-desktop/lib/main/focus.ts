@@ -0,0 +1,145 @@
+import { getStore } from './store'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+// A finished focus block, logged against the brief it was worked on
+export interface FocusBlock {
+  startedAt: number
+  endedAt: number
+  minutes: number
+  briefId: string | null
+  briefName: string | null
+  sessionId: string | null
+}
+
+export interface FocusStatus {
+  running: boolean
+  minutes: number
+  startedAt: number | null
+  remainingSeconds: number
+}
+
+const MAX_FOCUS_MINUTES = 240
+
+/**
+ * Pomodoro-style focus blocks. Counts down with `focus:tick` once per second,
+ * notifies when the block is over and logs it against the session's brief.
+ */
+class FocusTimer {
+  private intervalId: NodeJS.Timeout | null = null
+  private startedAt: number | null = null
+  private minutes = 0
+
+  getStatus(): FocusStatus {
+    return {
+      running: this.startedAt !== null,
+      minutes: this.minutes,
+      startedAt: this.startedAt,
+      remainingSeconds: this.remainingSeconds()
+    }
+  }
+
+  /**
+   * Start a focus block. With `session`, a session for that brief is started
+   * first unless one is already running.
+   */
+  async start(minutes: number, session?: { briefId: string; role: string }): Promise<any> {
+    if (this.startedAt !== null) {
+      return { error: 'A focus block is already running' }
+    }
+    if (!Number.isFinite(minutes) || minutes < 1 || minutes > MAX_FOCUS_MINUTES) {
+      return { error: `Focus blocks must be between 1 and ${MAX_FOCUS_MINUTES} minutes` }
+    }
+
+    if (session && !sessionManager.getActive()) {
+      const result = await sessionManager.start(session.briefId, session.role)
+      if (result?.error) return result
+    }
+
+    this.minutes = Math.floor(minutes)
+    this.startedAt = Date.now()
+    this.intervalId = setInterval(() => this.tick(), 1000)
+    emitEvent('focus:started', this.getStatus())
+    console.log('[Focus] Started', this.minutes, 'minute block')
+    return this.getStatus()
+  }
+
+  /**
+   * Abandon the running block. Cancelled blocks are not logged.
+   */
+  cancel(): boolean {
+    if (this.startedAt === null) return false
+    this.reset()
+    emitEvent('focus:cancelled')
+    return true
+  }
+
+  async listBlocks(range?: { from?: number; to?: number }): Promise<FocusBlock[]> {
+    const s = await getStore()
+    const all: FocusBlock[] = s.get('focusBlocks') || []
+    return all.filter((b) => {
+      if (range?.from !== undefined && b.endedAt < range.from) return false
+      if (range?.to !== undefined && b.startedAt > range.to) return false
+      return true
+    })
+  }
+
+  /**
+   * Add logged focus blocks, skipping ones already known (same start time). Returns how many were added.
+   */
+  async importBlocks(blocks: FocusBlock[]): Promise<number> {
+    const s = await getStore()
+    const all: FocusBlock[] = s.get('focusBlocks') || []
+    const known = new Set(all.map((b) => b.startedAt))
+    const added = blocks.filter((b) => !known.has(b.startedAt))
+    s.set('focusBlocks', [...all, ...added])
+    return added.length
+  }
+
+  private remainingSeconds(): number {
+    if (this.startedAt === null) return 0
+    return Math.max(0, Math.ceil((this.startedAt + this.minutes * 60 * 1000 - Date.now()) / 1000))
+  }
+
+  private tick(): void {
+    const remainingSeconds = this.remainingSeconds()
+    emitEvent('focus:tick', { remainingSeconds })
+    if (remainingSeconds === 0) {
+      this.complete()
+    }
+  }
+
+  private async complete(): Promise<void> {
+    const active = sessionManager.getActive()
+    const block: FocusBlock = {
+      startedAt: this.startedAt!,
+      endedAt: Date.now(),
+      minutes: this.minutes,
+      briefId: active?.briefId ?? null,
+      briefName: active?.briefName ?? null,
+      sessionId: active?.sessionId ?? null
+    }
+    this.reset()
+
+    const s = await getStore()
+    const all: FocusBlock[] = s.get('focusBlocks') || []
+    all.push(block)
+    s.set('focusBlocks', all)
+
+    emitEvent('focus:completed', block)
+    await notify('focusBlockEnded', 'Drift · Focus block done', `${block.minutes} minutes${block.briefName ? ` on ${block.briefName}` : ''}. Time for a break.`)
+  }
+
+  private reset(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+    this.startedAt = null
+    this.minutes = 0
+  }
+}
+
+// Singleton instance
+export const focusTimer = new FocusTimer()
@@ -0,0 +1,81 @@
+import { execFile } from 'child_process'
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { promisify } from 'util'
+import { getSettings } from './settings'
+import { sessionHistory, SessionRecord } from './sessionHistory'
+import { sessionFileName } from './sessionExport'
+import { renderVaultNote } from './vault'
+import { getWebBaseUrl } from './api'
+
+const execFileAsync = promisify(execFile)
+
+// Folder inside the repository that holds the notes and metadata
+const SESSIONS_DIR = 'sessions'
+
+async function git(repoPath: string, args: string[]): Promise<string> {
+  const { stdout } = await execFileAsync('git', args, { cwd: repoPath, timeout: 60000, windowsHide: true })
+  return stdout
+}
+
+// Metadata kept next to the note; bulky per-minute data and share tokens stay local
+function metadataOf(record: SessionRecord): Partial<SessionRecord> {
+  const { timeline, inputActivity, clips, screenText, resourceUsage, shareLinks, ...metadata } = record
+  return metadata
+}
+
+/**
+ * Commits each session's note and metadata to a local Git repository,
+ * optionally pushing to a remote, for a versioned, self-hosted work log.
+ * The repository is created on first use. Commits run one at a time.
+ */
+class GitBackup {
+  private queue: Promise<void> = Promise.resolve()
+
+  start(): void {
+    sessionHistory.on('saved', (record: SessionRecord) => {
+      this.queue = this.queue.then(() => this.backup(record))
+    })
+  }
+
+  private async backup(record: SessionRecord): Promise<void> {
+    const { gitBackup } = await getSettings()
+    if (!gitBackup.enabled || !gitBackup.repoPath) return
+
+    const { repoPath, authorName, authorEmail, remote } = gitBackup
+    try {
+      await fs.mkdir(join(repoPath, SESSIONS_DIR), { recursive: true })
+      const isRepo = await fs.stat(join(repoPath, '.git')).then(() => true, () => false)
+      if (!isRepo) {
+        await git(repoPath, ['init'])
+        console.log('[GitBackup] Initialized repository at', repoPath)
+      }
+
+      const notePath = join(SESSIONS_DIR, sessionFileName(record, 'md'))
+      const metadataPath = join(SESSIONS_DIR, sessionFileName(record, 'json'))
+      const existed = await fs.stat(join(repoPath, notePath)).then(() => true, () => false)
+      await fs.writeFile(join(repoPath, notePath), renderVaultNote(record, await getWebBaseUrl()))
+      await fs.writeFile(join(repoPath, metadataPath), JSON.stringify(metadataOf(record), null, 2) + '\n')
+      await git(repoPath, ['add', '--', notePath, metadataPath])
+
+      // Nothing staged: the record changed only in fields that aren't backed up
+      const staged = await git(repoPath, ['diff', '--cached', '--name-only'])
+      if (!staged.trim()) return
+
+      const identity = ['-c', `user.name=${authorName}`, '-c', `user.email=${authorEmail}`]
+      const verb = existed ? 'Update' : 'Add'
+      await git(repoPath, [...identity, 'commit', '-m', `${verb} session: ${record.briefName} (${new Date(record.startedAt).toISOString().slice(0, 10)})`])
+      console.log('[GitBackup] Committed session', record.id)
+
+      if (remote) {
+        await git(repoPath, ['push', remote, 'HEAD'])
+        console.log('[GitBackup] Pushed to', remote)
+      }
+    } catch (error: any) {
+      console.error('[GitBackup] Backup failed:', error.stderr?.trim() || error.message)
+    }
+  }
+}
+
+// Singleton instance
+export const gitBackup = new GitBackup()
@@ -0,0 +1,175 @@
+import { getStore } from './store'
+import { getSettings } from './settings'
+import { sessionHistory } from './sessionHistory'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+export type GoalPeriod = 'daily' | 'weekly'
+
+export interface GoalStatus {
+  goalSeconds: number
+  recordedSeconds: number // including the running session
+  reached: boolean
+}
+
+export interface GoalProgress {
+  daily: GoalStatus | null // null when no goal is set
+  weekly: GoalStatus | null
+  streakDays: number // consecutive days the daily goal was met, up to today
+  bestStreakDays: number
+}
+
+// Persisted so a goal is only celebrated once per day or week
+interface GoalState {
+  dailyReachedOn?: string
+  weeklyReachedOn?: string // Monday of the week
+  bestStreakDays?: number
+}
+
+function localDay(time: number): string {
+  const d = new Date(time)
+  return `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}`
+}
+
+// Local day `offset` days from the given time (negative: earlier)
+function dayOffset(time: number, offset: number): string {
+  const d = new Date(time)
+  return localDay(new Date(d.getFullYear(), d.getMonth(), d.getDate() + offset).getTime())
+}
+
+function daysSinceMonday(time: number): number {
+  return (new Date(time).getDay() + 6) % 7
+}
+
+function mondayOf(time: number): string {
+  return dayOffset(time, -daysSinceMonday(time))
+}
+
+/**
+ * Tracks recorded time against the daily and weekly goals from settings,
+ * including the running session, and keeps the daily streak. Emits
+ * `goal-reached` once per period when a goal is met.
+ */
+class GoalTracker {
+  // Recorded time per local day from history; rebuilt when a session is saved
+  private perDay: Map<string, number> | null = null
+  private liveSeconds = 0
+  private progress: GoalProgress | null = null
+  private checking = false
+
+  start(): void {
+    sessionHistory.on('saved', () => {
+      this.perDay = null
+      this.liveSeconds = 0
+      this.check()
+    })
+    sessionManager.on('heartbeat', ({ elapsedSeconds }) => {
+      this.liveSeconds = elapsedSeconds
+      this.check()
+    })
+    this.check()
+  }
+
+  async getProgress(): Promise<GoalProgress> {
+    const { goals } = await getSettings()
+    const perDay = await this.recordedPerDay()
+    const now = Date.now()
+    const today = localDay(now)
+    const recordedOn = (day: string) => (day === today ? (perDay.get(day) ?? 0) + this.liveSeconds : perDay.get(day) ?? 0)
+    const todaySeconds = recordedOn(today)
+
+    let weekSeconds = 0
+    for (let offset = -daysSinceMonday(now); offset <= 0; offset++) {
+      weekSeconds += recordedOn(dayOffset(now, offset))
+    }
+
+    const dailyGoal = goals.dailyMinutes * 60
+    const weeklyGoal = goals.weeklyMinutes * 60
+
+    // Today doesn't break the streak until it's over
+    let streakDays = 0
+    if (dailyGoal > 0) {
+      let offset = todaySeconds >= dailyGoal ? 0 : -1
+      while (recordedOn(dayOffset(now, offset)) >= dailyGoal) {
+        streakDays++
+        offset--
+      }
+    }
+
+    const state: GoalState = (await getStore()).get('goalState') || {}
+    return {
+      daily: dailyGoal > 0 ? { goalSeconds: dailyGoal, recordedSeconds: todaySeconds, reached: todaySeconds >= dailyGoal } : null,
+      weekly: weeklyGoal > 0 ? { goalSeconds: weeklyGoal, recordedSeconds: weekSeconds, reached: weekSeconds >= weeklyGoal } : null,
+      streakDays,
+      bestStreakDays: Math.max(state.bestStreakDays ?? 0, streakDays)
+    }
+  }
+
+  /**
+   * Progress as of the last check, for synchronous callers like the tray.
+   */
+  getCachedProgress(): GoalProgress | null {
+    return this.progress
+  }
+
+  private async recordedPerDay(): Promise<Map<string, number>> {
+    if (!this.perDay) {
+      const perDay = new Map<string, number>()
+      for (const r of await sessionHistory.list()) {
+        const day = localDay(r.endedAt)
+        perDay.set(day, (perDay.get(day) ?? 0) + r.durationSeconds)
+      }
+      this.perDay = perDay
+    }
+    return this.perDay
+  }
+
+  private async check(): Promise<void> {
+    if (this.checking) return
+    this.checking = true
+    try {
+      const progress = await this.getProgress()
+      this.progress = progress
+
+      const s = await getStore()
+      const state: GoalState = s.get('goalState') || {}
+      const now = Date.now()
+      let changed = false
+
+      if (progress.daily?.reached && state.dailyReachedOn !== localDay(now)) {
+        state.dailyReachedOn = localDay(now)
+        changed = true
+        this.celebrate('daily', progress.daily, progress.streakDays)
+      }
+      if (progress.weekly?.reached && state.weeklyReachedOn !== mondayOf(now)) {
+        state.weeklyReachedOn = mondayOf(now)
+        changed = true
+        this.celebrate('weekly', progress.weekly, progress.streakDays)
+      }
+      if (progress.streakDays > (state.bestStreakDays ?? 0)) {
+        state.bestStreakDays = progress.streakDays
+        changed = true
+      }
+      if (changed) s.set('goalState', state)
+    } finally {
+      this.checking = false
+    }
+  }
+
+  private celebrate(period: GoalPeriod, status: GoalStatus, streakDays: number): void {
+    console.log('[Goals]', period, 'goal reached')
+    emitEvent('goal-reached', { period, ...status, streakDays })
+    const hours = Math.round((status.goalSeconds / 3600) * 10) / 10
+    notify(
+      'goalReached',
+      `Drift · ${period === 'daily' ? 'Daily' : 'Weekly'} goal reached`,
+      period === 'daily' && streakDays > 1
+        ? `${hours}h recorded today. ${streakDays}-day streak!`
+        : `${hours}h recorded ${period === 'daily' ? 'today' : 'this week'}.`
+    )
+  }
+}
+
+// Singleton instance
+export const goalTracker = new GoalTracker()
@@ -0,0 +1,81 @@
+import { EventEmitter } from 'events'
+import { emitEvent } from './events'
+import { driftApi, toDriftError } from './api'
+
+// How often the backend is probed in the background
+const PROBE_INTERVAL_MS = 30 * 1000
+
+// A probe slower than this counts as degraded
+const DEGRADED_LATENCY_MS = 2000
+
+// A probe without an answer after this long counts as offline
+const PROBE_TIMEOUT_MS = 5000
+
+export type BackendStatus = 'online' | 'degraded' | 'offline'
+
+export interface BackendHealth {
+  status: BackendStatus
+  latencyMs: number | null // null when the backend didn't answer
+  checkedAt: number
+  error?: string
+}
+
+/**
+ * Periodically pings the backend (`/desktop/health`) and reports whether it is
+ * online, degraded (slow or failing) or offline, via `backend-status` events.
+ * Emits `checked` with every result for other main-process modules.
+ */
+class BackendHealthMonitor extends EventEmitter {
+  private intervalId: NodeJS.Timeout | null = null
+  private last: BackendHealth | null = null
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), PROBE_INTERVAL_MS)
+    this.check()
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  getLast(): BackendHealth | null {
+    return this.last
+  }
+
+  /**
+   * Probe the backend now.
+   */
+  async check(): Promise<BackendHealth> {
+    const started = Date.now()
+    let health: BackendHealth
+
+    try {
+      await driftApi.health(PROBE_TIMEOUT_MS)
+      const latencyMs = Date.now() - started
+      health = { status: latencyMs > DEGRADED_LATENCY_MS ? 'degraded' : 'online', latencyMs, checkedAt: Date.now() }
+    } catch (error) {
+      const failure = toDriftError(error)
+      if (failure.reachedServer) {
+        // Reachable, but not healthy
+        health = { status: 'degraded', latencyMs: Date.now() - started, checkedAt: Date.now(), error: `HTTP ${failure.status}` }
+      } else {
+        health = { status: 'offline', latencyMs: null, checkedAt: Date.now(), error: failure.message }
+      }
+    }
+
+    if (health.status !== this.last?.status) {
+      console.log('[Health] Backend is', health.status, health.latencyMs !== null ? `(${health.latencyMs}ms)` : '')
+    }
+    this.last = health
+    emitEvent('backend-status', health)
+    this.emit('checked', health)
+    return health
+  }
+}
+
+// Singleton instance
+export const backendHealth = new BackendHealthMonitor()
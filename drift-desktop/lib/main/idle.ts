@@ -0,0 +1,100 @@
+import { powerMonitor } from 'electron'
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+// How often to sample system idle time
+const POLL_SECONDS = 15
+
+/**
+ * Pauses the active session when the user stops using the machine and
+ * resumes it on the next input. The pause is back-dated to the last input
+ * so idle time never counts towards the session.
+ * Sleep is handled the same way: the session pauses on suspend and, depending
+ * on the setting, resumes on wake or waits for the user to confirm. Locking the
+ * screen counts as idle right away, so the lock screen is never captured.
+ */
+class IdleMonitor {
+  private intervalId: NodeJS.Timeout | null = null
+  private powerEventsAttached = false
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), POLL_SECONDS * 1000)
+
+    if (!this.powerEventsAttached) {
+      this.powerEventsAttached = true
+      powerMonitor.on('suspend', () => this.onSuspend())
+      powerMonitor.on('resume', () => this.onWake())
+      powerMonitor.on('lock-screen', () => this.onLock())
+      powerMonitor.on('unlock-screen', () => this.onUnlock())
+    }
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  private async check(): Promise<void> {
+    if (!sessionManager.getActive()) return
+
+    const { idle } = await getSettings()
+    const idleSeconds = powerMonitor.getSystemIdleTime()
+    const lastInput = Date.now() - idleSeconds * 1000
+
+    if (sessionManager.getPauseReason() === 'idle') {
+      // Any input since the last sample ends the idle gap
+      if (idleSeconds < POLL_SECONDS) {
+        sessionManager.resume(lastInput)
+      }
+      return
+    }
+
+    if (idle.enabled && idleSeconds >= idle.thresholdMinutes * 60) {
+      sessionManager.pause('idle', lastInput)
+    }
+  }
+
+  private async onLock(): Promise<void> {
+    if (!sessionManager.getActive()) return
+    const { idle } = await getSettings()
+    if (idle.enabled && sessionManager.pause('locked')) {
+      console.log('[Idle] Screen locked, session paused')
+    }
+  }
+
+  private onUnlock(): void {
+    if (sessionManager.getPauseReason() === 'locked') {
+      sessionManager.resume()
+    }
+  }
+
+  private onSuspend(): void {
+    if (!sessionManager.getActive()) return
+    if (sessionManager.pause('sleep')) {
+      console.log('[Idle] System suspended, session paused')
+    }
+  }
+
+  private async onWake(): Promise<void> {
+    const active = sessionManager.getActive()
+    if (!active || sessionManager.getPauseReason() !== 'sleep') return
+
+    const { sleep } = await getSettings()
+    if (sleep.resumeOnWake === 'auto') {
+      sessionManager.resume()
+      return
+    }
+
+    const sleptSeconds = Math.floor((Date.now() - sessionManager.getPausedAt()!) / 1000)
+    emitEvent('session:wake-prompt', { sessionId: active.sessionId, sleptSeconds })
+    await notify('wokeFromSleep', 'Drift · Session paused', `${active.briefName} was paused while your computer slept. Open Drift to resume.`)
+  }
+}
+
+// Singleton instance
+export const idleMonitor = new IdleMonitor()
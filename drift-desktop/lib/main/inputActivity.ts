@@ -0,0 +1,70 @@
+import { powerMonitor } from 'electron'
+
+// How much input there was in one minute of a session
+export interface InputActivityBucket {
+  minute: number // start of the minute, epoch ms
+  activeSeconds: number // seconds with keyboard or mouse input, 0-60
+}
+
+/**
+ * Aggregate input activity for engagement heatmaps. Only the number of
+ * seconds with any input per minute is recorded: no keys, no positions,
+ * no distinction between keyboard and mouse. Uses the system idle time,
+ * so no input hooks are installed.
+ */
+class InputActivityMonitor {
+  private intervalId: NodeJS.Timeout | null = null
+  private buckets: InputActivityBucket[] = []
+  private paused = false
+
+  start(): void {
+    if (this.intervalId) return
+    this.buckets = []
+    this.paused = false
+    this.intervalId = setInterval(() => this.sample(), 1000)
+  }
+
+  pause(): void {
+    this.paused = true
+  }
+
+  resume(): void {
+    this.paused = false
+  }
+
+  /**
+   * Stop sampling and return the per-minute buckets.
+   */
+  stop(): InputActivityBucket[] {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+    const buckets = this.buckets
+    this.buckets = []
+    return buckets
+  }
+
+  getBuckets(): InputActivityBucket[] {
+    return this.buckets.map((b) => ({ ...b }))
+  }
+
+  private sample(): void {
+    if (this.paused) return
+
+    const now = Date.now()
+    const minute = now - (now % 60000)
+    let bucket = this.buckets[this.buckets.length - 1]
+    if (!bucket || bucket.minute !== minute) {
+      bucket = { minute, activeSeconds: 0 }
+      this.buckets.push(bucket)
+    }
+    // Idle time is reported in whole seconds; 0 means input within the last second
+    if (powerMonitor.getSystemIdleTime() === 0) {
+      bucket.activeSeconds = Math.min(60, bucket.activeSeconds + 1)
+    }
+  }
+}
+
+// Singleton instance
+export const inputActivity = new InputActivityMonitor()
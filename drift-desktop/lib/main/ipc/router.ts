@@ -1,24 +1,62 @@
-import { BrowserWindow, ipcMain, screen, desktopCapturer, shell } from 'electron'
+import { app, BrowserWindow, dialog, ipcMain, shell, Rectangle } from 'electron'
 import { appState } from '@/lib/state/AppStateMachine'
-import { ShortcutsHelper } from '@/lib/main/shortcuts'
-import { windowRegistry } from '@/lib/main/windowRegistry'
+import { ShortcutsHelper, ShortcutAction } from '@/lib/main/shortcuts'
+import { emitEvent, replayEvents, resumeEvents, ackEvents } from '@/lib/main/events'
 import { activityTracker } from '@/lib/main/activityTracker'
-import { createServer, Server } from 'http'
+import { captureAllowed } from '@/lib/main/privacy'
+import { sessionManager } from '@/lib/main/session'
+import { accounts } from '@/lib/main/accounts'
+import { profiles } from '@/lib/main/profiles'
+import { getAuthInfo, requireScope } from '@/lib/main/authInfo'
+import { getDeviceInfo } from '@/lib/main/device'
+import { driftApi, getApiBaseUrl, getAuthToken, getWebBaseUrl, recentApiCalls, resolveApiBaseUrl, toDriftError, SessionAnalysisRequest } from '@/lib/main/api'
+import { getSettings, reloadSettings, updateSettings, Settings } from '@/lib/main/settings'
+import { validatePlugins, withPluginDefaults } from '@/lib/main/plugins'
+import { endOfDayScheduler } from '@/lib/main/endOfDay'
+import { notify } from '@/lib/main/notifications'
+import { sessionHistory, StatsRange } from '@/lib/main/sessionHistory'
+import { detectOverlaps } from '@/lib/main/sessionOverlaps'
+import { searchSessions } from '@/lib/main/sessionSearch'
+import { mergeSessions, splitSession, updateSession, SessionPatch } from '@/lib/main/sessionEdit'
+import { appendOrgJournal, exportSessionMarkdown, exportSessionMarkers, exportSessionOrg, exportSessionsCsv, revealSessionFiles, MarkerFormat } from '@/lib/main/sessionExport'
+import { createShareLink, revokeShareLink } from '@/lib/main/shareLinks'
+import { exportData, importData, ImportConflictStrategy } from '@/lib/main/dataArchive'
+import { createBackup, restoreBackup } from '@/lib/main/profileBackup'
+import { retentionManager } from '@/lib/main/retention'
+import { jobScheduler } from '@/lib/main/jobs'
+import { sessionAttachments } from '@/lib/main/attachments'
+import { nativeMessaging } from '@/lib/main/nativeMessaging'
+import { getStorageUsage } from '@/lib/main/storage'
+import { getStore } from '@/lib/main/store'
+import { getDbInfo } from '@/lib/main/storeMigrations'
+import { focusTimer } from '@/lib/main/focus'
+import { calendarService } from '@/lib/main/calendar'
+import { getAppSnapshot } from '@/lib/main/snapshot'
+import { telemetry } from '@/lib/main/telemetry'
+import { crashReports } from '@/lib/main/crashReports'
+import { trayManager } from '@/lib/main/tray'
+import { miniRecorder } from '@/lib/main/miniRecorder'
+import { quickCapture } from '@/lib/main/quickCapture'
+import { registerWindowManagementIPC } from '@/lib/window/ipcEvents'
+import { briefCache } from '@/lib/main/briefs'
+import { liveSync } from '@/lib/main/liveSync'
+import { uploadQueue } from '@/lib/main/uploadQueue'
+import { backendHealth } from '@/lib/main/health'
+import { connectivity } from '@/lib/main/connectivity'
+import { controlApi } from '@/lib/main/controlApi'
+import { slackIntegration } from '@/lib/main/slack'
+import { screenCapture, setRedactionZones, validateCaptureQuality, CaptureQuality } from '@/lib/main/capture'
+import { capturePresets } from '@/lib/main/presets'
+import { checkPermissions, requestPermission, PermissionKind } from '@/lib/main/permissions'
+import { getCapabilities } from '@/lib/main/capabilities'
+import { goalTracker } from '@/lib/main/goals'
+import { recordingNudge } from '@/lib/main/nudge'
+import { renderAuthPage, resolveLocale, AuthPage, AuthErrorReason } from '@/lib/main/authPages'
+import { IncomingMessage, ServerResponse } from 'http'
+import { listenLoopback, LoopbackListener } from '@/lib/main/loopbackServer'
 import { parse } from 'url'
-import axios from 'axios'
-
-// Persistent store for auth - lazy loaded
-let store: any = null
-const getStore = async () => {
-  if (!store) {
-    const Store = (await import('electron-store')).default
-    store = new Store<{ authToken?: string; userEmail?: string }>()
-  }
-  return store
-}
-
-// Drift Backend API URL - use environment variable or default to GCP server
-const DRIFT_API_URL = process.env.DRIFT_API_URL || 'https://test.usehavoc.com/api'
+import { randomBytes, timingSafeEqual } from 'crypto'
+import { join } from 'path'
 
 interface IpcContext {
   shortcutsHelper: ShortcutsHelper
@@ -42,19 +80,23 @@ export function registerIpcHandlers(ctx: IpcContext): void {
   } = ctx
 
   /* ---------------- generic helpers ---------------- */
-  const broadcast = (channel: string, ...args: any[]) => {
-    windowRegistry.broadcast(channel, ...args)
-  }
-
   /* ---------------- basic handlers ---------------- */
+  // Full UI state in one call, for hydrating after a reload
+  ipcMain.handle('app:get-snapshot', () => getAppSnapshot())
+  ipcMain.handle('events:replay', (_evt, sinceSeq: number) => replayEvents(Number(sinceSeq) || 0))
+  ipcMain.handle('events:resume', (evt, sinceSeq: number) => resumeEvents(evt.sender, Number(sinceSeq) || 0))
+  ipcMain.handle('events:ack', (evt, seq: number) => ackEvents(evt.sender, Number(seq) || 0))
+
   ipcMain.handle('get-invisibility-state', () => getIsInvisible())
   ipcMain.handle('shortcuts:get', () => shortcutsHelper.getShortcuts())
   ipcMain.handle('shortcuts:set', (_evt, next) => shortcutsHelper.setShortcuts(next))
+  ipcMain.handle('shortcuts:set-one', (_evt, action: ShortcutAction, accelerator: string) =>
+    shortcutsHelper.setShortcut(action, accelerator)
+  )
   ipcMain.handle('shortcuts:reset', () => shortcutsHelper.resetShortcuts())
 
-  ipcMain.on('quit-app', () => {
-    import('electron').then(({ app }) => app.quit())
-  })
+  // Submits the running session and waits for uploads before quitting
+  ipcMain.on('quit-app', () => trayManager.quit())
 
   ipcMain.on('set-current-input-value', (_event, value: string) => {
     setCurrentInputValue(value)
@@ -72,99 +114,273 @@ export function registerIpcHandlers(ctx: IpcContext): void {
   })
 
   /* ---------------- Auth handlers ---------------- */
-  let authServer: Server | null = null
-  ipcMain.on('open-auth-url', (_evt, url: string) => {
+  let authServer: LoopbackListener | null = null
+  let authTimeout: NodeJS.Timeout | null = null
+
+  // How long the callback server waits for the browser before giving up
+  const AUTH_CALLBACK_TIMEOUT_MS = 300_000
+
+  // The callback must carry the nonce handed to this sign-in attempt
+  const nonceMatches = (expected: string, received: unknown): boolean => {
+    if (typeof received !== 'string') return false
+    const a = Buffer.from(expected)
+    const b = Buffer.from(received)
+    return a.length === b.length && timingSafeEqual(a, b)
+  }
+
+  // Browsers navigating from the web app send its origin (or nothing); anything else is a forged request
+  const isAllowedOrigin = (value: string | undefined, webOrigin: string): boolean => {
+    if (!value) return true
+    try {
+      return new URL(value).origin === webOrigin
+    } catch {
+      return false
+    }
+  }
+
+  const sendAuthPage = async (
+    req: IncomingMessage,
+    res: ServerResponse,
+    status: number,
+    page: AuthPage,
+    reason?: AuthErrorReason
+  ) => {
+    const { locale } = await getSettings()
+    res.writeHead(status, { 'Content-Type': 'text/html; charset=utf-8' })
+    res.end(await renderAuthPage(page, resolveLocale(locale.language, req.headers['accept-language']), reason))
+  }
+
+  // Only a definite 401/403 counts as rejected; if the backend is unreachable the token is kept
+  const isTokenRejected = async (token: string): Promise<boolean> => {
+    try {
+      await driftApi.sync({ token, timeout: 5000, retry: false })
+      return false
+    } catch (error) {
+      return toDriftError(error).kind === 'rejected'
+    }
+  }
+
+  ipcMain.on('open-auth-url', async (_evt, url: string) => {
+    let webOrigin: string
+    try {
+      webOrigin = new URL(await getWebBaseUrl()).origin
+    } catch (error: any) {
+      console.error('[Auth]', error.message)
+      emitEvent('auth-error', { reason: 'unconfigured' })
+      return
+    }
+
     // Stop any existing server
+    if (authTimeout) {
+      clearTimeout(authTimeout)
+      authTimeout = null
+    }
     if (authServer) {
       authServer.close()
       authServer = null
     }
 
-    // Create callback server
-    authServer = createServer(async (req, res) => {
+    const nonce = randomBytes(32).toString('hex')
+    let accepted = false
+
+    // Callback handler, served on both loopback addresses
+    const handler = async (req: IncomingMessage, res: ServerResponse) => {
       const urlParts = parse(req.url || '', true)
 
-      if (urlParts.pathname === '/callback') {
-        const token = urlParts.query.token as string
-        const email = urlParts.query.email as string
-
-        if (token) {
-          // Store persistently
-          const s = await getStore()
-          s.set('authToken', token)
-          s.set('userEmail', email || 'user@drift.app')
-
-          res.writeHead(200, { 'Content-Type': 'text/html' })
-          res.end(`
-            <html>
-              <head>
-                <style>
-                  body { 
-                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
-                    display: flex; 
-                    align-items: center; 
-                    justify-content: center; 
-                    height: 100vh; 
-                    margin: 0;
-                    background: linear-gradient(135deg, #1a1a2e 0%, #16213e 100%);
-                    color: white;
-                  }
-                  .container { text-align: center; }
-                  h1 { color: #4ade80; }
-                </style>
-              </head>
-              <body>
-                <div class="container">
-                  <h1>✓ Connected to Drift!</h1>
-                  <p>You can close this tab and return to the app.</p>
-                </div>
-              </body>
-            </html>
-          `)
-
-          broadcast('auth-token-received', { token, email: email || 'user@drift.app' })
-
-          setTimeout(() => {
-            if (authServer) {
-              authServer.close()
-              authServer = null
-            }
-          }, 1000)
-        } else {
-          res.writeHead(400, { 'Content-Type': 'text/plain' })
-          res.end('Missing token')
-        }
-      } else {
+      if (req.method !== 'GET' || urlParts.pathname !== '/callback' || accepted) {
         res.writeHead(404, { 'Content-Type': 'text/plain' })
         res.end('Not found')
+        return
       }
-    })
 
-    authServer.listen(0, '127.0.0.1', () => {
-      const address = authServer!.address()
-      if (address && typeof address === 'object') {
-        const port = address.port
-        const callbackUrl = `http://localhost:${port}/callback`
-        const authUrl = `${url}?callback=${encodeURIComponent(callbackUrl)}`
-        shell.openExternal(authUrl)
+      if (
+        !isAllowedOrigin(req.headers.origin, webOrigin) ||
+        !isAllowedOrigin(req.headers.referer, webOrigin)
+      ) {
+        console.warn('[Auth] Rejected callback from unexpected origin')
+        emitEvent('auth-error', { reason: 'origin' })
+        await sendAuthPage(req, res, 403, 'error', 'origin')
+        return
       }
-    })
+
+      if (!nonceMatches(nonce, urlParts.query.nonce)) {
+        console.warn('[Auth] Rejected callback with invalid nonce')
+        emitEvent('auth-error', { reason: 'nonce' })
+        await sendAuthPage(req, res, 403, 'error', 'nonce')
+        return
+      }
+
+      const token = urlParts.query.token as string
+      const email = urlParts.query.email as string
+
+      if (!token) {
+        emitEvent('auth-error', { reason: 'missing-token' })
+        await sendAuthPage(req, res, 400, 'error', 'missing-token')
+        return
+      }
+
+      // Single use: later requests get a 404, even while this one is still being handled
+      accepted = true
+
+      if (await isTokenRejected(token)) {
+        console.warn('[Auth] Backend rejected the callback token')
+        // Let the browser retry with a fresh token within the same window
+        accepted = false
+        emitEvent('auth-error', { reason: 'rejected' })
+        await sendAuthPage(req, res, 401, 'error', 'rejected')
+        return
+      }
+
+      if (authTimeout) {
+        clearTimeout(authTimeout)
+        authTimeout = null
+      }
+
+      // Store persistently, one account per email
+      const accountEmail = email || 'user@drift.app'
+      await accounts.upsert(accountEmail, accountEmail, token)
+      liveSync.reconnect()
+      // Sessions ended while signed out are waiting for a token
+      void uploadQueue.flush()
+
+      await sendAuthPage(req, res, 200, 'success')
+
+      emitEvent('auth-token-received', { token, email: email || 'user@drift.app' })
+
+      setTimeout(() => {
+        listener.close()
+        if (authServer === listener) authServer = null
+      }, 1000)
+    }
+
+    const { authCallback } = await getSettings()
+    const bound = await listenLoopback(handler, authCallback.portFrom, authCallback.portTo)
+    if ('error' in bound) {
+      emitEvent('auth-server-error', bound)
+      return
+    }
+    const listener = bound
+    authServer = listener
+    emitEvent('auth-server-listening', { port: listener.port, hosts: listener.hosts })
+
+    authTimeout = setTimeout(() => {
+      authTimeout = null
+      if (accepted || authServer !== listener) return
+      console.warn('[Auth] No callback received, closing server')
+      listener.close()
+      authServer = null
+      emitEvent('auth-timeout', { timeoutSeconds: AUTH_CALLBACK_TIMEOUT_MS / 1000 })
+    }, AUTH_CALLBACK_TIMEOUT_MS)
+
+    const callbackUrl = `http://localhost:${listener.port}/callback?nonce=${nonce}`
+    // Sign in against the web app of the selected environment
+    const authPage = `${await getWebBaseUrl()}${new URL(url).pathname}`
+    const authUrl = `${authPage}?callback=${encodeURIComponent(callbackUrl)}`
+    shell.openExternal(authUrl)
   })
 
+  // Lets the web app list this installation and revoke it
+  const registerDevice = async (token: string) => {
+    try {
+      await driftApi.registerDevice(token, await getDeviceInfo())
+    } catch (error: any) {
+      console.error('[Auth] Device registration failed:', error.message)
+    }
+  }
+
   ipcMain.handle('store-auth-token', async (_evt, token: string | null) => {
-    const s = await getStore()
     if (token === null) {
-      s.delete('authToken')
-      s.delete('userEmail')
+      const active = await accounts.getActive()
+      if (active) await accounts.remove(active.name)
     } else {
-      s.set('authToken', token)
+      await accounts.setActiveToken(token)
+      void registerDevice(token)
     }
+    liveSync.reconnect()
     return true
   })
 
-  ipcMain.handle('get-auth-token', async () => {
-    const s = await getStore()
-    return s.get('authToken') || null
+  ipcMain.handle('get-auth-token', () => getAuthToken())
+
+  // Account, user and granted scopes of the active token
+  ipcMain.handle('auth:get-info', () => getAuthInfo())
+
+  ipcMain.handle('auth:logout', async () => {
+    const active = await accounts.getActive()
+    if (!active) return { ok: true, revoked: false }
+
+    // Submit a running session while the token is still valid
+    if (sessionManager.getActive()) {
+      await sessionManager.end()
+    }
+
+    // Revoke server-side; a failure here must not keep the user signed in
+    let revoked = false
+    try {
+      await driftApi.logout(active.token)
+      revoked = true
+    } catch (error: any) {
+      console.error('[Auth] Token revocation failed:', error.message)
+    }
+
+    await accounts.remove(active.name)
+    liveSync.reconnect()
+    emitEvent('auth-logged-out', { account: active.name, revoked })
+    return { ok: true, revoked }
+  })
+
+  ipcMain.handle('auth:list-accounts', () => accounts.list())
+
+  ipcMain.handle('auth:switch-account', async (_evt, name: string) => {
+    // Uploads go out with the active account's token, so never switch mid-session
+    if (sessionManager.getActive()) {
+      return { error: 'End the active session before switching accounts' }
+    }
+    if (!(await accounts.switchTo(name))) {
+      return { error: `Unknown account: ${name}` }
+    }
+    liveSync.reconnect()
+    emitEvent('auth:account-switched', { name })
+    return { ok: true }
+  })
+
+  ipcMain.handle('profiles:list', () => profiles.list())
+  ipcMain.handle('profiles:create', (_evt, name: string) => profiles.create(name || ''))
+
+  // Each profile has its own store, settings, accounts and data key; switching reloads them in place
+  ipcMain.handle('profiles:switch', async (_evt, id: string) => {
+    if (sessionManager.getActive()) {
+      return { error: 'End the active session before switching profiles' }
+    }
+    // Don't let a queued upload finish against the other profile's store
+    await uploadQueue.drain()
+
+    const prev = await getSettings()
+    const profile = profiles.switchTo(id)
+    if (!profile) {
+      return { error: `Unknown profile: ${id}` }
+    }
+    await reloadSettings(prev)
+    liveSync.reconnect()
+    void uploadQueue.flush()
+    emitEvent('profile:switched', profile)
+    return { ok: true, profile }
+  })
+
+  ipcMain.handle('auth:remove-account', async (_evt, name: string) => {
+    const active = await accounts.getActive()
+    if (active?.name === name && sessionManager.getActive()) {
+      return { error: 'End the active session before removing this account' }
+    }
+    if (!(await accounts.remove(name))) {
+      return { error: `Unknown account: ${name}` }
+    }
+    const next = await accounts.getActive()
+    if (next?.name !== active?.name) {
+      liveSync.reconnect()
+      emitEvent('auth:account-switched', { name: next?.name ?? null })
+    }
+    return { ok: true }
   })
 
   /* ---------------- Chat handlers (connect to Drift backend) ---------------- */
@@ -175,45 +391,27 @@ export function registerIpcHandlers(ctx: IpcContext): void {
     setCurrentInputValue(input)
     apiRequestController = new AbortController()
 
+    const missing = await requireScope('chat')
+    if (missing) {
+      emitEvent('api-error', missing.error)
+      return
+    }
+
     try {
-      // 1. Capture screenshot
-      const primaryDisplay = screen.getPrimaryDisplay()
-      const sources = await desktopCapturer.getSources({
-        types: ['screen'],
-        thumbnailSize: primaryDisplay.size
-      })
-      const primaryScreenSource =
-        sources.find((source) => source.display_id === String(primaryDisplay.id)) || sources[0]
-
-      let screenshotBase64 = ''
-      if (primaryScreenSource) {
-        const screenshotPng = primaryScreenSource.thumbnail.toPNG()
-        if (screenshotPng && screenshotPng.length > 0) {
-          screenshotBase64 = screenshotPng.toString('base64')
-        }
-      }
+      // 1. Capture screenshot (unless screenshots must stay on this machine)
+      // Never while an excluded or sensitive app (password manager, banking) is in front
+      const { upload } = await getSettings()
+      const allowed = upload.screenshots && (await captureAllowed((await activityTracker.getActiveWindow())?.app ?? null))
+      const frame = allowed ? await screenCapture.captureFrame(1, 80) : null
+      const screenshotBase64 = frame ? frame.toString('base64') : ''
 
       // 2. Send to Drift backend
-      const s = await getStore()
-      const authToken = s.get('authToken')
-      const response = await axios.post(`${DRIFT_API_URL}/chat`, {
-        message: input,
-        screenshot: screenshotBase64
-      }, {
-        headers: {
-          'Content-Type': 'application/json',
-          ...(authToken ? { Authorization: `Bearer ${authToken}` } : {})
-        },
-        signal: apiRequestController.signal,
-        timeout: 30000
-      })
-
-      const data = response.data
-      broadcast('api-success')
-      broadcast('chat:chunk', { text: data.response || data.message })
+      const data = await driftApi.chat(input, screenshotBase64, apiRequestController.signal)
+      emitEvent('api-success')
+      emitEvent('chat:chunk', { text: data.response || data.message })
     } catch (error: any) {
       console.error('Drift API error:', error)
-      broadcast('api-error', String(error.message || error))
+      emitEvent('api-error', String(error.message || error))
     }
   })
 
@@ -227,268 +425,101 @@ export function registerIpcHandlers(ctx: IpcContext): void {
   })
 
   /* ---------------- Session Management (connect to Drift backend) ---------------- */
-  let activeSessionId: string | null = null
-  let activeSessionBriefId: string | null = null
-  let activeSessionBriefName: string | null = null
+  ipcMain.handle('session:start', async (_evt, briefId: string, role: string, captureTarget?: string) => {
+    const missing = await requireScope('sessions:write')
+    if (missing) return missing
+    return sessionManager.startAfterCountdown(briefId, role, captureTarget ?? null)
+  })
 
-  ipcMain.handle('session:start', async (_evt, briefId: string, role: string) => {
-    // Check if session already active
-    if (activeSessionId) {
-      return { error: 'Session already active', sessionId: activeSessionId }
-    }
-    
-    const s = await getStore()
-    const authToken = s.get('authToken')
-    
-    if (!authToken) {
-      return { error: 'Not authenticated' }
-    }
-    
-    try {
-      const response = await axios.post(`${DRIFT_API_URL}/desktop/session/start`, {
-        briefId,
-        role
-      }, {
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${authToken}`
-        },
-        timeout: 10000
-      })
-      
-      const data = response.data
-      
-      // ONLY set state after successful response
-      activeSessionId = data.sessionId
-      activeSessionBriefId = data.briefId || briefId
-      activeSessionBriefName = data.briefName || 'Project'
-      
-      // NOW start tracking
-      activityTracker.start(role, (activity) => {
-        broadcast('session:activity', activity)
-      })
-      console.log('[Session] Started tracking activities for role:', role)
-      
-      // Start continuous screen analysis AFTER session confirmed
-      currentProjectName = data.briefName || 'Project'
-      currentProjectDescription = null
-      previousInsights = []
-      
-      if (screenAnalysisInterval) {
-        clearInterval(screenAnalysisInterval)
-      }
-      
-      // Start continuous screen analysis
-      const analyzeScreen = async () => {
-        const s = await getStore()
-        const authToken = s.get('authToken')
-        
-        if (!authToken || !activityTracker.getStatus().isTracking) {
-          return
-        }
-        
-        try {
-          const primaryDisplay = screen.getPrimaryDisplay()
-          const sources = await desktopCapturer.getSources({
-            types: ['screen'],
-            thumbnailSize: { width: Math.floor(primaryDisplay.size.width / 3), height: Math.floor(primaryDisplay.size.height / 3) }
-          })
-          
-          if (sources.length === 0) return
-          
-          const screenshot = sources[0].thumbnail.toJPEG(50).toString('base64')
-          
-          const response = await fetch(`${DRIFT_API_URL}/desktop/session/analyze-screen`, {
-            method: 'POST',
-            headers: {
-              'Content-Type': 'application/json',
-              'Authorization': `Bearer ${authToken}`
-            },
-            body: JSON.stringify({
-              screenshot,
-              projectName: currentProjectName,
-              projectDescription: currentProjectDescription,
-              previousInsights: previousInsights.slice(-10)
-            })
-          })
-          
-          if (!response.ok) return
-          
-          const analysisData = await response.json()
-          
-          if (analysisData.bullets && analysisData.bullets.length > 0 && !analysisData.skip) {
-            previousInsights.push(...analysisData.bullets)
-            if (previousInsights.length > 20) {
-              previousInsights = previousInsights.slice(-20)
-            }
-            broadcast('session:screen-insight', { bullets: analysisData.bullets, timestamp: Date.now() })
-          }
-        } catch (error) {
-          console.error('[ScreenAnalysis] Error:', error)
-        }
-      }
-      
-      screenAnalysisInterval = setInterval(analyzeScreen, 15000)
-      analyzeScreen() // Run immediately
-      
-      broadcast('session:started', data)
-      return data
-      
-    } catch (error: any) {
-      // Cleanup on error
-      activeSessionId = null
-      activeSessionBriefId = null
-      activeSessionBriefName = null
-      
-      console.error('Session start error:', error.message)
-      if (error.response) {
-        return { error: `API Error: ${error.response.status} - ${JSON.stringify(error.response.data)}` }
-      } else if (error.request) {
-        return { error: 'Keine Verbindung zum Server möglich' }
-      }
-      return { error: error.message || String(error) }
-    }
+  ipcMain.handle('session:cancel-countdown', () => sessionManager.cancelCountdown())
+
+  ipcMain.handle('session:switch-brief', (_evt, briefId: string) => sessionManager.switchBrief(briefId))
+
+  // Displays and windows a session can be restricted to
+  ipcMain.handle('capture:list-targets', () => screenCapture.listTargets())
+
+  // Blacked-out screen areas, per display id (see capture:list-targets for display ids)
+  ipcMain.handle('capture:get-redaction-zones', async () => (await getSettings()).redaction.zones)
+  ipcMain.handle('capture:set-redaction-zones', (_evt, displayId: string, zones: Rectangle[]) => {
+    return setRedactionZones(displayId, zones || [])
   })
 
-  ipcMain.handle('session:end', async (_evt, _activities?: any[], summary?: string) => {
-    if (!activeSessionId) {
-      return { error: 'No active session' }
-    }
-    
-    // Stop screen analysis
-    if (screenAnalysisInterval) {
-      clearInterval(screenAnalysisInterval)
-      screenAnalysisInterval = null
-    }
-    
-    const s = await getStore()
-    const authToken = s.get('authToken')
-    
-    if (!authToken) {
-      return { error: 'Not authenticated' }
-    }
-    
-    try {
-      // Stop activity tracking and get results
-      const { activities, summary: activitySummary, notes } = activityTracker.stop()
-      console.log('[Session] Stopped tracking, got', activities.length, 'relevant activities,', notes.length, 'notes')
-      
-      // Generate summary from activities + notes
-      let generatedSummary = summary
-      if (!generatedSummary) {
-        const summaryParts: string[] = []
-        
-        // Add activity summary
-        for (const item of activitySummary.slice(0, 3)) {
-          const mins = Math.floor(item.totalDuration / 60)
-          if (mins > 0) {
-            if (item.files.length > 0) {
-              summaryParts.push(`${item.app}: ${item.files.slice(0, 3).join(', ')} (${mins}m)`)
-            } else {
-              summaryParts.push(`${item.app} (${mins}m)`)
-            }
-          }
-        }
-        
-        // Add manual notes
-        if (notes.length > 0) {
-          summaryParts.push('Notes: ' + notes.map(n => n.text).join(', '))
-        }
-        
-        generatedSummary = summaryParts.join(' | ')
-      }
-      
-      // Convert to API format (include screenshots for relevant activities)
-      const apiActivities = activities.map(a => ({
-        app: a.app,
-        title: a.title,
-        file: a.file,
-        duration: a.duration,
-        timestamp: a.timestamp,
-        screenshot: a.screenshot // Include screenshot if captured
-      }))
-      
-      const response = await axios.post(`${DRIFT_API_URL}/desktop/session/end`, {
-        sessionId: activeSessionId,
-        activities: apiActivities,
-        notes: notes.map(n => n.text),
-        summary: generatedSummary
-      }, {
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${authToken}`
-        },
-        timeout: 15000
-      })
-      
-      const data = response.data
-      const briefId = activeSessionBriefId
-      const briefName = activeSessionBriefName
-      activeSessionId = null
-      activeSessionBriefId = null
-      activeSessionBriefName = null
-      broadcast('session:ended', { ...data, activitySummary, notes, briefId, briefName })
-      return { ...data, activitySummary, notes, briefId, briefName }
-    } catch (error: any) {
-      console.error('Session end error:', error.message)
-      
-      // Cleanup screen analysis even on error
-      if (screenAnalysisInterval) {
-        clearInterval(screenAnalysisInterval)
-        screenAnalysisInterval = null
-      }
-      
-      // Stop activity tracking
-      activityTracker.stop()
-      
-      // Reset session state
-      const briefId = activeSessionBriefId
-      const briefName = activeSessionBriefName
-      activeSessionId = null
-      activeSessionBriefId = null
-      activeSessionBriefName = null
-      
-      // Provide detailed error
-      if (error.code === 'ECONNABORTED') {
-        return { error: 'Request timeout - server nicht erreichbar', briefId, briefName }
-      } else if (error.response) {
-        return { error: `Server error: ${error.response.status}`, briefId, briefName }
-      } else if (error.request) {
-        return { error: 'Keine Verbindung zum Server', briefId, briefName }
-      }
-      
-      return { error: String(error.message || error), briefId, briefName }
-    }
+  // Applies to the running session right away
+  ipcMain.handle('capture:set-quality', async (_evt, quality: CaptureQuality) => {
+    const error = validateCaptureQuality(quality)
+    if (error) return { error }
+    await updateSettings({ capture: quality })
+    activityTracker.setCaptureQuality(quality)
+    return { ok: true }
   })
 
-  ipcMain.handle('session:get-active', () => activeSessionId)
-  
+  // Capture presets; a brief's preset is applied whenever a recording for it starts
+  ipcMain.handle('presets:list', () => capturePresets.list())
+  ipcMain.handle('presets:save', (_evt, preset) => capturePresets.save(preset))
+  ipcMain.handle('presets:remove', (_evt, id: string) => capturePresets.remove(id))
+  ipcMain.handle('presets:set-default', (_evt, presetId: string | null, briefId?: string) => {
+    return capturePresets.setDefault(presetId, briefId)
+  })
+
+  ipcMain.handle('session:set-capture-region', (_evt, region: Rectangle | null) => {
+    return sessionManager.setCaptureRegion(region)
+  })
+
+  ipcMain.handle('session:end', (_evt, _activities?: any[], summary?: string) => {
+    return sessionManager.end(summary)
+  })
+
+  ipcMain.handle('session:pause', () => sessionManager.pause('manual'))
+  ipcMain.handle('session:resume', () => sessionManager.resume())
+
+  /* ---------------- Mini recorder ---------------- */
+  ipcMain.handle('mini-recorder:show', () => {
+    miniRecorder.show()
+    return { ok: true }
+  })
+  ipcMain.handle('mini-recorder:hide', () => {
+    miniRecorder.hide()
+    return { ok: true }
+  })
+
+  /* ---------------- Quick capture ---------------- */
+  ipcMain.handle('quick-capture:show', () => {
+    quickCapture.show()
+    return { ok: true }
+  })
+  ipcMain.handle('quick-capture:hide', () => {
+    quickCapture.hide()
+    return { ok: true }
+  })
+  // Files the note against the active session, or as a standalone note; closes the input window
+  ipcMain.handle('quick-capture:submit', async (_evt, text: string) => {
+    const result = await quickCapture.capture(text)
+    if (!('error' in result)) quickCapture.hide()
+    return result
+  })
+  ipcMain.handle('notes:list-standalone', () => quickCapture.listStandalone())
+  ipcMain.handle('notes:remove-standalone', (_evt, id: string) => quickCapture.removeStandalone(id))
+
+  registerWindowManagementIPC()
+
+  ipcMain.handle('session:get-active', () => sessionManager.getActive()?.sessionId ?? null)
+
+  ipcMain.handle('session:get-state', () => sessionManager.getRecordingState())
+
   ipcMain.handle('session:get-activities', () => {
     return activityTracker.getActivities()
   })
   
   ipcMain.handle('session:add-note', async (_evt, text: string) => {
-    const s = await getStore()
-    const authToken = s.get('authToken')
+    const authToken = await getAuthToken()
     
     // If authenticated, process the note through AI
     if (authToken && text.length > 2) {
       try {
-        const response = await axios.post(`${DRIFT_API_URL}/desktop/session/process-note`, {
-          note: text,
-          projectName: currentProjectName
-        }, {
-          headers: {
-            'Content-Type': 'application/json',
-            'Authorization': `Bearer ${authToken}`
-          },
-          timeout: 5000
-        })
-        
-        const processed = response.data
+        const processed = await driftApi.processNote(text, sessionManager.getProjectName())
         if (processed.bullet) {
-          activityTracker.addNote(processed.bullet)
-          broadcast('session:note-processed', { original: text, bullet: processed.bullet })
+          sessionManager.addNote(processed.bullet)
+          emitEvent('session:note-processed', { original: text, bullet: processed.bullet })
           return { ok: true, bullet: processed.bullet, processed: true }
         }
       } catch (err) {
@@ -498,7 +529,7 @@ export function registerIpcHandlers(ctx: IpcContext): void {
     }
     
     // Fallback: add original note
-    activityTracker.addNote(text)
+    sessionManager.addNote(text)
     return { ok: true, bullet: text, processed: false }
   })
   
@@ -516,108 +547,22 @@ export function registerIpcHandlers(ctx: IpcContext): void {
     return activityTracker.getStatus()
   })
 
-  // Continuous screen analysis state
-  let screenAnalysisInterval: NodeJS.Timeout | null = null
-  let previousInsights: string[] = []
-  let currentProjectName: string | null = null
-  let currentProjectDescription: string | null = null
-
   // Start continuous screen analysis
-  ipcMain.handle('session:start-screen-analysis', async (_evt, projectInfo?: { name: string; description?: string }) => {
-    if (screenAnalysisInterval) {
-      clearInterval(screenAnalysisInterval)
-    }
-    
-    previousInsights = []
-    currentProjectName = projectInfo?.name || null
-    currentProjectDescription = projectInfo?.description || null
-    
-    const analyzeScreen = async () => {
-      const s = await getStore()
-      const authToken = s.get('authToken')
-      
-      if (!authToken || !activityTracker.getStatus().isTracking) {
-        return
-      }
-      
-      try {
-        // Capture current screen
-        const primaryDisplay = screen.getPrimaryDisplay()
-        const sources = await desktopCapturer.getSources({
-          types: ['screen'],
-          thumbnailSize: { width: Math.floor(primaryDisplay.size.width / 3), height: Math.floor(primaryDisplay.size.height / 3) }
-        })
-        
-        if (sources.length === 0) return
-        
-        const screenshot = sources[0].thumbnail.toJPEG(50).toString('base64')
-        
-        // Send to backend for analysis
-        const response = await fetch(`${DRIFT_API_URL}/desktop/session/analyze-screen`, {
-          method: 'POST',
-          headers: {
-            'Content-Type': 'application/json',
-            'Authorization': `Bearer ${authToken}`
-          },
-          body: JSON.stringify({
-            screenshot,
-            projectName: currentProjectName,
-            projectDescription: currentProjectDescription,
-            previousInsights: previousInsights.slice(-10)
-          })
-        })
-        
-        if (!response.ok) return
-        
-        const data = await response.json()
-        
-        if (data.bullets && data.bullets.length > 0 && !data.skip) {
-          // Add to previous insights to avoid repetition
-          previousInsights.push(...data.bullets)
-          
-          // Keep only last 20 insights
-          if (previousInsights.length > 20) {
-            previousInsights = previousInsights.slice(-20)
-          }
-          
-          // Broadcast to renderer
-          broadcast('session:screen-insight', {
-            bullets: data.bullets,
-            timestamp: Date.now()
-          })
-          
-          console.log('[ScreenAnalysis] New insights:', data.bullets)
-        }
-      } catch (error) {
-        console.error('[ScreenAnalysis] Error:', error)
-      }
-    }
-    
+  ipcMain.handle('session:start-screen-analysis', (_evt, projectInfo?: { name: string; description?: string }) => {
     // Run analysis every 10 seconds
-    screenAnalysisInterval = setInterval(analyzeScreen, 10000)
-    
-    // Run immediately
-    analyzeScreen()
-    
+    sessionManager.startScreenAnalysis(10000, projectInfo)
     return { ok: true }
   })
 
   // Stop screen analysis
   ipcMain.handle('session:stop-screen-analysis', () => {
-    if (screenAnalysisInterval) {
-      clearInterval(screenAnalysisInterval)
-      screenAnalysisInterval = null
-    }
-    previousInsights = []
+    sessionManager.stopScreenAnalysis()
     return { ok: true }
   })
 
   // Get live AI insight about current session
   ipcMain.handle('session:get-live-insight', async () => {
-    const s = await getStore()
-    const authToken = s.get('authToken')
-    
-    if (!authToken) {
+    if (!(await getAuthToken())) {
       return { error: 'Not authenticated' }
     }
     
@@ -631,72 +576,31 @@ export function registerIpcHandlers(ctx: IpcContext): void {
     }
     
     try {
-      const response = await fetch(`${DRIFT_API_URL}/desktop/session/live-insight`, {
-        method: 'POST',
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${authToken}`
-        },
-        body: JSON.stringify({
-          activities: activities.slice(-10).map(a => ({
-            app: a.app,
-            title: a.title,
-            file: a.file,
-            duration: a.duration
-          })),
-          notes: notes.slice(-5).map(n => n.text),
-          totalDuration: status.activityCount * 3 // rough estimate in seconds
-        })
+      const data = await driftApi.liveInsight({
+        activities: activities.slice(-10).map(a => ({
+          app: a.app,
+          title: a.title,
+          file: a.file,
+          duration: a.duration
+        })),
+        notes: notes.slice(-5).map(n => n.text),
+        totalDuration: status.activityCount * 3 // rough estimate in seconds
       })
-      
-      if (!response.ok) {
-        return { error: `Insight failed: ${response.status}` }
-      }
-      
-      const data = await response.json()
       return { insight: data.insight }
     } catch (error) {
-      console.error('Live insight error:', error)
-      return { error: String(error) }
+      const failure = toDriftError(error)
+      console.error('Live insight error:', failure.message)
+      return { error: failure.reachedServer ? `Insight failed: ${failure.status}` : failure.message }
     }
   })
 
   // Analyze session and update workspace tasks
-  ipcMain.handle('session:analyze', async (_evt, data: {
-    sessionId: string
-    submissionId?: string
-    briefId?: string
-    activities?: Array<{ app: string; totalDuration: number; files: string[] }>
-    notes?: Array<{ text: string; timestamp: number }>
-    summaryLines: string[]
-    durationMinutes: number
-  }) => {
-    const s = await getStore()
-    const authToken = s.get('authToken')
-    
-    if (!authToken) {
-      return { error: 'Not authenticated' }
-    }
-    
+  ipcMain.handle('session:analyze', async (_evt, data: SessionAnalysisRequest) => {
     try {
-      const response = await fetch(`${DRIFT_API_URL}/desktop/session/analyze`, {
-        method: 'POST',
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${authToken}`
-        },
-        body: JSON.stringify(data)
-      })
-      
-      if (!response.ok) {
-        const errorText = await response.text()
-        throw new Error(`Session analysis failed: ${response.status} - ${errorText}`)
-      }
-      
-      const result = await response.json()
+      const result = await driftApi.analyzeSession(data)
       
       // Broadcast to web clients that workspace was updated
-      broadcast('workspace:updated', {
+      emitEvent('workspace:updated', {
         sessionId: data.sessionId,
         briefId: data.briefId,
         ...result
@@ -704,64 +608,292 @@ export function registerIpcHandlers(ctx: IpcContext): void {
       
       return result
     } catch (error) {
-      console.error('Session analysis error:', error)
-      return { error: String(error) }
+      const failure = toDriftError(error)
+      console.error('Session analysis error:', failure.message)
+      return { error: `Session analysis failed: ${failure.message}` }
+    }
+  })
+
+  /* ---------------- Local session history ---------------- */
+  ipcMain.handle('sessions:detect-overlaps', async (_evt, range?: { from?: number; to?: number }) => {
+    return detectOverlaps(await sessionHistory.list(range))
+  })
+
+  ipcMain.handle('goals:get-progress', () => goalTracker.getProgress())
+  ipcMain.handle('nudge:snooze', (_evt, minutes?: number) => recordingNudge.snooze(minutes))
+  ipcMain.handle('sessions:get-stats', (_evt, range: StatsRange = 'week') => sessionHistory.getStats(range))
+  ipcMain.handle('sessions:search', (_evt, query: string, limit?: number) => searchSessions(query || '', limit))
+  ipcMain.handle('sessions:get-timeline', (_evt, sessionId: string) => sessionManager.getTimeline(sessionId))
+  ipcMain.handle('sessions:append-note', (_evt, text: string, sessionId?: string) => {
+    return sessionManager.appendNote(text, sessionId)
+  })
+
+  // Deleting moves a session to the trash; it is purged after TRASH_DAYS unless restored
+  ipcMain.handle('sessions:delete', async (_evt, sessionId: string) => {
+    if (sessionManager.getActive()?.sessionId === sessionId) {
+      return { error: 'End the active session first' }
+    }
+    return (await sessionHistory.trash(sessionId)) ? { ok: true } : { error: `Unknown session: ${sessionId}` }
+  })
+  ipcMain.handle('sessions:restore', async (_evt, sessionId: string) => {
+    const record = await sessionHistory.restore(sessionId)
+    return record ? { ok: true, session: record } : { error: `Not in the trash: ${sessionId}` }
+  })
+  ipcMain.handle('sessions:list-trash', () => sessionHistory.listTrash())
+  ipcMain.handle('sessions:empty-trash', async () => ({ ok: true, purged: await sessionHistory.emptyTrash() }))
+
+  // Clean up double starts and forgotten stops; sessions still waiting to upload can't be changed
+  ipcMain.handle('sessions:merge', (_evt, ids: string[]) => mergeSessions(ids || []))
+  ipcMain.handle('sessions:split', (_evt, sessionId: string, at: number) => splitSession(sessionId, at))
+  ipcMain.handle('sessions:update', (_evt, sessionId: string, patch: SessionPatch) => updateSession(sessionId, patch || {}))
+
+  ipcMain.handle('attachments:add', async (_evt, sessionId: string, path?: string) => {
+    if (!path) {
+      const picked = await dialog.showOpenDialog({ title: 'Attach file', properties: ['openFile'] })
+      if (picked.canceled || picked.filePaths.length === 0) return { error: 'Cancelled' }
+      path = picked.filePaths[0]
+    }
+    return sessionAttachments.attach(sessionId, path)
+  })
+  ipcMain.handle('attachments:list', (_evt, sessionId: string) => sessionAttachments.list(sessionId))
+  ipcMain.handle('attachments:remove', (_evt, id: string) => sessionAttachments.remove(id))
+
+  ipcMain.handle('sessions:get-clips', (_evt, sessionId: string) => sessionManager.getClips(sessionId))
+  ipcMain.handle('sessions:search-screen-text', (_evt, sessionId: string, query: string) => {
+    return sessionManager.searchScreenText(sessionId, query || '')
+  })
+  ipcMain.handle('sessions:get-input-activity', (_evt, sessionId: string) => sessionManager.getInputActivity(sessionId))
+  ipcMain.handle('sessions:get-performance-report', (_evt, sessionId: string) => sessionManager.getPerformanceReport(sessionId))
+
+  ipcMain.handle('sessions:export-markdown', (_evt, sessionId: string) => exportSessionMarkdown(sessionId))
+
+  ipcMain.handle('sessions:reveal-files', (_evt, sessionId: string) => revealSessionFiles(sessionId))
+
+  // Public summary link, copied to the clipboard
+  ipcMain.handle('sessions:create-share-link', (_evt, sessionId: string, expiryHours?: number) => {
+    return createShareLink(sessionId, expiryHours)
+  })
+  ipcMain.handle('sessions:revoke-share-link', (_evt, sessionId: string, token: string) => {
+    return revokeShareLink(sessionId, token)
+  })
+
+  // Without a path the entry goes to Documents/Drift
+  ipcMain.handle('sessions:export-org', (_evt, sessionId: string, path?: string) => exportSessionOrg(sessionId, path))
+  ipcMain.handle('sessions:append-org-journal', (_evt, path: string, range?: { from?: number; to?: number }) => {
+    return appendOrgJournal(path, range)
+  })
+
+  // Spreadsheet-friendly history for billing or reporting hours; without a path it goes to Documents/Drift
+  ipcMain.handle('sessions:export-csv', (_evt, range?: { from?: number; to?: number }, path?: string) => {
+    return exportSessionsCsv(range, path)
+  })
+
+  // Markers and notes as SRT/WebVTT cues or FFmpeg/YouTube chapters; without a path they go to Documents/Drift
+  ipcMain.handle('sessions:export-markers', (_evt, sessionId: string, format: MarkerFormat, path?: string) => {
+    return exportSessionMarkers(sessionId, format, path)
+  })
+
+  /* ---------------- Data archive ---------------- */
+  // Without a path the user picks where the archive goes
+  ipcMain.handle('data:export', async (_evt, path?: string) => {
+    if (!path) {
+      const today = new Date().toISOString().slice(0, 10)
+      const picked = await dialog.showSaveDialog({
+        title: 'Export Drift data',
+        defaultPath: join(app.getPath('documents'), `drift-export-${today}.json`),
+        filters: [{ name: 'Drift archive', extensions: ['json'] }]
+      })
+      if (picked.canceled || !picked.filePath) return { error: 'Cancelled' }
+      path = picked.filePath
+    }
+    return exportData(path)
+  })
+
+  ipcMain.handle('data:import', async (_evt, path?: string, strategy?: ImportConflictStrategy) => {
+    if (!path) {
+      const picked = await dialog.showOpenDialog({
+        title: 'Import Drift data',
+        properties: ['openFile'],
+        filters: [{ name: 'Drift archive', extensions: ['json'] }]
+      })
+      if (picked.canceled || picked.filePaths.length === 0) return { error: 'Cancelled' }
+      path = picked.filePaths[0]
+    }
+    return importData(path, strategy)
+  })
+
+  // Whole-profile backup for moving machines; unlike data:export it includes settings secrets and pending uploads
+  ipcMain.handle('backup:create', async (_evt, path?: string, options?: { includeMedia?: boolean }) => {
+    if (!path) {
+      const today = new Date().toISOString().slice(0, 10)
+      const picked = await dialog.showSaveDialog({
+        title: 'Back up Drift profile',
+        defaultPath: join(app.getPath('documents'), `drift-backup-${today}.driftbackup`),
+        filters: [{ name: 'Drift backup', extensions: ['driftbackup'] }]
+      })
+      if (picked.canceled || !picked.filePath) return { error: 'Cancelled' }
+      path = picked.filePath
+    }
+    return createBackup(path, options)
+  })
+
+  ipcMain.handle('backup:restore', async (_evt, path?: string) => {
+    if (!path) {
+      const picked = await dialog.showOpenDialog({
+        title: 'Restore Drift profile',
+        properties: ['openFile'],
+        filters: [{ name: 'Drift backup', extensions: ['driftbackup'] }]
+      })
+      if (picked.canceled || picked.filePaths.length === 0) return { error: 'Cancelled' }
+      path = picked.filePaths[0]
     }
+    return restoreBackup(path)
+  })
+
+  // Apply the retention policy immediately; reports via retention:purged as well
+  ipcMain.handle('retention:purge-now', (_evt, mediaDays?: number) => retentionManager.purgeNow(mediaDays))
+
+  // Scheduled background work (retention, end of day, telemetry); changes arrive as job:status
+  ipcMain.handle('jobs:list', () => jobScheduler.list())
+  ipcMain.handle('jobs:cancel', (_evt, id: string) => jobScheduler.cancel(id))
+
+  // Local store schema version, last migration and integrity check
+  ipcMain.handle('db:get-info', async () => getDbInfo(await getStore()))
+
+  // Per-category disk usage and free space, so the UI can warn before the disk fills up
+  ipcMain.handle('storage:get-usage', () => getStorageUsage())
+
+  /* ---------------- Settings ---------------- */
+  ipcMain.handle('settings:get', () => getSettings())
+  ipcMain.handle('settings:update', (_evt, patch) => {
+    // Switching backends goes through settings:set-environment, which checks the URL and drops cached data
+    if (patch?.environment !== undefined) {
+      return { error: 'Use settings:set-environment to change the environment' }
+    }
+    if (patch?.plugins !== undefined) {
+      const error = validatePlugins(patch.plugins)
+      if (error) return { error }
+      patch = { ...patch, plugins: withPluginDefaults(patch.plugins) }
+    }
+    return updateSettings(patch)
+  })
+
+  // Switch backends. Cached data from the previous backend is dropped.
+  ipcMain.handle('settings:set-environment', async (_evt, environment: Settings['environment']) => {
+    if (sessionManager.getActive()) {
+      return { error: 'End the active session before switching environments' }
+    }
+    const apiUrl = resolveApiBaseUrl(environment)
+    if (!apiUrl) {
+      return { error: `No API URL configured for ${environment.name}` }
+    }
+
+    await updateSettings({ environment })
+    await briefCache.clear()
+    liveSync.reconnect()
+    emitEvent('settings:environment-changed', { name: environment.name, apiUrl })
+    return { ok: true, apiUrl }
+  })
+
+  ipcMain.handle('telemetry:set-enabled', async (_evt, enabled: boolean) => {
+    await updateSettings({ telemetry: { enabled: !!enabled } })
+    return { ok: true, enabled: !!enabled }
+  })
+  ipcMain.handle('telemetry:preview', () => telemetry.preview())
+
+  // Run the end-of-day wrap-up now instead of waiting for the scheduled time
+  ipcMain.handle('session:wrap-up-day', () => endOfDayScheduler.run())
+
+  /* ---------------- Calendar ---------------- */
+  ipcMain.handle('calendar:upcoming-events', (_evt, hours?: number) => calendarService.upcomingEvents(hours))
+  ipcMain.handle('calendar:current-event', () => calendarService.currentEvent())
+
+  /* ---------------- Focus blocks ---------------- */
+  ipcMain.handle('focus:start', (_evt, minutes: number, session?: { briefId: string; role: string }) => {
+    return focusTimer.start(minutes, session)
   })
+  ipcMain.handle('focus:cancel', () => focusTimer.cancel())
+  ipcMain.handle('focus:get-status', () => focusTimer.getStatus())
+  ipcMain.handle('focus:list-blocks', (_evt, range?: { from?: number; to?: number }) => focusTimer.listBlocks(range))
+
+  /* ---------------- Crash reports ---------------- */
+  ipcMain.handle('crash:list-reports', () => crashReports.list())
+  ipcMain.handle('crash:submit-report', (_evt, id: string) => crashReports.submit(id))
+
+  /* ---------------- Permissions ---------------- */
+  ipcMain.handle('permissions:check', () => checkPermissions())
+  ipcMain.handle('permissions:request', (_evt, kind: PermissionKind) => requestPermission(kind))
+
+  /* ---------------- Capabilities ---------------- */
+  ipcMain.handle('capabilities:get', () => getCapabilities())
+
+  /* ---------------- Live sync ---------------- */
+  ipcMain.handle('live:get-status', () => liveSync.getStatus())
+  // Take over the session running on another device
+  ipcMain.handle('session:request-handoff', () => liveSync.requestHandoff())
+
+  // Probe the backend now; the result is also broadcast as backend-status
+  ipcMain.handle('backend:check-health', () => backendHealth.check())
+  // Method, path, status, attempts and timing of the last backend calls, for diagnostics
+  ipcMain.handle('backend:recent-calls', () => recentApiCalls())
+
+  ipcMain.handle('connectivity:get', () => connectivity.get())
+
+  /* ---------------- Control API ---------------- */
+  // Enabled and configured through settings:update (controlApi)
+  ipcMain.handle('control-api:get-info', () => controlApi.getInfo())
+  ipcMain.handle('control-api:regenerate-token', () => controlApi.regenerateToken())
+  ipcMain.handle('browser-extension:get-status', () => nativeMessaging.getStatus())
+
+  /* ---------------- Slack ---------------- */
+  // Configured through settings:update (slack); the preview never posts
+  ipcMain.handle('slack:preview', (_evt, sessionId?: string) => slackIntegration.preview(sessionId))
+
+  /* ---------------- Briefs ---------------- */
+  // Served from the local cache when possible; a stale cache is refreshed in the background
+  ipcMain.handle('briefs:fetch', (_evt, options?: { force?: boolean }) => briefCache.fetch(options))
+  ipcMain.handle('briefs:search', (_evt, query: string) => briefCache.search(query || ''))
+  ipcMain.handle('briefs:recent', (_evt, limit?: number) => briefCache.recent(limit))
+  ipcMain.handle('briefs:pinned', () => briefCache.pinned())
+  ipcMain.handle('briefs:set-pinned', (_evt, briefId: string, pinned: boolean) => briefCache.setPinned(briefId, pinned))
 
   /* ---------------- Sync with Drift backend ---------------- */
   ipcMain.handle('drift:sync', async () => {
     console.log('[drift:sync] Starting sync...')
-    console.log('[drift:sync] API URL:', DRIFT_API_URL)
-    const s = await getStore()
-    const authToken = s.get('authToken')
+    const authToken = await getAuthToken()
     
     if (!authToken) {
       console.log('[drift:sync] ❌ No auth token found')
       return { error: 'Not authenticated' }
     }
-    
-    console.log('[drift:sync] Auth token found, calling API:', `${DRIFT_API_URL}/desktop/sync`)
+
+    const missing = await requireScope('briefs:read')
+    if (missing) return missing
     
     try {
-      const response = await axios.post(`${DRIFT_API_URL}/desktop/sync`, {
-        userId: 'from-token'
-      }, {
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${authToken}`
-        },
-        timeout: 10000
-      })
-      
-      console.log('[drift:sync] Response status:', response.status)
-      const data = response.data
+      console.log('[drift:sync] Auth token found, calling API:', `${await getApiBaseUrl()}/desktop/sync`)
+      const data = await driftApi.sync()
       
       console.log('[drift:sync] ✅ Success! Briefs:', data.briefs?.length || 0, 'Role:', data.role)
-      if (data.briefs?.length > 0) {
-        console.log('[drift:sync] Projects:', data.briefs.map((b: any) => b.name).join(', '))
+      if ((data.briefs?.length ?? 0) > 0) {
+        console.log('[drift:sync] Projects:', (data.briefs ?? []).map((b) => b.name).join(', '))
       }
-      broadcast('drift:synced', data)
+      await briefCache.store(data)
+      emitEvent('drift:synced', data)
       return data
-    } catch (error: any) {
-      console.error('[drift:sync] ❌ Error:', error.message)
-      if (error.response) {
-        const status = error.response.status
-        console.error('[drift:sync] Response error:', status, error.response.data)
-        
-        // Specific handling for auth errors
-        if (status === 401 || status === 403) {
-          return { error: `401 - Not authenticated or token expired` }
-        }
-        
-        return { error: `API Error: ${status} - ${JSON.stringify(error.response.data)}` }
-      } else if (error.request) {
-        console.error('[drift:sync] No response received:', error.request)
-        return { error: 'No connection to server' }
-      } else {
-        console.error('[drift:sync] Request setup error:', error.message)
-        return { error: error.message }
+    } catch (error) {
+      const failure = toDriftError(error)
+      console.error('[drift:sync] ❌ Error:', failure.message)
+
+      // Specific handling for auth errors
+      if (failure.kind === 'rejected') {
+        await notify('authExpired', 'Drift · Signed out', 'Your session expired. Sign in again to keep syncing.')
+        return { error: `401 - Not authenticated or token expired`, retry: failure.retry }
       }
+      if (failure.kind === 'network' || failure.kind === 'timeout') {
+        return { error: 'No connection to server', retry: failure.retry }
+      }
+      return { error: failure.message, retry: failure.retry }
     }
   })
 
@@ -770,8 +902,9 @@ export function registerIpcHandlers(ctx: IpcContext): void {
     const newInvisible = !getIsInvisible()
     setIsInvisible(newInvisible)
 
+    // Destroy rather than close: the window is replaced, not hidden to the tray
     const m = getMainWindow()
-    if (m && !m.isDestroyed()) m.close()
+    if (m && !m.isDestroyed()) m.destroy()
 
     const newMain = createAppWindow(newInvisible)
     setMainWindow(newMain)
@@ -783,22 +916,14 @@ export function registerIpcHandlers(ctx: IpcContext): void {
       }
     })
 
-    broadcast('invisibility-state-changed', newInvisible)
+    emitEvent('invisibility-state-changed', newInvisible)
   })
 
   /* ---------------- Screenshot capture ---------------- */
+  // Redaction zones are blacked out, as in every session capture; null while an excluded app is in front
   ipcMain.handle('capture-screenshot', async () => {
-    const primaryDisplay = screen.getPrimaryDisplay()
-    const sources = await desktopCapturer.getSources({
-      types: ['screen'],
-      thumbnailSize: primaryDisplay.size
-    })
-    const source =
-      sources.find((s) => s.display_id === String(primaryDisplay.id)) || sources[0]
-
-    if (!source) return null
-
-    const png = source.thumbnail.toPNG()
-    return png.toString('base64')
+    if (!(await captureAllowed((await activityTracker.getActiveWindow())?.app ?? null))) return null
+    const frame = await screenCapture.captureFrame(1, 80)
+    return frame ? frame.toString('base64') : null
   })
 }
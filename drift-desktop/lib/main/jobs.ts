@@ -0,0 +1,249 @@
+import { getStore } from './store'
+import { emitEvent } from './events'
+
+// Upper bound for a single wait, so jobs stay on time across sleep and clock changes
+const MAX_WAIT_MS = 60 * 1000
+
+// Finished one-off jobs kept for list() before they are forgotten
+const KEEP_FINISHED_MS = 24 * 60 * 60 * 1000
+
+export type JobSchedule =
+  | { every: number } // milliseconds between runs, counted from the last run
+  | { dailyAt: string } // local time, HH:MM
+  | { at: number } // once, at this time
+
+export type JobStatus = 'scheduled' | 'running' | 'done' | 'failed' | 'cancelled'
+
+export interface Job {
+  id: string
+  name: string // registered handler
+  schedule: JobSchedule
+  payload?: unknown
+  status: JobStatus
+  nextRunAt: number | null
+  lastRunAt: number | null
+  lastError: string | null
+  attempts: number // failed runs in a row
+  maxAttempts: number // one-off jobs are retried with backoff until this many failures
+}
+
+export interface ScheduleOptions {
+  id?: string // defaults to the handler name; scheduling an existing id replaces it
+  payload?: unknown
+  maxAttempts?: number
+}
+
+type JobHandler = (payload: unknown) => Promise<void> | void
+
+/**
+ * Next local occurrence of HH:MM after `from`, or null if invalid.
+ */
+function nextDailyAt(time: string, from: number): number | null {
+  const match = /^(\d{1,2}):(\d{2})$/.exec(time)
+  if (!match) return null
+  const hours = Number(match[1])
+  const minutes = Number(match[2])
+  if (hours > 23 || minutes > 59) return null
+
+  const now = new Date(from)
+  const next = new Date(now.getFullYear(), now.getMonth(), now.getDate(), hours, minutes)
+  if (next.getTime() <= from) {
+    next.setDate(next.getDate() + 1)
+  }
+  return next.getTime()
+}
+
+function nextRun(schedule: JobSchedule, lastRunAt: number | null, now: number): number | null {
+  if ('every' in schedule) return lastRunAt === null ? now : Math.max(now, lastRunAt + schedule.every)
+  if ('dailyAt' in schedule) return nextDailyAt(schedule.dailyAt, now)
+  return schedule.at
+}
+
+function sameSchedule(a: JobSchedule, b: JobSchedule): boolean {
+  return JSON.stringify(a) === JSON.stringify(b)
+}
+
+/**
+ * Persistent scheduler for recurring and delayed background work. Features
+ * register a handler by name and schedule jobs for it; jobs, their last run
+ * and pending one-off jobs survive restarts, and a job whose handler isn't
+ * registered yet waits for it. Every status change is sent as `job:status`.
+ */
+class JobScheduler {
+  private jobs = new Map<string, Job>()
+  private handlers = new Map<string, JobHandler>()
+  private loaded: Promise<void> | null = null
+  private timer: NodeJS.Timeout | null = null
+  private running = new Set<string>()
+  private started = false
+
+  start(): void {
+    this.started = true
+    this.load().then(() => this.arm())
+  }
+
+  stop(): void {
+    this.started = false
+    if (this.timer) {
+      clearTimeout(this.timer)
+      this.timer = null
+    }
+  }
+
+  register(name: string, handler: JobHandler): void {
+    this.handlers.set(name, handler)
+    this.arm()
+  }
+
+  /**
+   * Add or replace a job. A recurring job keeps its last run when rescheduled
+   * unchanged, so restarting the app doesn't run it early.
+   */
+  async schedule(name: string, schedule: JobSchedule, options: ScheduleOptions = {}): Promise<Job> {
+    await this.load()
+    const id = options.id ?? name
+    const existing = this.jobs.get(id)
+    const lastRunAt = existing && sameSchedule(existing.schedule, schedule) ? existing.lastRunAt : null
+
+    const job: Job = {
+      id,
+      name,
+      schedule,
+      payload: options.payload,
+      status: 'scheduled',
+      nextRunAt: nextRun(schedule, lastRunAt, Date.now()),
+      lastRunAt,
+      lastError: null,
+      attempts: 0,
+      maxAttempts: options.maxAttempts ?? 1
+    }
+    if (job.nextRunAt === null) {
+      job.status = 'failed'
+      job.lastError = `Invalid schedule: ${JSON.stringify(schedule)}`
+      console.warn('[Jobs]', job.lastError)
+    }
+
+    this.jobs.set(id, job)
+    this.changed(job)
+    this.arm()
+    return job
+  }
+
+  async cancel(id: string): Promise<{ ok: true } | { error: string }> {
+    await this.load()
+    const job = this.jobs.get(id)
+    if (!job) return { error: `Unknown job: ${id}` }
+
+    job.status = 'cancelled'
+    job.nextRunAt = null
+    this.jobs.delete(id)
+    this.changed(job)
+    return { ok: true }
+  }
+
+  async list(): Promise<Job[]> {
+    await this.load()
+    return [...this.jobs.values()].sort((a, b) => (a.nextRunAt ?? Infinity) - (b.nextRunAt ?? Infinity))
+  }
+
+  private load(): Promise<void> {
+    if (!this.loaded) {
+      this.loaded = (async () => {
+        const s = await getStore()
+        const cutoff = Date.now() - KEEP_FINISHED_MS
+        for (const job of (s.get('jobs') || []) as Job[]) {
+          if (job.nextRunAt === null && (job.lastRunAt ?? 0) < cutoff) continue
+          // Interrupted by a quit; run it again
+          if (job.status === 'running') job.status = 'scheduled'
+          this.jobs.set(job.id, job)
+        }
+      })()
+    }
+    return this.loaded
+  }
+
+  private async persist(): Promise<void> {
+    const s = await getStore()
+    s.set('jobs', [...this.jobs.values()])
+  }
+
+  private changed(job: Job): void {
+    void this.persist()
+    emitEvent('job:status', { ...job })
+  }
+
+  // Wait until the earliest due job with a handler
+  private arm(): void {
+    if (!this.started) return
+    if (this.timer) clearTimeout(this.timer)
+
+    const due = [...this.jobs.values()]
+      .filter((j) => j.nextRunAt !== null && this.handlers.has(j.name) && !this.running.has(j.id))
+      .map((j) => j.nextRunAt as number)
+    if (due.length === 0) {
+      this.timer = null
+      return
+    }
+    const wait = Math.min(Math.max(0, Math.min(...due) - Date.now()), MAX_WAIT_MS)
+    this.timer = setTimeout(() => this.runDue(), wait)
+  }
+
+  private runDue(): void {
+    this.timer = null
+    const now = Date.now()
+    for (const job of this.jobs.values()) {
+      if (job.nextRunAt !== null && job.nextRunAt <= now && this.handlers.has(job.name) && !this.running.has(job.id)) {
+        void this.run(job)
+      }
+    }
+    this.arm()
+  }
+
+  private async run(job: Job): Promise<void> {
+    const handler = this.handlers.get(job.name)!
+    this.running.add(job.id)
+    job.status = 'running'
+    this.changed(job)
+
+    const startedAt = Date.now()
+    try {
+      await handler(job.payload)
+      job.attempts = 0
+      job.lastError = null
+      job.status = 'scheduled'
+    } catch (error: any) {
+      job.attempts++
+      job.lastError = String(error?.message ?? error)
+      job.status = 'failed'
+      console.error(`[Jobs] ${job.id} failed (attempt ${job.attempts}):`, job.lastError)
+    } finally {
+      this.running.delete(job.id)
+    }
+    job.lastRunAt = startedAt
+
+    // Cancelled or replaced while running
+    if (this.jobs.get(job.id) !== job) {
+      this.arm()
+      return
+    }
+
+    if ('at' in job.schedule) {
+      if (job.status === 'failed' && job.attempts < job.maxAttempts) {
+        // Exponential backoff from 30s, capped at an hour
+        job.nextRunAt = Date.now() + Math.min(30 * 1000 * 2 ** (job.attempts - 1), 60 * 60 * 1000)
+        job.status = 'scheduled'
+      } else {
+        job.nextRunAt = null
+        if (job.status === 'scheduled') job.status = 'done'
+      }
+    } else {
+      job.nextRunAt = nextRun(job.schedule, startedAt, Date.now())
+    }
+
+    this.changed(job)
+    this.arm()
+  }
+}
+
+// Singleton instance
+export const jobScheduler = new JobScheduler()
@@ -0,0 +1,287 @@
+import { emitEvent } from './events'
+import { sessionManager } from './session'
+import { getApiBaseUrl, getAuthToken } from './api'
+import { getDeviceId } from './device'
+
+// Reconnect backoff bounds
+const MIN_RECONNECT_MS = 1000
+const MAX_RECONNECT_MS = 60 * 1000
+
+// Keep-alive interval while connected
+const HEARTBEAT_MS = 30 * 1000
+
+// Events kept while disconnected; older ones are dropped first
+const MAX_QUEUED_EVENTS = 100
+
+// How long a requested handoff waits for the recording device to answer
+const HANDOFF_TIMEOUT_MS = 30 * 1000
+
+type SessionEvent = 'started' | 'ended' | 'paused' | 'resumed' | 'marker'
+
+interface LiveCommand {
+  command: string
+  sessionId?: string
+  requestedBy?: string // handoff: device that asked to take over
+  briefId?: string
+  role?: string
+}
+
+export type LiveSyncStatus = 'disconnected' | 'connecting' | 'connected'
+
+/**
+ * Persistent WebSocket to the backend (`/desktop/ws`).
+ * Pushes session events as they happen so the web app sees live state,
+ * and executes commands the server sends (e.g. stop_session from the web app).
+ * Reconnects with exponential backoff and re-authenticates with the active account's token.
+ *
+ * Handoff: the device taking over sends `handoff_request`; the recording device
+ * ends its segment and answers `handoff_ready`, and the requester starts a
+ * session that continues it. The backend links the two sessions.
+ */
+class LiveSync {
+  private socket: WebSocket | null = null
+  private status: LiveSyncStatus = 'disconnected'
+  private running = false
+  private attempts = 0
+  private seq = 0
+  private generation = 0
+  private queue: Array<Record<string, unknown>> = []
+  private reconnectTimer: NodeJS.Timeout | null = null
+  private heartbeatTimer: NodeJS.Timeout | null = null
+  private handoffTimer: NodeJS.Timeout | null = null
+
+  start(): void {
+    if (this.running) return
+    this.running = true
+
+    const events: SessionEvent[] = ['started', 'ended', 'paused', 'resumed', 'marker']
+    for (const event of events) {
+      sessionManager.on(event, (payload) => this.push(event, payload))
+    }
+
+    this.connect()
+  }
+
+  stop(): void {
+    this.running = false
+    if (this.reconnectTimer) {
+      clearTimeout(this.reconnectTimer)
+      this.reconnectTimer = null
+    }
+    this.close()
+  }
+
+  /**
+   * Drop the current connection and connect again, e.g. after the account changed.
+   */
+  reconnect(): void {
+    if (!this.running) return
+    this.attempts = 0
+    this.close()
+    this.connect()
+  }
+
+  /**
+   * Ask whichever device is recording to hand its session over to this one.
+   */
+  async requestHandoff(): Promise<{ ok: true } | { error: string }> {
+    if (sessionManager.getActive()) return { error: 'A session is already running on this device' }
+    if (!this.socket || this.status !== 'connected') return { error: 'Not connected' }
+    if (this.handoffTimer) return { error: 'Handoff already requested' }
+
+    this.socket.send(JSON.stringify({ type: 'handoff_request', deviceId: await getDeviceId() }))
+    this.handoffTimer = setTimeout(() => {
+      this.handoffTimer = null
+      emitEvent('session:handoff', { status: 'failed', error: 'No device with a running session answered' })
+    }, HANDOFF_TIMEOUT_MS)
+    emitEvent('session:handoff', { status: 'requested' })
+    return { ok: true }
+  }
+
+  getStatus(): LiveSyncStatus {
+    return this.status
+  }
+
+  private async connect(): Promise<void> {
+    if (!this.running || this.status !== 'disconnected') return
+    this.setStatus('connecting')
+    const generation = this.generation
+
+    const token = await getAuthToken()
+    const deviceId = await getDeviceId()
+    let url: string
+    try {
+      url = `${(await getApiBaseUrl()).replace(/^http/, 'ws')}/desktop/ws`
+    } catch (error: any) {
+      // Nothing to connect to until the environment is fixed; switching it reconnects
+      console.warn('[LiveSync]', error.message)
+      if (generation === this.generation) this.setStatus('disconnected')
+      return
+    }
+    if (generation !== this.generation) return // closed meanwhile
+    if (!token) {
+      // Nothing to authenticate with yet; try again later
+      this.setStatus('disconnected')
+      this.scheduleReconnect()
+      return
+    }
+
+    const socket = new WebSocket(url)
+    this.socket = socket
+
+    socket.onopen = () => {
+      socket.send(JSON.stringify({ type: 'authenticate', token, deviceId }))
+    }
+
+    socket.onmessage = (event) => {
+      try {
+        this.handleMessage(JSON.parse(String(event.data)))
+      } catch (error) {
+        console.warn('[LiveSync] Bad message:', error)
+      }
+    }
+
+    socket.onclose = () => {
+      if (this.socket !== socket) return
+      this.socket = null
+      this.stopHeartbeat()
+      this.setStatus('disconnected')
+      this.scheduleReconnect()
+    }
+
+    socket.onerror = () => {
+      // onclose follows and schedules the reconnect
+      console.warn('[LiveSync] Connection error')
+    }
+  }
+
+  private close(): void {
+    this.generation++
+    this.stopHeartbeat()
+    if (this.socket) {
+      const socket = this.socket
+      this.socket = null
+      socket.close()
+    }
+    this.setStatus('disconnected')
+  }
+
+  private scheduleReconnect(): void {
+    if (!this.running || this.reconnectTimer) return
+
+    const delay = Math.min(MIN_RECONNECT_MS * 2 ** this.attempts, MAX_RECONNECT_MS)
+    this.attempts++
+    this.reconnectTimer = setTimeout(() => {
+      this.reconnectTimer = null
+      this.connect()
+    }, delay)
+  }
+
+  private handleMessage(message: any): void {
+    switch (message.type) {
+      case 'authenticated':
+        this.attempts = 0
+        this.setStatus('connected')
+        this.startHeartbeat()
+        this.flush()
+        break
+      case 'command':
+        this.handleCommand(message)
+        break
+      case 'error':
+        console.warn('[LiveSync] Server error:', message.message)
+        if (this.status !== 'connected') {
+          // Authentication failed; retry with backoff in case the token is refreshed
+          this.close()
+          this.scheduleReconnect()
+        }
+        break
+    }
+  }
+
+  private async handleCommand(message: LiveCommand): Promise<void> {
+    console.log('[LiveSync] Command:', message.command)
+    emitEvent('live:command', { command: message.command, sessionId: message.sessionId })
+
+    if (message.command === 'stop_session') {
+      const active = sessionManager.getActive()
+      // Ignore commands aimed at a session that has already ended
+      if (active && (!message.sessionId || message.sessionId === active.sessionId)) {
+        await sessionManager.end(undefined, 'remote')
+      }
+    } else if (message.command === 'handoff') {
+      await this.handOff(message)
+    } else if (message.command === 'continue_session') {
+      await this.continueHandedOff(message)
+    }
+  }
+
+  // Recording device: finalize this segment and tell the requester what to continue
+  private async handOff(message: LiveCommand): Promise<void> {
+    const active = sessionManager.getActive()
+    if (!active || !message.requestedBy || message.requestedBy === (await getDeviceId())) return
+
+    const { sessionId, briefId, role } = active
+    await sessionManager.end(undefined, 'handoff')
+    this.socket?.send(JSON.stringify({ type: 'handoff_ready', requestedBy: message.requestedBy, sessionId, briefId, role }))
+    emitEvent('session:handoff', { status: 'handed-off', sessionId })
+  }
+
+  // Requesting device: start the session that continues the handed-off one
+  private async continueHandedOff(message: LiveCommand): Promise<void> {
+    if (!this.handoffTimer || message.requestedBy !== (await getDeviceId())) return
+    clearTimeout(this.handoffTimer)
+    this.handoffTimer = null
+
+    if (!message.briefId || !message.role || !message.sessionId) {
+      emitEvent('session:handoff', { status: 'failed', error: 'Incomplete handoff' })
+      return
+    }
+    const result = await sessionManager.start(message.briefId, message.role, null, message.sessionId)
+    emitEvent(
+      'session:handoff',
+      result.error
+        ? { status: 'failed', error: result.error }
+        : { status: 'continued', sessionId: result.sessionId, continuesSessionId: message.sessionId }
+    )
+  }
+
+  private push(event: SessionEvent, payload: Record<string, unknown>): void {
+    this.queue.push({ type: 'session_event', event, seq: ++this.seq, ...payload })
+    if (this.queue.length > MAX_QUEUED_EVENTS) {
+      this.queue.shift()
+    }
+    if (this.status === 'connected') {
+      this.flush()
+    }
+  }
+
+  private flush(): void {
+    while (this.queue.length > 0 && this.socket && this.status === 'connected') {
+      this.socket.send(JSON.stringify(this.queue.shift()))
+    }
+  }
+
+  private startHeartbeat(): void {
+    this.stopHeartbeat()
+    this.heartbeatTimer = setInterval(() => {
+      this.socket?.send(JSON.stringify({ type: 'heartbeat' }))
+    }, HEARTBEAT_MS)
+  }
+
+  private stopHeartbeat(): void {
+    if (this.heartbeatTimer) {
+      clearInterval(this.heartbeatTimer)
+      this.heartbeatTimer = null
+    }
+  }
+
+  private setStatus(status: LiveSyncStatus): void {
+    if (this.status === status) return
+    this.status = status
+    emitEvent('live:status', status)
+  }
+}
+
+// Singleton instance
+export const liveSync = new LiveSync()
@@ -0,0 +1,73 @@
+import { createServer, IncomingMessage, Server, ServerResponse } from 'http'
+
+type Handler = (req: IncomingMessage, res: ServerResponse) => void
+
+// One port serving both loopback addresses
+export interface LoopbackListener {
+  port: number
+  hosts: string[] // addresses actually bound, e.g. ['127.0.0.1', '::1']
+  close(): void
+}
+
+export interface LoopbackBindError {
+  error: string
+  code: 'bind-failed'
+  ports: { from: number; to: number }
+}
+
+function listen(handler: Handler, port: number, host: string): Promise<Server> {
+  return new Promise((resolve, reject) => {
+    const server = createServer(handler)
+    server.once('error', reject)
+    server.listen(port, host, () => {
+      server.off('error', reject)
+      resolve(server)
+    })
+  })
+}
+
+function boundPort(server: Server): number {
+  const address = server.address()
+  return address && typeof address === 'object' ? address.port : 0
+}
+
+/**
+ * Serve `handler` on the first free port of [from, to] (0 for one the OS
+ * picks) on 127.0.0.1 and, where IPv6 is available, on ::1 as well, since
+ * browsers may resolve `localhost` to either. A port is skipped when either
+ * address is taken on it.
+ */
+export async function listenLoopback(handler: Handler, from: number, to: number): Promise<LoopbackListener | LoopbackBindError> {
+  const ports = from === 0 ? [0] : Array.from({ length: Math.max(0, to - from) + 1 }, (_, i) => from + i)
+  let lastError = 'No port to try'
+
+  for (const port of ports) {
+    const servers: Server[] = []
+    const hosts: string[] = []
+    let actualPort = port
+    let inUse = false
+
+    for (const host of ['127.0.0.1', '::1']) {
+      try {
+        const server = await listen(handler, actualPort, host)
+        actualPort = boundPort(server)
+        servers.push(server)
+        hosts.push(host)
+      } catch (error: any) {
+        lastError = `${host}:${actualPort}: ${error.message}`
+        // Address family not available on this machine; the other one is enough
+        if (error.code === 'EADDRNOTAVAIL' || error.code === 'EAFNOSUPPORT') continue
+        inUse = true
+        break
+      }
+    }
+
+    if (!inUse && servers.length > 0) {
+      return { port: actualPort, hosts, close: () => servers.forEach((s) => s.close()) }
+    }
+    servers.forEach((s) => s.close())
+  }
+
+  console.warn('[Loopback] Could not bind ports', from, '-', to, ':', lastError)
+  return { error: `Could not open a local port for sign-in (${lastError})`, code: 'bind-failed', ports: { from, to } }
+}
@@ -1,9 +1,12 @@
 import { app } from 'electron'
 import { DriftApp } from './Drift'
+import { crashReports } from './crashReports'
+import { parseCliCommand, runCliCommand } from './cli'
+import { HOST_FLAG, runNativeMessagingHost } from './nativeMessaging'
+import { enablePortableMode } from './portable'
 import { performance } from 'node:perf_hooks'
 
 const t0 = performance.now()
-console.log('Starting Drift main process...')
 
 // Handle creating/removing shortcuts on Windows when installing/uninstalling.
 // eslint-disable-next-line @typescript-eslint/no-var-requires
@@ -11,12 +14,30 @@ if (require('electron-squirrel-startup')) {
   app.quit()
 }
 
-// Instantiate the app. This will handle all app lifecycle events.
-new DriftApp(t0)
+// Before anything resolves a storage path, including the CLI socket
+enablePortableMode()
 
-// Quit when all windows are closed, except on macOS.
-app.on('window-all-closed', () => {
-  if (process.platform !== 'darwin') {
-    app.quit()
-  }
-})
+// `drift record start|stop` and `drift status` talk to the running instance and exit
+const cliCommand = parseCliCommand(process.argv)
+
+if (process.argv.includes(HOST_FLAG)) {
+  // Started by a browser for the companion extension
+  runNativeMessagingHost()
+} else if (cliCommand) {
+  runCliCommand(cliCommand)
+} else {
+  console.log('Starting Drift main process...')
+
+  // Capture crashes as early as possible, before any window exists
+  crashReports.install()
+
+  // Instantiate the app. This will handle all app lifecycle events.
+  new DriftApp(t0)
+
+  // Quit when all windows are closed, except on macOS.
+  app.on('window-all-closed', () => {
+    if (process.platform !== 'darwin') {
+      app.quit()
+    }
+  })
+}
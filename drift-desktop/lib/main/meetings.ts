@@ -0,0 +1,120 @@
+import { execFile } from 'child_process'
+import { promisify } from 'util'
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { activityTracker } from './activityTracker'
+import { calendarService } from './calendar'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+const execFileAsync = promisify(execFile)
+
+// How often the foreground window is checked while nothing is recording
+const POLL_MS = 15 * 1000
+
+// The same meeting window isn't announced again within this time
+const REANNOUNCE_MS = 30 * 60 * 1000
+
+// Foreground windows that are a call in progress, by app name and window title
+const MEETING_WINDOWS: Array<{ name: string; app: RegExp; title: RegExp }> = [
+  { name: 'Zoom', app: /zoom/i, title: /zoom (meeting|webinar)/i },
+  { name: 'Microsoft Teams', app: /teams/i, title: /meeting|call/i },
+  { name: 'Google Meet', app: /chrome|firefox|safari|edge|arc|brave/i, title: /^meet -|google meet/i },
+  { name: 'Webex', app: /webex/i, title: /meeting/i },
+  { name: 'Slack', app: /slack/i, title: /huddle/i }
+]
+
+export interface DetectedMeeting {
+  service: string
+  app: string
+  title: string
+  microphone: boolean | null // null where microphone use can't be read (macOS)
+  suggestedBriefId: string | null
+}
+
+/**
+ * Whether any app is capturing from the microphone, or null where this can't be read.
+ */
+export async function isMicrophoneInUse(): Promise<boolean | null> {
+  try {
+    if (process.platform === 'win32') {
+      // Apps currently using the mic have LastUsedTimeStop = 0
+      const { stdout } = await execFileAsync(
+        'reg',
+        ['query', 'HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\microphone', '/s', '/v', 'LastUsedTimeStop'],
+        { timeout: 3000, windowsHide: true }
+      )
+      return /LastUsedTimeStop\s+REG_QWORD\s+0x0\b/i.test(stdout)
+    }
+    if (process.platform === 'linux') {
+      const { stdout } = await execFileAsync('pactl', ['list', 'short', 'source-outputs'], { timeout: 3000 })
+      return stdout.trim().length > 0
+    }
+    return null
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Notices when a call starts (a meeting window in the foreground, with the
+ * microphone in use where that can be checked) while nothing is recording,
+ * and offers to start a session against a suggested brief.
+ */
+class MeetingDetector {
+  private intervalId: NodeJS.Timeout | null = null
+  private announced = new Map<string, number>()
+  private checking = false
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), POLL_MS)
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  private async check(): Promise<void> {
+    if (this.checking || sessionManager.getActive()) return
+    const { meetings } = await getSettings()
+    if (!meetings.detect) return
+
+    this.checking = true
+    try {
+      const window = await activityTracker.getActiveWindow()
+      const match = window && MEETING_WINDOWS.find((m) => m.app.test(window.app) && m.title.test(window.title))
+      if (!window || !match) return
+
+      const key = `${window.app}:${window.title}`
+      if (Date.now() - (this.announced.get(key) ?? 0) < REANNOUNCE_MS) return
+
+      const microphone = await isMicrophoneInUse()
+      if (microphone === false) return
+      this.announced.set(key, Date.now())
+
+      const event = await calendarService.currentEvent().catch(() => null)
+      const meeting: DetectedMeeting = {
+        service: match.name,
+        app: window.app,
+        title: window.title,
+        microphone,
+        suggestedBriefId: event?.suggestedBriefId ?? (await calendarService.suggestBrief(window.title))
+      }
+      console.log('[Meetings] Detected', match.name, 'meeting')
+      emitEvent('meeting-detected', meeting)
+
+      if (meetings.prompt) {
+        await notify('meetingDetected', `Drift · ${match.name} meeting`, 'Start recording this meeting?')
+      }
+    } finally {
+      this.checking = false
+    }
+  }
+}
+
+// Singleton instance
+export const meetingDetector = new MeetingDetector()
@@ -0,0 +1,78 @@
+import { app, BrowserWindow, screen } from 'electron'
+import { join } from 'path'
+import { windowRegistry } from './windowRegistry'
+
+const WIDTH = 240
+const HEIGHT = 56
+
+// Gap to the screen edge
+const MARGIN = 16
+
+/**
+ * Small always-visible window with the elapsed time and pause/stop buttons.
+ * Owned by the main process, so it stays up when the main window is hidden.
+ * Renders the `#mini-recorder` view of the regular renderer bundle.
+ */
+class MiniRecorder {
+  private window: BrowserWindow | null = null
+
+  show(): BrowserWindow {
+    if (this.window && !this.window.isDestroyed()) {
+      this.window.showInactive()
+      return this.window
+    }
+
+    const { workArea } = screen.getPrimaryDisplay()
+    const win = new BrowserWindow({
+      width: WIDTH,
+      height: HEIGHT,
+      x: workArea.x + workArea.width - WIDTH - MARGIN,
+      y: workArea.y + workArea.height - HEIGHT - MARGIN,
+      frame: false,
+      resizable: false,
+      minimizable: false,
+      maximizable: false,
+      fullscreenable: false,
+      skipTaskbar: true,
+      alwaysOnTop: true,
+      show: false,
+      title: 'Drift Recorder',
+      backgroundColor: '#0a0a0b',
+      webPreferences: {
+        preload: join(__dirname, '../preload/preload.js'),
+        sandbox: false,
+        contextIsolation: true,
+        nodeIntegration: false
+      }
+    })
+    win.setVisibleOnAllWorkspaces(true, { visibleOnFullScreen: true })
+    win.once('ready-to-show', () => win.showInactive())
+    win.on('closed', () => {
+      if (this.window === win) this.window = null
+    })
+    windowRegistry.addWindow('mini-recorder', win)
+
+    if (!app.isPackaged && process.env['ELECTRON_RENDERER_URL']) {
+      win.loadURL(`${process.env['ELECTRON_RENDERER_URL']}#mini-recorder`)
+    } else {
+      win.loadFile(join(__dirname, '../renderer/index.html'), { hash: 'mini-recorder' })
+    }
+
+    this.window = win
+    return win
+  }
+
+  hide(): void {
+    if (this.window && !this.window.isDestroyed()) {
+      this.window.close()
+    }
+    this.window = null
+  }
+
+  isVisible(): boolean {
+    return !!this.window && !this.window.isDestroyed() && this.window.isVisible()
+  }
+}
+
+// Singleton instance
+export const miniRecorder = new MiniRecorder()
@@ -0,0 +1,258 @@
+import { app } from 'electron'
+import { execFile } from 'child_process'
+import { promises as fs } from 'fs'
+import { homedir } from 'os'
+import { dirname, join } from 'path'
+import { promisify } from 'util'
+import { CliCommand, sendToRunningInstance } from './cli'
+import { getSettings, onSettingsChanged } from './settings'
+
+const execFileAsync = promisify(execFile)
+
+// Name the extension connects to; browsers require lowercase letters, digits, dots and underscores
+export const HOST_NAME = 'app.drift.desktop'
+
+// Passed by the wrapper script, so a browser launch can be told apart from a normal one
+export const HOST_FLAG = '--native-messaging-host'
+
+// Browsers cap messages to the host at 1 MB
+const MAX_MESSAGE_BYTES = 1024 * 1024
+
+// How often the host checks the recording status to push changes to the extension
+const STATUS_POLL_MS = 3000
+
+interface BrowserTarget {
+  name: string
+  family: 'chromium' | 'firefox'
+  dir?: string // manifest directory (macOS, Linux)
+  registryKey?: string // HKCU key whose default value is the manifest path (Windows)
+}
+
+function browserTargets(): BrowserTarget[] {
+  const home = homedir()
+  if (process.platform === 'win32') {
+    const key = (path: string) => `HKCU\\Software\\${path}\\NativeMessagingHosts\\${HOST_NAME}`
+    return [
+      { name: 'Chrome', family: 'chromium', registryKey: key('Google\\Chrome') },
+      { name: 'Chromium', family: 'chromium', registryKey: key('Chromium') },
+      { name: 'Edge', family: 'chromium', registryKey: key('Microsoft\\Edge') },
+      { name: 'Brave', family: 'chromium', registryKey: key('BraveSoftware\\Brave-Browser') },
+      { name: 'Firefox', family: 'firefox', registryKey: key('Mozilla') }
+    ]
+  }
+  const base = process.platform === 'darwin' ? join(home, 'Library', 'Application Support') : join(home, '.config')
+  const chromium = (name: string, mac: string, linux: string): BrowserTarget => ({
+    name,
+    family: 'chromium',
+    dir: join(base, process.platform === 'darwin' ? mac : linux, 'NativeMessagingHosts')
+  })
+  return [
+    chromium('Chrome', 'Google/Chrome', 'google-chrome'),
+    chromium('Chromium', 'Chromium', 'chromium'),
+    chromium('Edge', 'Microsoft Edge', 'microsoft-edge'),
+    chromium('Brave', 'BraveSoftware/Brave-Browser', 'BraveSoftware/Brave-Browser'),
+    chromium('Vivaldi', 'Vivaldi', 'vivaldi'),
+    {
+      name: 'Firefox',
+      family: 'firefox',
+      dir: process.platform === 'darwin'
+        ? join(base, 'Mozilla', 'NativeMessagingHosts')
+        : join(home, '.mozilla', 'native-messaging-hosts')
+    }
+  ]
+}
+
+function hostDir(): string {
+  return join(app.getPath('userData'), 'native-messaging')
+}
+
+/**
+ * Messages the extension can send. `id` is echoed back with the result.
+ *
+ *   { type: 'start', briefId, role? }
+ *   { type: 'stop', summary? }
+ *   { type: 'status' }
+ *   { type: 'tab', url, title }   or { type: 'tab', url: null } when no tab should be recorded
+ */
+function toCommand(message: any): CliCommand | null {
+  switch (message?.type) {
+    case 'start':
+      return typeof message.briefId === 'string' ? { command: 'record-start', briefId: message.briefId, role: message.role } : null
+    case 'stop':
+      return { command: 'record-stop', summary: message.summary }
+    case 'status':
+      return { command: 'status' }
+    case 'tab':
+      return { command: 'browser-tab', tab: typeof message.url === 'string' ? { url: message.url, title: String(message.title ?? '') } : null }
+    default:
+      return null
+  }
+}
+
+function writeMessage(message: unknown): void {
+  const body = Buffer.from(JSON.stringify(message))
+  const header = Buffer.alloc(4)
+  header.writeUInt32LE(body.length, 0)
+  process.stdout.write(Buffer.concat([header, body]))
+}
+
+/**
+ * Act as the native messaging host the browser started: read length-prefixed
+ * JSON messages from stdin, run them against the running instance over the
+ * CLI socket, and push recording status changes back. Runs instead of the
+ * app and exits when the extension disconnects.
+ */
+export function runNativeMessagingHost(): void {
+  app.dock?.hide()
+  let buffer = Buffer.alloc(0)
+  let lastStatus = ''
+
+  const pushStatus = async () => {
+    const status = await sendToRunningInstance({ command: 'status' })
+    const serialized = JSON.stringify(status)
+    if (serialized !== lastStatus) {
+      lastStatus = serialized
+      writeMessage({ type: 'status', status })
+    }
+  }
+  const poll = setInterval(pushStatus, STATUS_POLL_MS)
+  void pushStatus()
+
+  process.stdin.on('data', async (chunk: Buffer) => {
+    buffer = Buffer.concat([buffer, chunk])
+    while (buffer.length >= 4) {
+      const length = buffer.readUInt32LE(0)
+      if (length > MAX_MESSAGE_BYTES) {
+        writeMessage({ type: 'error', error: 'Message too large' })
+        app.exit(1)
+        return
+      }
+      if (buffer.length < 4 + length) return
+      const raw = buffer.subarray(4, 4 + length).toString('utf-8')
+      buffer = buffer.subarray(4 + length)
+
+      let message: any
+      try {
+        message = JSON.parse(raw)
+      } catch {
+        writeMessage({ type: 'error', error: 'Invalid JSON' })
+        continue
+      }
+      const command = toCommand(message)
+      const result = command ? await sendToRunningInstance(command) : { error: `Unknown message type: ${message?.type}` }
+      writeMessage({ type: 'result', id: message?.id, result })
+      if (command?.command === 'record-start' || command?.command === 'record-stop') void pushStatus()
+    }
+  })
+  process.stdin.on('end', async () => {
+    clearInterval(poll)
+    // The extension is gone, so its tab can't be trusted any more
+    await sendToRunningInstance({ command: 'browser-tab', tab: null })
+    app.exit(0)
+  })
+}
+
+export interface NativeHostStatus {
+  enabled: boolean
+  hostName: string
+  installed: Array<{ browser: string; manifest: string }>
+}
+
+/**
+ * Registers Drift as a native messaging host with the installed browsers so
+ * the companion extension can start and stop recordings and report the
+ * current tab. Manifests are written while the browserExtension setting is
+ * on and removed when it is turned off.
+ */
+class NativeMessaging {
+  private installed: NativeHostStatus['installed'] = []
+
+  start(): void {
+    onSettingsChanged((next, prev) => {
+      if (JSON.stringify(next.browserExtension) !== JSON.stringify(prev.browserExtension)) {
+        this.sync().catch((error) => console.warn('[NativeMessaging] Update failed:', error.message))
+      }
+    })
+    this.sync().catch((error) => console.warn('[NativeMessaging] Setup failed:', error.message))
+  }
+
+  async getStatus(): Promise<NativeHostStatus> {
+    const { browserExtension } = await getSettings()
+    return { enabled: browserExtension.enabled, hostName: HOST_NAME, installed: [...this.installed] }
+  }
+
+  private async sync(): Promise<void> {
+    const { browserExtension } = await getSettings()
+    if (browserExtension.enabled) {
+      await this.install(browserExtension.chromeExtensionIds, browserExtension.firefoxExtensionIds)
+    } else {
+      await this.uninstall()
+    }
+  }
+
+  private async install(chromeIds: string[], firefoxIds: string[]): Promise<void> {
+    const dir = hostDir()
+    await fs.mkdir(dir, { recursive: true })
+    const launcher = await this.writeLauncher(dir)
+
+    const manifests = {
+      chromium: { name: HOST_NAME, description: 'Drift', path: launcher, type: 'stdio', allowed_origins: chromeIds.map((id) => `chrome-extension://${id}/`) },
+      firefox: { name: HOST_NAME, description: 'Drift', path: launcher, type: 'stdio', allowed_extensions: firefoxIds }
+    }
+
+    const installed: NativeHostStatus['installed'] = []
+    for (const target of browserTargets()) {
+      const content = JSON.stringify(manifests[target.family], null, 2)
+      try {
+        if (target.registryKey) {
+          const manifest = join(dir, `${HOST_NAME}.${target.family}.json`)
+          await fs.writeFile(manifest, content)
+          await execFileAsync('reg', ['add', target.registryKey, '/ve', '/t', 'REG_SZ', '/d', manifest, '/f'], { windowsHide: true })
+          installed.push({ browser: target.name, manifest })
+        } else if (target.dir) {
+          // Only browsers that are installed, i.e. have a profile directory
+          const browserDir = await fs.stat(dirname(target.dir)).catch(() => null)
+          if (!browserDir?.isDirectory()) continue
+          const manifest = join(target.dir, `${HOST_NAME}.json`)
+          await fs.mkdir(target.dir, { recursive: true })
+          await fs.writeFile(manifest, content)
+          installed.push({ browser: target.name, manifest })
+        }
+      } catch (error: any) {
+        console.warn(`[NativeMessaging] Could not register with ${target.name}:`, error.message)
+      }
+    }
+    this.installed = installed
+    console.log('[NativeMessaging] Registered with', installed.map((i) => i.browser).join(', ') || 'no browsers')
+  }
+
+  private async uninstall(): Promise<void> {
+    for (const target of browserTargets()) {
+      if (target.registryKey) {
+        await execFileAsync('reg', ['delete', target.registryKey, '/f'], { windowsHide: true }).catch(() => {})
+      } else if (target.dir) {
+        await fs.rm(join(target.dir, `${HOST_NAME}.json`), { force: true })
+      }
+    }
+    await fs.rm(hostDir(), { recursive: true, force: true })
+    this.installed = []
+  }
+
+  // Browsers start the host without arguments of our choosing, so point them at a script that adds the flag
+  private async writeLauncher(dir: string): Promise<string> {
+    const target = app.isPackaged ? [process.execPath] : [process.execPath, app.getAppPath()]
+    if (process.platform === 'win32') {
+      const path = join(dir, 'drift-host.bat')
+      const command = target.map((p) => `"${p}"`).join(' ')
+      await fs.writeFile(path, `@echo off\r\n${command} ${HOST_FLAG} %*\r\n`)
+      return path
+    }
+    const path = join(dir, 'drift-host.sh')
+    const command = target.map((p) => `'${p.replace(/'/g, `'\\''`)}'`).join(' ')
+    await fs.writeFile(path, `#!/bin/sh\nexec ${command} ${HOST_FLAG} "$@"\n`, { mode: 0o755 })
+    return path
+  }
+}
+
+// Singleton instance
+export const nativeMessaging = new NativeMessaging()
@@ -0,0 +1,27 @@
+import { Notification } from 'electron'
+import { getSettings } from './settings'
+
+export type NotificationKind =
+  | 'sessionAutoStopped'
+  | 'uploadComplete'
+  | 'uploadFailed'
+  | 'authExpired'
+  | 'authExpiring'
+  | 'dailyRecap'
+  | 'focusBlockEnded'
+  | 'wokeFromSleep'
+  | 'lowBattery'
+  | 'lowDisk'
+  | 'goalReached'
+  | 'meetingDetected'
+  | 'notRecording'
+
+/**
+ * Show an OS notification if this kind is enabled in settings.
+ */
+export async function notify(kind: NotificationKind, title: string, body: string): Promise<void> {
+  const { notifications } = await getSettings()
+  if (!notifications[kind] || !Notification.isSupported()) return
+
+  new Notification({ title, body }).show()
+}
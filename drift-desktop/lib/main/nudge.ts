@@ -0,0 +1,84 @@
+import { powerMonitor } from 'electron'
+import { getSettings, Settings } from './settings'
+import { sessionManager } from './session'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+
+// How often activity is sampled while nothing is recording
+const POLL_SECONDS = 60
+
+/**
+ * Minutes since midnight for a local HH:MM time, or null if invalid.
+ */
+function minutesOf(time: string): number | null {
+  const match = /^(\d{1,2}):(\d{2})$/.exec(time)
+  if (!match || Number(match[1]) > 23 || Number(match[2]) > 59) return null
+  return Number(match[1]) * 60 + Number(match[2])
+}
+
+export function withinWorkHours(workHours: Settings['nudge']['workHours'], now: Date = new Date()): boolean {
+  const start = minutesOf(workHours.start)
+  const end = minutesOf(workHours.end)
+  if (start === null || end === null || !workHours.days.includes(now.getDay())) return false
+  const minutes = now.getHours() * 60 + now.getMinutes()
+  return minutes >= start && minutes < end
+}
+
+/**
+ * Suggests starting a recording when the user has been working for a while
+ * during work hours without a session. Sends one notification per stretch of
+ * activity; a stretch ends when the user goes idle or a session runs.
+ */
+class RecordingNudge {
+  private intervalId: NodeJS.Timeout | null = null
+  private activeSince: number | null = null
+  private nudged = false
+  private snoozedUntil = 0
+
+  start(): void {
+    if (this.intervalId) return
+    this.intervalId = setInterval(() => this.check(), POLL_SECONDS * 1000)
+  }
+
+  stop(): void {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+  }
+
+  /**
+   * No nudges for the given time (default from settings).
+   */
+  async snooze(minutes?: number): Promise<{ snoozedUntil: number }> {
+    const { nudge } = await getSettings()
+    this.snoozedUntil = Date.now() + (minutes ?? nudge.snoozeMinutes) * 60 * 1000
+    return { snoozedUntil: this.snoozedUntil }
+  }
+
+  private async check(): Promise<void> {
+    const { nudge, idle } = await getSettings()
+    const idleSeconds = powerMonitor.getSystemIdleTime()
+
+    // A session or a break ends the stretch
+    if (!nudge.enabled || sessionManager.getActive() || idleSeconds >= idle.thresholdMinutes * 60) {
+      this.activeSince = null
+      this.nudged = false
+      return
+    }
+
+    const now = Date.now()
+    this.activeSince = this.activeSince ?? now - idleSeconds * 1000
+    const activeMinutes = Math.floor((now - this.activeSince) / 60000)
+    if (this.nudged || activeMinutes < nudge.afterMinutes || now < this.snoozedUntil || !withinWorkHours(nudge.workHours)) {
+      return
+    }
+
+    this.nudged = true
+    emitEvent('nudge:not-recording', { activeMinutes })
+    await notify('notRecording', "Drift · You're not recording", `You've been working for ${activeMinutes} minutes. Start a session?`)
+  }
+}
+
+// Singleton instance
+export const recordingNudge = new RecordingNudge()
@@ -0,0 +1,130 @@
+import { spawn } from 'child_process'
+import type { ActivityEntry } from './activityTracker'
+import { Settings } from './settings'
+
+// Text recognized on a screenshot taken during a session
+export interface ScreenText {
+  at: number
+  app: string
+  title: string
+  text: string
+}
+
+// A search hit inside a session, anchored to when the text was on screen
+export interface ScreenTextHit {
+  at: number
+  app: string
+  title: string
+  snippet: string
+}
+
+// Longest text kept per screenshot
+const MAX_CHARS = 5000
+
+// Characters of context on each side of a search match
+const SNIPPET_CONTEXT = 60
+
+/**
+ * Run tesseract on a JPEG, reading it from stdin.
+ */
+function recognize(jpeg: Buffer, languages: string): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const child = spawn('tesseract', ['stdin', 'stdout', '-l', languages], { windowsHide: true })
+    let stdout = ''
+    child.stdout.on('data', (chunk) => (stdout += chunk))
+    child.on('error', reject)
+    child.on('close', (code) => (code === 0 ? resolve(stdout) : reject(new Error(`tesseract exited with ${code}`))))
+    child.stdin.end(jpeg)
+  })
+}
+
+/**
+ * Case-insensitive search over a session's recognized text.
+ */
+export function searchScreenText(entries: ScreenText[], query: string): ScreenTextHit[] {
+  const needle = query.trim().toLowerCase()
+  if (!needle) return []
+
+  const hits: ScreenTextHit[] = []
+  for (const entry of entries) {
+    const index = entry.text.toLowerCase().indexOf(needle)
+    if (index === -1) continue
+    const start = Math.max(0, index - SNIPPET_CONTEXT)
+    const end = Math.min(entry.text.length, index + needle.length + SNIPPET_CONTEXT)
+    hits.push({
+      at: entry.at,
+      app: entry.app,
+      title: entry.title,
+      snippet: `${start > 0 ? '…' : ''}${entry.text.slice(start, end)}${end < entry.text.length ? '…' : ''}`
+    })
+  }
+  return hits
+}
+
+/**
+ * Recognizes text on session screenshots in the background, at most one
+ * screenshot per interval and one at a time, so a session can later be
+ * searched for something that was on screen. Needs tesseract on the PATH;
+ * without it OCR is skipped for the session.
+ */
+class ScreenOcr {
+  private config: Settings['ocr'] | null = null
+  private entries: ScreenText[] = []
+  private lastRunAt = 0
+  private busy = false
+  private unavailable = false
+
+  start(config: Settings['ocr']): void {
+    this.config = config
+    this.entries = []
+    this.lastRunAt = 0
+    this.unavailable = false
+  }
+
+  /**
+   * Consider a tracked activity; its screenshot is recognized if the interval has passed.
+   */
+  offer(activity: ActivityEntry): void {
+    if (!this.config || !activity.screenshot || this.busy || this.unavailable) return
+    if (Date.now() - this.lastRunAt < this.config.intervalSeconds * 1000) return
+
+    this.busy = true
+    this.lastRunAt = Date.now()
+    this.process(activity, this.config).finally(() => (this.busy = false))
+  }
+
+  /**
+   * Stop recognizing and return the text collected. A recognition still running is discarded.
+   */
+  stop(): ScreenText[] {
+    const entries = this.entries
+    this.entries = []
+    this.config = null
+    return entries
+  }
+
+  getEntries(): ScreenText[] {
+    return this.entries.map((e) => ({ ...e }))
+  }
+
+  private async process(activity: ActivityEntry, config: Settings['ocr']): Promise<void> {
+    try {
+      const raw = await recognize(Buffer.from(activity.screenshot!, 'base64'), config.languages)
+      const text = raw.replace(/[ \t]+/g, ' ').replace(/\n{2,}/g, '\n').trim().slice(0, MAX_CHARS)
+      // Ended meanwhile, nothing legible, or the same screen as last time
+      if (this.config !== config || !text || text === this.entries[this.entries.length - 1]?.text) return
+
+      this.entries.push({ at: Date.now(), app: activity.app, title: activity.title, text })
+    } catch (error: any) {
+      if (error.code === 'ENOENT') {
+        this.unavailable = true
+        console.warn('[OCR] tesseract not found, skipping OCR for this session')
+      } else {
+        console.error('[OCR] Recognition failed:', error.message)
+      }
+    }
+  }
+}
+
+// Singleton instance
+export const screenOcr = new ScreenOcr()
@@ -0,0 +1,85 @@
+import { desktopCapturer, shell, systemPreferences } from 'electron'
+
+export type PermissionKind = 'microphone' | 'screen' | 'accessibility'
+
+// 'not-applicable' means the OS has no such permission gate
+export type PermissionStatus = 'granted' | 'denied' | 'restricted' | 'not-determined' | 'not-applicable' | 'unknown'
+
+// Where each permission is managed when it can't be prompted for
+const SETTINGS_PANES: Partial<Record<NodeJS.Platform, Partial<Record<PermissionKind, string>>>> = {
+  darwin: {
+    microphone: 'x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone',
+    screen: 'x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture',
+    accessibility: 'x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility'
+  },
+  win32: {
+    microphone: 'ms-settings:privacy-microphone'
+  }
+}
+
+function mediaStatus(kind: 'microphone' | 'screen'): PermissionStatus {
+  if (process.platform === 'darwin' || (process.platform === 'win32' && kind === 'microphone')) {
+    return systemPreferences.getMediaAccessStatus(kind) as PermissionStatus
+  }
+  return 'not-applicable'
+}
+
+/**
+ * Current status of every permission capture depends on.
+ * Screen recording and accessibility are also needed for window titles on macOS.
+ */
+export function checkPermissions(): Record<PermissionKind, PermissionStatus> {
+  return {
+    microphone: mediaStatus('microphone'),
+    screen: mediaStatus('screen'),
+    accessibility:
+      process.platform === 'darwin'
+        ? systemPreferences.isTrustedAccessibilityClient(false) ? 'granted' : 'denied'
+        : 'not-applicable'
+  }
+}
+
+/**
+ * Permissions that are known to be missing.
+ */
+export function missingPermissions(kinds: PermissionKind[]): PermissionKind[] {
+  const status = checkPermissions()
+  return kinds.filter((kind) => status[kind] === 'denied' || status[kind] === 'restricted')
+}
+
+/**
+ * Trigger the OS prompt for a permission. Once a permission has been decided,
+ * the OS won't prompt again, so the matching settings pane is opened instead.
+ */
+export async function requestPermission(kind: PermissionKind): Promise<{ status: PermissionStatus; openedSettings: boolean }> {
+  const before = checkPermissions()[kind]
+  if (before === 'granted' || before === 'not-applicable') {
+    return { status: before, openedSettings: false }
+  }
+
+  if (before === 'not-determined') {
+    if (kind === 'microphone' && process.platform === 'darwin') {
+      await systemPreferences.askForMediaAccess('microphone')
+    } else if (kind === 'screen') {
+      // Requesting sources is what makes macOS show the screen recording prompt
+      await desktopCapturer.getSources({ types: ['screen'], thumbnailSize: { width: 1, height: 1 } })
+    }
+    const after = checkPermissions()[kind]
+    if (after !== 'not-determined') {
+      return { status: after, openedSettings: false }
+    }
+  }
+
+  if (kind === 'accessibility' && process.platform === 'darwin') {
+    // Shows the system dialog pointing at the Accessibility pane
+    systemPreferences.isTrustedAccessibilityClient(true)
+    return { status: checkPermissions()[kind], openedSettings: true }
+  }
+
+  const pane = SETTINGS_PANES[process.platform]?.[kind]
+  if (pane) {
+    await shell.openExternal(pane)
+    return { status: checkPermissions()[kind], openedSettings: true }
+  }
+  return { status: checkPermissions()[kind], openedSettings: false }
+}
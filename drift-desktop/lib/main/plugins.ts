@@ -0,0 +1,183 @@
+import { spawn } from 'child_process'
+import { promises as fs } from 'fs'
+import { tmpdir } from 'os'
+import { join, resolve, sep } from 'path'
+import { getSettings, Settings } from './settings'
+import { sessionAttachments } from './attachments'
+
+export type PluginConfig = Settings['plugins'][number]
+
+// Largest stdout accepted from a plugin
+const MAX_OUTPUT_BYTES = 1024 * 1024
+
+// Lines a plugin can add to the summary
+const MAX_SUMMARY_LINES = 20
+
+// Used when a plugin has no timeout of its own
+export const DEFAULT_PLUGIN_TIMEOUT_SECONDS = 60
+
+// Longest a plugin may hold up the upload
+const MAX_PLUGIN_TIMEOUT_SECONDS = 600
+
+// What a plugin receives on stdin
+export interface PluginInput {
+  hook: 'session-finished'
+  session: { id: string; briefId: string; briefName: string; role: string; startedAt: number; endedAt: number }
+  manifest: Record<string, any> // as it will be uploaded
+}
+
+// What a plugin may print to stdout; everything is optional
+interface PluginOutput {
+  summaryLines?: string[] // appended to the session summary before upload
+  artifacts?: string[] // files the plugin wrote to its working directory, attached to the session
+}
+
+export interface PluginRun {
+  plugin: string
+  ok: boolean
+  error?: string
+  durationMs: number
+}
+
+export interface PluginResults {
+  summaryLines: string[]
+  artifacts: string[] // absolute paths, until attach() copies them into the session
+  runs: PluginRun[]
+  attach(sessionId: string): Promise<void>
+}
+
+/**
+ * Check plugin entries before they are stored.
+ * Returns an error message, or null when they are usable. A missing timeout
+ * is allowed; withPluginDefaults() fills it in.
+ */
+export function validatePlugins(plugins: unknown): string | null {
+  if (!Array.isArray(plugins)) return 'Plugins must be a list'
+  for (const plugin of plugins) {
+    const name = typeof plugin?.name === 'string' && plugin.name ? plugin.name : null
+    if (!name) return 'Every plugin needs a name'
+    if (typeof plugin.command !== 'string' || !plugin.command.trim()) return `Plugin ${name} needs a command`
+    if (!Array.isArray(plugin.args) || !plugin.args.every((a: unknown) => typeof a === 'string')) {
+      return `Arguments of plugin ${name} must be a list of strings`
+    }
+    if (typeof plugin.enabled !== 'boolean') return `Plugin ${name} must be enabled or disabled`
+    const timeout = plugin.timeoutSeconds
+    if (timeout !== undefined && (!Number.isFinite(timeout) || timeout <= 0 || timeout > MAX_PLUGIN_TIMEOUT_SECONDS)) {
+      return `Timeout of plugin ${name} must be between 1 and ${MAX_PLUGIN_TIMEOUT_SECONDS} seconds`
+    }
+  }
+  return null
+}
+
+/**
+ * Plugin entries with the default timeout where none is set.
+ */
+export function withPluginDefaults(plugins: PluginConfig[]): PluginConfig[] {
+  return plugins.map((plugin) => ({ ...plugin, timeoutSeconds: plugin.timeoutSeconds ?? DEFAULT_PLUGIN_TIMEOUT_SECONDS }))
+}
+
+// Timeout to enforce, even for entries that reached the settings without validation (older stores, restored backups)
+function timeoutOf(plugin: PluginConfig): number {
+  const seconds = plugin.timeoutSeconds
+  if (!Number.isFinite(seconds) || seconds <= 0) return DEFAULT_PLUGIN_TIMEOUT_SECONDS
+  return Math.min(seconds, MAX_PLUGIN_TIMEOUT_SECONDS)
+}
+
+/**
+ * Run one plugin and kill it at the timeout. This limits what a plugin sees,
+ * not what it can do: it runs without a shell, in its own scratch directory,
+ * with only PATH from the environment (no tokens or proxy credentials), and
+ * its output is capped. It is not a sandbox; it runs as the user and can
+ * read and write anything the user can.
+ */
+function runPlugin(plugin: PluginConfig, input: PluginInput, cwd: string): Promise<PluginOutput> {
+  const timeoutSeconds = timeoutOf(plugin)
+  return new Promise((resolvePromise, reject) => {
+    const child = spawn(plugin.command, plugin.args, {
+      cwd,
+      env: { PATH: process.env.PATH ?? '', DRIFT_PLUGIN_DIR: cwd, DRIFT_SESSION_ID: input.session.id },
+      stdio: ['pipe', 'pipe', 'pipe'],
+      windowsHide: true
+    })
+    let stdout = ''
+    let stderr = ''
+    const timer = setTimeout(() => {
+      child.kill('SIGKILL')
+      reject(new Error(`Timed out after ${timeoutSeconds}s`))
+    }, timeoutSeconds * 1000)
+
+    child.stdout.on('data', (chunk) => {
+      stdout += chunk
+      if (stdout.length > MAX_OUTPUT_BYTES) {
+        child.kill('SIGKILL')
+        reject(new Error('Output too large'))
+      }
+    })
+    child.stderr.on('data', (chunk) => (stderr = (stderr + chunk).slice(-2000)))
+    child.on('error', (error) => {
+      clearTimeout(timer)
+      reject(error)
+    })
+    child.on('close', (code) => {
+      clearTimeout(timer)
+      if (code !== 0) return reject(new Error(`Exited with ${code}${stderr ? `: ${stderr.trim()}` : ''}`))
+      if (!stdout.trim()) return resolvePromise({})
+      try {
+        resolvePromise(JSON.parse(stdout))
+      } catch {
+        reject(new Error('Output is not JSON'))
+      }
+    })
+    // A plugin that doesn't read its input is fine
+    child.stdin.on('error', () => {})
+    child.stdin.end(JSON.stringify(input))
+  })
+}
+
+/**
+ * Run the enabled post-session plugins one after another on the finished
+ * session's manifest. A plugin that fails or times out is logged and
+ * skipped; the session is uploaded either way.
+ */
+export async function runSessionPlugins(input: PluginInput): Promise<PluginResults> {
+  const { plugins } = await getSettings()
+  const results: PluginResults = {
+    summaryLines: [],
+    artifacts: [],
+    runs: [],
+    attach: async (sessionId) => {
+      for (const path of results.artifacts) {
+        const attached = await sessionAttachments.attach(sessionId, path)
+        if ('error' in attached) console.warn('[Plugins] Could not attach', path, ':', attached.error)
+      }
+      await Promise.all(scratchDirs.map((dir) => fs.rm(dir, { recursive: true, force: true })))
+    }
+  }
+  const scratchDirs: string[] = []
+
+  for (const plugin of plugins.filter((p) => p.enabled && p.command)) {
+    const startedAt = Date.now()
+    const cwd = await fs.mkdtemp(join(tmpdir(), 'drift-plugin-'))
+    scratchDirs.push(cwd)
+    try {
+      const output = await runPlugin(plugin, input, cwd)
+      if (Array.isArray(output.summaryLines)) {
+        results.summaryLines.push(...output.summaryLines.filter((l) => typeof l === 'string').slice(0, MAX_SUMMARY_LINES))
+      }
+      for (const artifact of Array.isArray(output.artifacts) ? output.artifacts : []) {
+        const path = resolve(cwd, String(artifact))
+        // Only files inside the plugin's own directory
+        if (!path.startsWith(cwd + sep)) {
+          console.warn('[Plugins]', plugin.name, 'returned an artifact outside its directory:', artifact)
+          continue
+        }
+        results.artifacts.push(path)
+      }
+      results.runs.push({ plugin: plugin.name, ok: true, durationMs: Date.now() - startedAt })
+    } catch (error: any) {
+      console.warn('[Plugins]', plugin.name, 'failed:', error.message)
+      results.runs.push({ plugin: plugin.name, ok: false, error: error.message, durationMs: Date.now() - startedAt })
+    }
+  }
+  return results
+}
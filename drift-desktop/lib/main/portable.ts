@@ -0,0 +1,49 @@
+import { app } from 'electron'
+import { existsSync } from 'fs'
+import { dirname, join } from 'path'
+
+// Next to the executable (or the .app bundle), this file turns portable mode on without the flag
+const MARKER_FILE = 'drift.portable'
+
+// Folder next to the executable that holds all app data in portable mode
+const DATA_DIR = 'DriftData'
+
+let portableDir: string | null = null
+
+// The directory the user sees Drift in, not the bundle internals
+function executableDir(): string {
+  // Set by electron-builder's portable Windows target, which runs from a temp copy
+  if (process.env.PORTABLE_EXECUTABLE_DIR) return process.env.PORTABLE_EXECUTABLE_DIR
+  const exeDir = dirname(app.getPath('exe'))
+  if (process.platform === 'darwin') {
+    // Drift.app/Contents/MacOS/Drift
+    return dirname(dirname(dirname(exeDir)))
+  }
+  return exeDir
+}
+
+/**
+ * Switch to portable mode when launched with `--portable` or next to a
+ * drift.portable marker file: app data, logs and crash dumps then live in a
+ * DriftData folder beside the executable instead of the OS locations.
+ * Must run before anything reads those paths. Returns the data folder, or null.
+ */
+export function enablePortableMode(argv: string[] = process.argv): string | null {
+  const dir = executableDir()
+  if (!argv.includes('--portable') && !existsSync(join(dir, MARKER_FILE))) return null
+
+  portableDir = join(dir, DATA_DIR)
+  app.setPath('userData', portableDir)
+  app.setPath('sessionData', portableDir)
+  app.setPath('logs', join(portableDir, 'logs'))
+  app.setPath('crashDumps', join(portableDir, 'Crashpad'))
+  console.log('[Portable] Storing data in', portableDir)
+  return portableDir
+}
+
+/**
+ * Whether data is kept beside the executable; it may then be opened on other machines.
+ */
+export function isPortable(): boolean {
+  return portableDir !== null
+}
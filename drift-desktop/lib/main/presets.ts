@@ -0,0 +1,101 @@
+import { randomUUID } from 'crypto'
+import { getSettings, updateSettings } from './settings'
+import { CaptureQuality, validateCaptureQuality } from './capture'
+
+// How a session records; chosen per brief, or the default preset when a brief has none
+export interface CapturePreset {
+  id: string
+  name: string
+  screenshots: boolean // false records window activity only
+  captureTarget: string | null // display/window id, null for the primary display
+  capture: CaptureQuality
+}
+
+/**
+ * Saved capture presets and which one each brief records with.
+ */
+class CapturePresets {
+  async list(): Promise<{ presets: CapturePreset[]; defaultId: string | null; briefDefaults: Record<string, string> }> {
+    const { capturePresets } = await getSettings()
+    return capturePresets
+  }
+
+  /**
+   * Create a preset, or replace the one with the same id.
+   */
+  async save(preset: Omit<CapturePreset, 'id'> & { id?: string }): Promise<{ ok: true; preset: CapturePreset } | { error: string }> {
+    if (!preset.name?.trim()) return { error: 'Preset needs a name' }
+    const invalid = validateCaptureQuality(preset.capture)
+    if (invalid) return { error: invalid }
+
+    const saved: CapturePreset = {
+      id: preset.id || randomUUID(),
+      name: preset.name.trim(),
+      screenshots: preset.screenshots !== false,
+      captureTarget: preset.captureTarget ?? null,
+      capture: { ...preset.capture }
+    }
+    const { capturePresets } = await getSettings()
+    const existing = capturePresets.presets.some((p) => p.id === saved.id)
+    await updateSettings({
+      capturePresets: {
+        presets: existing
+          ? capturePresets.presets.map((p) => (p.id === saved.id ? saved : p))
+          : [...capturePresets.presets, saved]
+      }
+    })
+    return { ok: true, preset: saved }
+  }
+
+  /**
+   * Delete a preset. Briefs that used it fall back to the default preset.
+   */
+  async remove(id: string): Promise<{ ok: true } | { error: string }> {
+    const { capturePresets } = await getSettings()
+    if (!capturePresets.presets.some((p) => p.id === id)) return { error: `Unknown preset: ${id}` }
+
+    const briefDefaults = Object.fromEntries(Object.entries(capturePresets.briefDefaults).filter(([, presetId]) => presetId !== id))
+    await updateSettings({
+      capturePresets: {
+        presets: capturePresets.presets.filter((p) => p.id !== id),
+        defaultId: capturePresets.defaultId === id ? null : capturePresets.defaultId,
+        briefDefaults
+      }
+    })
+    return { ok: true }
+  }
+
+  /**
+   * Record `briefId` with this preset from now on; null goes back to the default preset.
+   * Without a brief id, sets the default preset itself.
+   */
+  async setDefault(presetId: string | null, briefId?: string): Promise<{ ok: true } | { error: string }> {
+    const { capturePresets } = await getSettings()
+    if (presetId && !capturePresets.presets.some((p) => p.id === presetId)) return { error: `Unknown preset: ${presetId}` }
+
+    if (!briefId) {
+      await updateSettings({ capturePresets: { defaultId: presetId } })
+      return { ok: true }
+    }
+    const briefDefaults = { ...capturePresets.briefDefaults }
+    if (presetId) {
+      briefDefaults[briefId] = presetId
+    } else {
+      delete briefDefaults[briefId]
+    }
+    await updateSettings({ capturePresets: { briefDefaults } })
+    return { ok: true }
+  }
+
+  /**
+   * The preset a recording of `briefId` uses, or null to record with the global capture settings.
+   */
+  async resolve(briefId: string, presetId?: string | null): Promise<CapturePreset | null> {
+    const { capturePresets } = await getSettings()
+    const id = presetId ?? capturePresets.briefDefaults[briefId] ?? capturePresets.defaultId
+    return capturePresets.presets.find((p) => p.id === id) ?? null
+  }
+}
+
+// Singleton instance
+export const capturePresets = new CapturePresets()
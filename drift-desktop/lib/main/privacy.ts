@@ -0,0 +1,21 @@
+import { getSettings } from './settings'
+
+/**
+ * Whether an app is on a list of app names. Matching is case-insensitive and
+ * by substring, so 'Bitwarden' also covers 'Bitwarden Helper'.
+ */
+export function matchesApp(app: string | null, list: string[]): boolean {
+  if (!app) return false
+  const name = app.toLowerCase()
+  return list.some((entry) => entry.trim() && name.includes(entry.trim().toLowerCase()))
+}
+
+/**
+ * Whether the screen may be captured while `app` is in the foreground:
+ * not for excluded or sensitive apps. Window bounds aren't known, so
+ * frames are skipped rather than masked.
+ */
+export async function captureAllowed(app: string | null): Promise<boolean> {
+  const { privacy } = await getSettings()
+  return !matchesApp(app, privacy.excludedApps) && !matchesApp(app, privacy.sensitiveApps)
+}
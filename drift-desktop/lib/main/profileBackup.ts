@@ -0,0 +1,216 @@
+import { app, dialog } from 'electron'
+import { promises as fs } from 'fs'
+import { dirname, join, relative, sep } from 'path'
+import { getStore } from './store'
+import { migrateStore, SCHEMA_VERSION } from './storeMigrations'
+import { sessionManager } from './session'
+import { getSettings } from './settings'
+import { withoutSecrets } from './dataArchive'
+import { encryptFileStream, readEncryptedFile, readEncryptedStreamFile, writeEncryptedFile } from './crypto'
+
+// Bump when the backup layout changes; restores reject versions they don't know
+export const BACKUP_VERSION = 1
+
+// Kept per installation: signing in again is required on the new machine, and ids must stay unique
+const MACHINE_KEYS = ['accounts', 'activeAccount', 'authToken', 'userEmail', 'deviceId', 'telemetryInstallId', 'runningSession']
+
+// Never taken from a backup file, which may come from anyone: settings can name
+// commands to run (plugins), a git remote to push to, and a control API or proxy to use
+const LOCAL_KEYS = ['settings']
+
+// Directories under userData whose files belong to the profile
+const MEDIA_DIRS = ['crash-reports', 'attachments']
+
+export interface MediaEntry {
+  path: string // relative to userData, with forward slashes
+  size: number
+  data?: string // base64, when media files were included
+}
+
+// Everything needed to recreate this profile on another machine
+export interface ProfileBackup {
+  format: 'drift-backup'
+  version: number
+  createdAt: number
+  appVersion: string
+  schemaVersion: number
+  store: Record<string, any> // sessions, settings, focus blocks, queues...
+  media: MediaEntry[]
+}
+
+// Media is encrypted with this installation's data key, which doesn't travel with
+// a backup, so backups carry it decrypted and restores encrypt it with the local key.
+// Attachments are stream-encrypted (see attachments.ts), crash reports in one piece.
+function isAttachment(file: string): boolean {
+  return relative(app.getPath('userData'), file).split(sep)[0] === 'attachments'
+}
+
+function readMedia(file: string): Promise<Buffer> {
+  return isAttachment(file) ? readEncryptedStreamFile(file) : readEncryptedFile(file)
+}
+
+async function writeMedia(file: string, data: Buffer): Promise<void> {
+  if (!isAttachment(file)) return writeEncryptedFile(file, data)
+  const plain = `${file}.restore`
+  await fs.writeFile(plain, data, { mode: 0o600 })
+  try {
+    await encryptFileStream(plain, file)
+  } finally {
+    await fs.rm(plain, { force: true })
+  }
+}
+
+async function listFiles(dir: string): Promise<string[]> {
+  const entries = await fs.readdir(dir, { withFileTypes: true }).catch(() => [])
+  const files: string[] = []
+  for (const entry of entries) {
+    const path = join(dir, entry.name)
+    if (entry.isDirectory()) files.push(...(await listFiles(path)))
+    else if (entry.isFile()) files.push(path)
+  }
+  return files
+}
+
+/**
+ * Write the whole local profile to one file: the store (history, settings,
+ * pending uploads) and a manifest of media files, with their contents if
+ * `includeMedia` is set. Account tokens, this installation's ids and the
+ * credentials in settings (see withoutSecrets) are left out.
+ */
+export async function createBackup(
+  path: string,
+  options: { includeMedia?: boolean } = {}
+): Promise<{ path: string; sessions: number; mediaFiles: number } | { error: string }> {
+  const s = await getStore()
+  const store = structuredClone(s.store)
+  for (const key of MACHINE_KEYS) delete store[key]
+  if (store.settings) store.settings = withoutSecrets(await getSettings())
+
+  const userData = app.getPath('userData')
+  const media: MediaEntry[] = []
+  for (const dir of MEDIA_DIRS) {
+    for (const file of await listFiles(join(userData, dir))) {
+      const content = await readMedia(file).catch(() => null)
+      if (!content) continue
+      media.push({
+        path: relative(userData, file).split(sep).join('/'),
+        size: content.length,
+        ...(options.includeMedia ? { data: content.toString('base64') } : {})
+      })
+    }
+  }
+
+  const backup: ProfileBackup = {
+    format: 'drift-backup',
+    version: BACKUP_VERSION,
+    createdAt: Date.now(),
+    appVersion: app.getVersion(),
+    schemaVersion: s.get('schemaVersion') ?? 0,
+    store,
+    media
+  }
+
+  try {
+    await fs.writeFile(path, JSON.stringify(backup), { mode: 0o600 })
+  } catch (error: any) {
+    return { error: `Could not write backup: ${error.message}` }
+  }
+  const sessions = Array.isArray(store.sessions) ? store.sessions.length : 0
+  console.log('[Backup] Wrote', sessions, 'sessions and', media.length, 'media files to', path)
+  return { path, sessions, mediaFiles: media.length }
+}
+
+// Structural checks before anything local is touched
+function validate(backup: any): string | null {
+  if (backup?.format !== 'drift-backup' || typeof backup.version !== 'number') return 'Not a Drift backup'
+  if (backup.version > BACKUP_VERSION) {
+    return `Backup version ${backup.version} is newer than this app supports (${BACKUP_VERSION})`
+  }
+  if (typeof backup.schemaVersion !== 'number' || backup.schemaVersion > SCHEMA_VERSION) {
+    return 'Backup was made by a newer version of Drift; update the app first'
+  }
+  if (!backup.store || typeof backup.store !== 'object' || Array.isArray(backup.store)) return 'Backup has no data'
+  if (backup.store.sessions !== undefined && !Array.isArray(backup.store.sessions)) return 'Backup data is damaged'
+  if (!Array.isArray(backup.media)) return 'Backup data is damaged'
+  for (const entry of backup.media) {
+    const inside = typeof entry?.path === 'string' && MEDIA_DIRS.some((d) => entry.path.startsWith(`${d}/`)) && !entry.path.split('/').includes('..')
+    if (!inside) return `Backup contains an unexpected file: ${entry?.path}`
+  }
+  return null
+}
+
+/**
+ * Replace the local profile's data with a backup, then offer to restart. Signed-in
+ * accounts and the local settings stay as they are. Asks before anything is replaced;
+ * the current store file is kept as config.json.pre-restore.bak.
+ */
+export async function restoreBackup(path: string): Promise<{ ok: true; sessions: number; mediaFiles: number } | { error: string }> {
+  if (sessionManager.getActive()) {
+    return { error: 'End the active session before restoring a backup' }
+  }
+
+  let backup: ProfileBackup
+  try {
+    backup = JSON.parse(await fs.readFile(path, 'utf-8'))
+  } catch (error: any) {
+    return { error: `Could not read backup: ${error.message}` }
+  }
+  const invalid = validate(backup)
+  if (invalid) return { error: invalid }
+
+  const count = Array.isArray(backup.store.sessions) ? backup.store.sessions.length : 0
+  const confirm = await dialog.showMessageBox({
+    type: 'warning',
+    message: 'Replace your Drift data with this backup?',
+    detail: `Sessions, notes and queued uploads are replaced by the ${count} sessions in ${path}, made ${new Date(backup.createdAt).toLocaleString()}. Only restore backups you made yourself. Your settings are kept.`,
+    buttons: ['Restore', 'Cancel'],
+    defaultId: 1,
+    cancelId: 1
+  })
+  if (confirm.response !== 0) return { error: 'Cancelled' }
+
+  const s = await getStore()
+  const userData = app.getPath('userData')
+  try {
+    await fs.copyFile(s.path, `${s.path}.pre-restore.bak`).catch((error) => {
+      if (error.code !== 'ENOENT') throw error
+    })
+
+    const kept: Record<string, any> = {}
+    for (const key of [...MACHINE_KEYS, ...LOCAL_KEYS]) {
+      if (s.has(key)) kept[key] = s.get(key)
+    }
+    const restored = { ...backup.store }
+    for (const key of LOCAL_KEYS) delete restored[key]
+    s.store = { ...restored, ...kept, schemaVersion: backup.schemaVersion }
+    await migrateStore(s)
+
+    for (const entry of backup.media) {
+      if (!entry.data) continue
+      const file = join(userData, ...entry.path.split('/'))
+      await fs.mkdir(dirname(file), { recursive: true })
+      await writeMedia(file, Buffer.from(entry.data, 'base64'))
+    }
+  } catch (error: any) {
+    return { error: `Restore failed: ${error.message}` }
+  }
+
+  const sessions = Array.isArray(backup.store.sessions) ? backup.store.sessions.length : 0
+  const mediaFiles = backup.media.filter((m) => m.data).length
+  console.log('[Backup] Restored', sessions, 'sessions and', mediaFiles, 'media files from', path)
+
+  // Services hold state read at startup, so the restore only fully applies after a restart
+  const { response } = await dialog.showMessageBox({
+    type: 'info',
+    message: 'Backup restored',
+    detail: `${sessions} sessions were restored. Restart Drift to finish.`,
+    buttons: ['Restart now', 'Later'],
+    defaultId: 0,
+    cancelId: 1
+  })
+  if (response === 0) {
+    app.relaunch()
+    app.exit(0)
+  }
+  return { ok: true, sessions, mediaFiles }
+}
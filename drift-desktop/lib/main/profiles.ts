@@ -0,0 +1,100 @@
+import { app } from 'electron'
+import { EventEmitter } from 'events'
+import { randomUUID } from 'crypto'
+import { mkdirSync, readFileSync, writeFileSync } from 'fs'
+import { join } from 'path'
+
+// A separate set of local data: store, settings, accounts and data key
+export interface Profile {
+  id: string
+  name: string
+  createdAt: number
+}
+
+interface ProfileRegistry {
+  active: string
+  profiles: Profile[]
+}
+
+// Uses the app data folder itself, so data from before profiles existed stays where it is
+const DEFAULT_PROFILE: Profile = { id: 'default', name: 'Default', createdAt: 0 }
+
+/**
+ * Local profiles such as work and personal. The registry is a small plain
+ * file next to the profiles, read synchronously because the store location
+ * depends on it. Emits `switched` with the new and previous profile; the
+ * store and data key are reopened from the new profile's folder after that.
+ */
+class ProfileManager extends EventEmitter {
+  private registry: ProfileRegistry | null = null
+
+  /**
+   * Folder holding a profile's data, by default the active one's.
+   */
+  dataDir(id: string = this.read().active): string {
+    const userData = app.getPath('userData')
+    return id === DEFAULT_PROFILE.id ? userData : join(userData, 'profiles', id)
+  }
+
+  getActive(): Profile {
+    const registry = this.read()
+    return registry.profiles.find((p) => p.id === registry.active) ?? DEFAULT_PROFILE
+  }
+
+  list(): Array<Profile & { active: boolean }> {
+    const registry = this.read()
+    return registry.profiles.map((p) => ({ ...p, active: p.id === registry.active }))
+  }
+
+  create(name: string): Profile | { error: string } {
+    const trimmed = name.trim()
+    if (!trimmed) return { error: 'Profile name is empty' }
+    const registry = this.read()
+    if (registry.profiles.some((p) => p.name.toLowerCase() === trimmed.toLowerCase())) {
+      return { error: `A profile named ${trimmed} already exists` }
+    }
+
+    const profile: Profile = { id: randomUUID(), name: trimmed, createdAt: Date.now() }
+    mkdirSync(this.dataDir(profile.id), { recursive: true })
+    registry.profiles.push(profile)
+    this.write(registry)
+    console.log('[Profiles] Created', profile.name)
+    return profile
+  }
+
+  /**
+   * Make a profile active. Returns null for an unknown id.
+   */
+  switchTo(id: string): Profile | null {
+    const registry = this.read()
+    const next = registry.profiles.find((p) => p.id === id)
+    if (!next) return null
+    const prev = this.getActive()
+    if (prev.id === next.id) return next
+
+    registry.active = next.id
+    this.write(registry)
+    console.log('[Profiles] Switched to', next.name)
+    this.emit('switched', next, prev)
+    return next
+  }
+
+  private read(): ProfileRegistry {
+    if (!this.registry) {
+      try {
+        this.registry = JSON.parse(readFileSync(join(app.getPath('userData'), 'profiles.json'), 'utf8'))
+      } catch {
+        this.registry = { active: DEFAULT_PROFILE.id, profiles: [DEFAULT_PROFILE] }
+      }
+    }
+    return this.registry!
+  }
+
+  private write(registry: ProfileRegistry): void {
+    this.registry = registry
+    writeFileSync(join(app.getPath('userData'), 'profiles.json'), JSON.stringify(registry, null, 2))
+  }
+}
+
+// Singleton instance
+export const profiles = new ProfileManager()
@@ -0,0 +1,56 @@
+import { app, session } from 'electron'
+import { getSettings, onSettingsChanged, Settings } from './settings'
+
+/**
+ * Routes all main-process traffic through the configured proxy.
+ * Requests go through Electron's network stack (see `http` in api.ts),
+ * so the system proxy, PAC scripts and the manual proxy setting all apply.
+ */
+class ProxyManager {
+  private credentials: { username: string; password: string } | null = null
+
+  async start(): Promise<void> {
+    // Answer proxy auth challenges with the configured credentials
+    app.on('login', (event, _webContents, _details, authInfo, callback) => {
+      if (!authInfo.isProxy || !this.credentials) return
+      event.preventDefault()
+      callback(this.credentials.username, this.credentials.password)
+    })
+
+    onSettingsChanged((next, prev) => {
+      if (JSON.stringify(next.proxy) !== JSON.stringify(prev.proxy)) {
+        this.apply(next.proxy)
+      }
+    })
+
+    await this.apply((await getSettings()).proxy)
+  }
+
+  private async apply(proxy: Settings['proxy']): Promise<void> {
+    this.credentials = proxy.username ? { username: proxy.username, password: proxy.password } : null
+
+    if (proxy.mode === 'manual' && proxy.url) {
+      let rules = proxy.url
+      try {
+        // Credentials are supplied on challenge, never as part of the proxy rule
+        const url = new URL(proxy.url)
+        if (url.username && !this.credentials) {
+          this.credentials = { username: decodeURIComponent(url.username), password: decodeURIComponent(url.password) }
+        }
+        rules = `${url.protocol}//${url.host}`
+      } catch {
+        // Not a URL; pass through as a Chromium proxy rule such as "host:port"
+      }
+      await session.defaultSession.setProxy({ mode: 'fixed_servers', proxyRules: rules })
+    } else {
+      await session.defaultSession.setProxy({ mode: proxy.mode === 'direct' ? 'direct' : 'system' })
+    }
+
+    // Drop connections opened through the previous proxy
+    await session.defaultSession.closeAllConnections()
+    console.log('[Proxy] Mode:', proxy.mode)
+  }
+}
+
+// Singleton instance
+export const proxyManager = new ProxyManager()
@@ -0,0 +1,132 @@
+import { app, BrowserWindow, screen } from 'electron'
+import { join } from 'path'
+import { randomUUID } from 'crypto'
+import { windowRegistry } from './windowRegistry'
+import { sessionManager } from './session'
+import { getStore } from './store'
+import { emitEvent } from './events'
+
+const WIDTH = 480
+const HEIGHT = 56
+
+// A note taken while nothing was recording
+export interface StandaloneNote {
+  id: string
+  text: string
+  createdAt: number
+}
+
+export type QuickCaptureResult =
+  | { ok: true; sessionId: string }
+  | { ok: true; standalone: StandaloneNote }
+  | { error: string }
+
+/**
+ * Jot a note from anywhere: a small input window on the display under the
+ * cursor (`#quick-capture` view), or `capture(text)` directly. Notes go to the
+ * active session, or are kept as standalone notes when nothing is recording.
+ * The main window is never shown or focused.
+ */
+class QuickCapture {
+  private window: BrowserWindow | null = null
+
+  show(): BrowserWindow {
+    if (this.window && !this.window.isDestroyed()) {
+      this.window.show()
+      this.window.focus()
+      return this.window
+    }
+
+    const { workArea } = screen.getDisplayNearestPoint(screen.getCursorScreenPoint())
+    const win = new BrowserWindow({
+      width: WIDTH,
+      height: HEIGHT,
+      x: Math.round(workArea.x + (workArea.width - WIDTH) / 2),
+      y: Math.round(workArea.y + workArea.height / 4),
+      frame: false,
+      resizable: false,
+      minimizable: false,
+      maximizable: false,
+      fullscreenable: false,
+      skipTaskbar: true,
+      alwaysOnTop: true,
+      show: false,
+      title: 'Drift Quick Note',
+      backgroundColor: '#0a0a0b',
+      webPreferences: {
+        preload: join(__dirname, '../preload/preload.js'),
+        sandbox: false,
+        contextIsolation: true,
+        nodeIntegration: false
+      }
+    })
+    win.setVisibleOnAllWorkspaces(true, { visibleOnFullScreen: true })
+    win.once('ready-to-show', () => {
+      win.show()
+      win.focus()
+    })
+    // Clicking elsewhere dismisses it, like a launcher
+    win.on('blur', () => this.hide())
+    win.on('closed', () => {
+      if (this.window === win) this.window = null
+    })
+    windowRegistry.addWindow('quick-capture', win)
+
+    if (!app.isPackaged && process.env['ELECTRON_RENDERER_URL']) {
+      win.loadURL(`${process.env['ELECTRON_RENDERER_URL']}#quick-capture`)
+    } else {
+      win.loadFile(join(__dirname, '../renderer/index.html'), { hash: 'quick-capture' })
+    }
+
+    this.window = win
+    return win
+  }
+
+  hide(): void {
+    if (this.window && !this.window.isDestroyed()) {
+      this.window.close()
+    }
+    this.window = null
+  }
+
+  /**
+   * File a note against the active session, or keep it as a standalone note.
+   */
+  async capture(text: string): Promise<QuickCaptureResult> {
+    const trimmed = text?.trim()
+    if (!trimmed) {
+      return { error: 'Note is empty' }
+    }
+
+    if (sessionManager.getActive()) {
+      const result = await sessionManager.appendNote(trimmed)
+      if (result.error) return { error: result.error }
+      emitEvent('quick-capture:saved', { text: trimmed, sessionId: result.sessionId })
+      return { ok: true, sessionId: result.sessionId }
+    }
+
+    const note: StandaloneNote = { id: randomUUID(), text: trimmed, createdAt: Date.now() }
+    const s = await getStore()
+    const notes: StandaloneNote[] = s.get('standaloneNotes') || []
+    notes.push(note)
+    s.set('standaloneNotes', notes)
+    emitEvent('quick-capture:saved', { text: trimmed, sessionId: null })
+    return { ok: true, standalone: note }
+  }
+
+  async listStandalone(): Promise<StandaloneNote[]> {
+    const s = await getStore()
+    return ((s.get('standaloneNotes') || []) as StandaloneNote[]).sort((a, b) => b.createdAt - a.createdAt)
+  }
+
+  async removeStandalone(id: string): Promise<boolean> {
+    const s = await getStore()
+    const notes: StandaloneNote[] = s.get('standaloneNotes') || []
+    const remaining = notes.filter((n) => n.id !== id)
+    s.set('standaloneNotes', remaining)
+    return remaining.length !== notes.length
+  }
+}
+
+// Singleton instance
+export const quickCapture = new QuickCapture()
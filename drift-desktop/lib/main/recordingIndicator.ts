@@ -0,0 +1,92 @@
+import { BrowserWindow, Rectangle } from 'electron'
+import { sessionManager } from './session'
+import { screenCapture } from './capture'
+import { getSettings, onSettingsChanged, Settings } from './settings'
+
+const BORDER_HTML = `
+  <html><body style="margin:0;height:100vh;box-sizing:border-box;border:3px solid #ef4444;background:transparent"></body></html>
+`
+
+const BADGE_HTML = `
+  <html><body style="margin:0;background:transparent;font:600 11px -apple-system,'Segoe UI',sans-serif">
+    <div style="position:fixed;top:8px;right:8px;padding:3px 8px;border-radius:9px;background:#ef4444;color:white">● REC</div>
+  </body></html>
+`
+
+// Size of the corner badge window
+const BADGE_SIZE = { width: 72, height: 32 }
+
+/**
+ * Red frame or corner badge over the captured screen area while a session
+ * records. Click-through and excluded from screen capture, so it never ends
+ * up in screenshots or gets in the way.
+ */
+class RecordingIndicator {
+  private window: BrowserWindow | null = null
+  private generation = 0
+
+  start(): void {
+    sessionManager.on('started', () => this.show())
+    sessionManager.on('restored', () => this.show())
+    sessionManager.on('capture-changed', () => this.show())
+    sessionManager.on('ended', () => this.hide())
+    onSettingsChanged((next, prev) => {
+      if (JSON.stringify(next.recordingIndicator) === JSON.stringify(prev.recordingIndicator)) return
+      if (sessionManager.getActive()) this.show()
+    })
+  }
+
+  private async show(): Promise<void> {
+    this.hide()
+    const generation = this.generation
+    const { recordingIndicator } = await getSettings()
+    if (!recordingIndicator.enabled || !sessionManager.getActive()) return
+
+    const area = await screenCapture.getCaptureBounds()
+    if (generation !== this.generation) return // hidden or shown again meanwhile
+    const bounds = this.boundsFor(recordingIndicator.style, area)
+    const win = new BrowserWindow({
+      ...bounds,
+      frame: false,
+      transparent: true,
+      hasShadow: false,
+      resizable: false,
+      movable: false,
+      focusable: false,
+      skipTaskbar: true,
+      alwaysOnTop: true,
+      show: false,
+      backgroundColor: '#00000000'
+    })
+    win.setAlwaysOnTop(true, 'screen-saver', 2)
+    win.setIgnoreMouseEvents(true)
+    win.setVisibleOnAllWorkspaces(true, { visibleOnFullScreen: true })
+    // Keep the overlay out of the session's own screenshots
+    win.setContentProtection(true)
+
+    const html = recordingIndicator.style === 'badge' ? BADGE_HTML : BORDER_HTML
+    win.loadURL(`data:text/html;charset=utf-8,${encodeURIComponent(html)}`)
+    win.once('ready-to-show', () => win.showInactive())
+    this.window = win
+  }
+
+  private hide(): void {
+    this.generation++
+    if (this.window && !this.window.isDestroyed()) {
+      this.window.destroy()
+    }
+    this.window = null
+  }
+
+  private boundsFor(style: Settings['recordingIndicator']['style'], area: Rectangle): Rectangle {
+    if (style === 'border') return area
+    return {
+      x: area.x + area.width - BADGE_SIZE.width,
+      y: area.y,
+      ...BADGE_SIZE
+    }
+  }
+}
+
+// Singleton instance
+export const recordingIndicator = new RecordingIndicator()
@@ -0,0 +1,111 @@
+import { app } from 'electron'
+import { promises as fs } from 'fs'
+
+// How often the app's own resource usage is sampled during a session
+const SAMPLE_SECONDS = 30
+
+// Drift's own footprint (all its processes) at one point of a session
+export interface ResourceSample {
+  at: number
+  cpuPercent: number // summed over processes; may exceed 100 on multi-core machines
+  memoryMB: number // working set
+  diskWriteKBps: number | null // only where per-process I/O counters are readable (Linux)
+}
+
+export interface PerformanceReport {
+  samples: ResourceSample[]
+  avgCpuPercent: number
+  peakCpuPercent: number
+  peakMemoryMB: number
+  avgDiskWriteKBps: number | null
+}
+
+/**
+ * Averages and peaks over a series of samples.
+ */
+export function summarizeUsage(samples: ResourceSample[]): PerformanceReport {
+  const writes = samples.map((s) => s.diskWriteKBps).filter((w): w is number => w !== null)
+  const avg = (values: number[]) => (values.length > 0 ? values.reduce((sum, v) => sum + v, 0) / values.length : 0)
+
+  return {
+    samples,
+    avgCpuPercent: Math.round(avg(samples.map((s) => s.cpuPercent)) * 10) / 10,
+    peakCpuPercent: Math.max(0, ...samples.map((s) => s.cpuPercent)),
+    peakMemoryMB: Math.max(0, ...samples.map((s) => s.memoryMB)),
+    avgDiskWriteKBps: writes.length > 0 ? Math.round(avg(writes)) : null
+  }
+}
+
+async function bytesWritten(pids: number[]): Promise<number | null> {
+  if (process.platform !== 'linux') return null
+  let total = 0
+  for (const pid of pids) {
+    const io = await fs.readFile(`/proc/${pid}/io`, 'utf8').catch(() => null)
+    const match = io?.match(/^write_bytes:\s*(\d+)/m)
+    if (match) total += Number(match[1])
+  }
+  return total
+}
+
+/**
+ * Samples CPU, memory and disk writes of every Drift process while a
+ * session records, to diagnose reports of the app slowing a machine down.
+ */
+class ResourceMonitor {
+  private intervalId: NodeJS.Timeout | null = null
+  private samples: ResourceSample[] = []
+  private lastWrite: { at: number; bytes: number } | null = null
+
+  start(): void {
+    if (this.intervalId) return
+    this.samples = []
+    this.lastWrite = null
+    this.intervalId = setInterval(() => this.sample(), SAMPLE_SECONDS * 1000)
+    this.sample()
+  }
+
+  /**
+   * Stop sampling and return the series.
+   */
+  stop(): ResourceSample[] {
+    if (this.intervalId) {
+      clearInterval(this.intervalId)
+      this.intervalId = null
+    }
+    const samples = this.samples
+    this.samples = []
+    return samples
+  }
+
+  getSamples(): ResourceSample[] {
+    return this.samples.map((s) => ({ ...s }))
+  }
+
+  private async sample(): Promise<void> {
+    const at = Date.now()
+    const metrics = app.getAppMetrics()
+    const cpuPercent = metrics.reduce((sum, m) => sum + m.cpu.percentCPUUsage, 0)
+    const memoryKB = metrics.reduce((sum, m) => sum + m.memory.workingSetSize, 0)
+
+    let diskWriteKBps: number | null = null
+    const bytes = await bytesWritten(metrics.map((m) => m.pid))
+    if (bytes !== null) {
+      if (this.lastWrite && at > this.lastWrite.at) {
+        diskWriteKBps = Math.max(0, Math.round((bytes - this.lastWrite.bytes) / 1024 / ((at - this.lastWrite.at) / 1000)))
+      }
+      this.lastWrite = { at, bytes }
+    }
+
+    // Stopped while reading the counters
+    if (!this.intervalId) return
+    this.samples.push({
+      at,
+      cpuPercent: Math.round(cpuPercent * 10) / 10,
+      memoryMB: Math.round(memoryKB / 1024),
+      diskWriteKBps
+    })
+  }
+}
+
+// Singleton instance
+export const resourceMonitor = new ResourceMonitor()
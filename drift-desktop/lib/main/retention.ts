@@ -0,0 +1,86 @@
+import { emitEvent } from './events'
+import { getSettings } from './settings'
+import { sessionHistory, SessionRecord, TRASH_DAYS } from './sessionHistory'
+import { jobScheduler } from './jobs'
+
+// How often the retention policy is enforced
+const CHECK_INTERVAL_MS = 6 * 60 * 60 * 1000
+
+const DAY_MS = 24 * 60 * 60 * 1000
+
+export interface PurgeResult {
+  sessions: number // sessions that had artifacts removed
+  bytesReclaimed: number
+}
+
+// Approximate on-disk size of the artifacts the policy removes
+function artifactBytes(record: SessionRecord): number {
+  const { timeline, inputActivity, clips, screenText } = record
+  return Buffer.byteLength(JSON.stringify({ timeline, inputActivity, clips, screenText }))
+}
+
+/**
+ * Enforces the retention setting: drops the bulky per-session artifacts
+ * (timeline, input metrics, clips, screen text) of old sessions while keeping their
+ * metadata, summary and notes. Runs as a job every few hours.
+ */
+class RetentionManager {
+  start(): void {
+    jobScheduler.register('retention', () => this.enforce())
+    void jobScheduler.schedule('retention', { every: CHECK_INTERVAL_MS })
+
+    // Independent of the retention setting: the trash is always emptied eventually
+    jobScheduler.register('trash-purge', async () => {
+      const purged = await sessionHistory.emptyTrash(TRASH_DAYS)
+      if (purged > 0) console.log('[Retention] Purged', purged, 'sessions from the trash')
+    })
+    void jobScheduler.schedule('trash-purge', { every: CHECK_INTERVAL_MS })
+  }
+
+  stop(): void {
+    void jobScheduler.cancel('retention')
+    void jobScheduler.cancel('trash-purge')
+  }
+
+  /**
+   * Purge now, regardless of whether the policy is enabled. `mediaDays` that
+   * isn't a number of days (≥ 0) is ignored in favour of the setting.
+   */
+  async purgeNow(mediaDays?: unknown): Promise<PurgeResult> {
+    const { retention } = await getSettings()
+    const valid = typeof mediaDays === 'number' && Number.isFinite(mediaDays) && mediaDays >= 0
+    return this.purge(valid ? mediaDays : retention.mediaDays)
+  }
+
+  private async enforce(): Promise<void> {
+    const { retention } = await getSettings()
+    if (!retention.enabled) return
+    await this.purge(retention.mediaDays)
+  }
+
+  private async purge(mediaDays: number): Promise<PurgeResult> {
+    const cutoff = Date.now() - Math.max(0, mediaDays) * DAY_MS
+    const result: PurgeResult = { sessions: 0, bytesReclaimed: 0 }
+
+    await sessionHistory.updateAll((record) => {
+      if (record.endedAt >= cutoff) return
+      if (!record.timeline && !record.inputActivity && !record.clips && !record.screenText) return
+
+      result.bytesReclaimed += artifactBytes(record)
+      result.sessions++
+      delete record.timeline
+      delete record.inputActivity
+      delete record.clips
+      delete record.screenText
+    })
+
+    if (result.sessions > 0) {
+      console.log('[Retention] Purged', result.sessions, 'sessions,', result.bytesReclaimed, 'bytes')
+    }
+    emitEvent('retention:purged', result)
+    return result
+  }
+}
+
+// Singleton instance
+export const retentionManager = new RetentionManager()
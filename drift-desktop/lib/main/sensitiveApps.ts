@@ -0,0 +1,57 @@
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { clipboardWatcher } from './clipboardWatcher'
+import { emitEvent } from './events'
+import { matchesApp } from './privacy'
+
+/**
+ * Pauses capture while a sensitive app (per `privacy.sensitiveApps`) is in
+ * the foreground, based on the activity tracker's window samples. The
+ * session keeps running: screenshots are already withheld for these apps
+ * (see captureAllowed), and clipboard capture is paused too if configured.
+ * Each stretch is recorded as a privacy pause and shown in the timeline.
+ */
+class SensitiveAppGuard {
+  private current: { app: string; since: number; clipboardPaused: boolean } | null = null
+
+  start(): void {
+    sessionManager.on('activity', ({ app }) => this.onForegroundApp(app))
+    sessionManager.on('paused', ({ at }) => this.leave(at, false))
+    sessionManager.on('ended', ({ at }) => this.leave(at, false))
+  }
+
+  private async onForegroundApp(app: string): Promise<void> {
+    const { privacy } = await getSettings()
+    const sensitive = matchesApp(app, privacy.sensitiveApps)
+
+    if (this.current && (!sensitive || app !== this.current.app)) {
+      this.leave(Date.now(), true)
+    }
+    if (!sensitive || this.current || sessionManager.getPauseReason()) return
+
+    const clipboardPaused = privacy.pauseClipboardForSensitive
+    if (clipboardPaused) {
+      clipboardWatcher.pause()
+    }
+    this.current = { app, since: Date.now(), clipboardPaused }
+    emitEvent('privacy:capture-paused', { app })
+    console.log('[Privacy] Capture paused for', app)
+  }
+
+  // `resumeCapture` is false when the whole session paused or ended meanwhile
+  private leave(at: number, resumeCapture: boolean): void {
+    if (!this.current) return
+    const { app, since, clipboardPaused } = this.current
+    this.current = null
+
+    if (clipboardPaused && resumeCapture) {
+      clipboardWatcher.resume()
+    }
+    sessionManager.markPrivacyPause({ start: since, end: Math.max(at, since), app })
+    emitEvent('privacy:capture-resumed', { app })
+    console.log('[Privacy] Capture resumed after', app)
+  }
+}
+
+// Singleton instance
+export const sensitiveAppGuard = new SensitiveAppGuard()
@@ -0,0 +1,966 @@
+import { Rectangle } from 'electron'
+import { EventEmitter } from 'events'
+import { activityTracker, ActivityEntry, AggregatedActivity, ManualNote } from './activityTracker'
+import { emitEvent } from './events'
+import { sessionHistory, toTimeline, TimelineEntry } from './sessionHistory'
+import { driftApi, getAuthToken, toDriftError, DriftError } from './api'
+import { accounts } from './accounts'
+import { requireLiveToken } from './authInfo'
+import { getStore } from './store'
+import { getSettings, Settings } from './settings'
+import { notify } from './notifications'
+import { missingPermissions } from './permissions'
+import { screenCapture } from './capture'
+import { inputActivity, InputActivityBucket } from './inputActivity'
+import { clipboardWatcher, Clip } from './clipboardWatcher'
+import { screenOcr, searchScreenText, ScreenTextHit } from './ocr'
+import { connectivity } from './connectivity'
+import { uploadQueue } from './uploadQueue'
+import { checkDiskSpace } from './diskSpace'
+import { captureAllowed } from './privacy'
+import { resourceMonitor, summarizeUsage, PerformanceReport } from './resourceUsage'
+import { RecordingState, RecordingStateMachine } from '../state/RecordingStateMachine'
+import { currentTimeZone, offsetMinutesAt } from './timezone'
+import { runSessionPlugins } from './plugins'
+import { capturePresets } from './presets'
+
+// The session currently being tracked
+export interface ActiveSession {
+  sessionId: string
+  account: string | null
+  briefId: string
+  briefName: string
+  role: string
+  startedAt: number // UTC epoch ms
+  timeZone: string // IANA zone of this machine at the start
+  startOffsetMinutes: number // local offset from UTC at the start, east positive
+  captureTarget: string | null // display/window id, null for the primary display
+  captureRegion: Rectangle | null // screen rectangle, overrides captureTarget
+  presetId: string | null // capture preset resolved for the brief at the start
+}
+
+// Why a session was ended
+export type EndReason = 'user' | 'end-of-day' | 'remote' | 'switch' | 'quit' | 'low-battery' | 'low-disk' | 'handoff'
+
+// How often the running session is checkpointed to disk
+const CHECKPOINT_MS = 60 * 1000
+
+// How often `heartbeat` reports the elapsed time of the running session
+const HEARTBEAT_MS = 1000
+
+// Ended without the user asking for it; the UI and a notification must tell them
+function isAutomatic(reason: EndReason): boolean {
+  return reason === 'end-of-day' || reason === 'remote' || reason === 'low-battery' || reason === 'low-disk' || reason === 'handoff'
+}
+
+// Why a session was paused
+export type PauseReason = 'idle' | 'app-closed' | 'manual' | 'sleep' | 'locked'
+
+// A stretch of a session that does not count as work
+export interface IdleGap {
+  start: number
+  end: number
+  reason: PauseReason
+}
+
+// Time spent in a sensitive app, with capture paused; still counts as work
+export interface PrivacyPause {
+  start: number
+  end: number
+  app: string
+}
+
+// A stretch during which a capture component produced nothing; still counts as work
+export interface CaptureGap {
+  start: number
+  end: number
+  component: 'activity'
+}
+
+/**
+ * Build the session end payload, leaving out every artifact the user
+ * has not allowed to be uploaded.
+ */
+export function buildUploadManifest(
+  sessionId: string,
+  tracked: { activities: ActivityEntry[]; summary: AggregatedActivity[]; notes: ManualNote[] },
+  upload: Settings['upload'],
+  idleGaps: IdleGap[],
+  summary?: string
+) {
+  // Generate summary from activities + notes
+  let generatedSummary = summary
+  if (!generatedSummary) {
+    const summaryParts: string[] = []
+
+    // Add activity summary
+    for (const item of tracked.summary.slice(0, 3)) {
+      const mins = Math.floor(item.totalDuration / 60)
+      if (mins > 0) {
+        if (upload.windowTitles && item.files.length > 0) {
+          summaryParts.push(`${item.app}: ${item.files.slice(0, 3).join(', ')} (${mins}m)`)
+        } else {
+          summaryParts.push(`${item.app} (${mins}m)`)
+        }
+      }
+    }
+
+    // Add manual notes
+    if (upload.notes && tracked.notes.length > 0) {
+      summaryParts.push('Notes: ' + tracked.notes.map(n => n.text).join(', '))
+    }
+
+    generatedSummary = summaryParts.join(' | ')
+  }
+
+  // Convert to API format (include screenshots for relevant activities)
+  const activities = tracked.activities.map(a => ({
+    app: a.app,
+    title: upload.windowTitles ? a.title : '',
+    file: upload.windowTitles ? a.file : undefined,
+    duration: a.duration,
+    timestamp: a.timestamp,
+    screenshot: upload.screenshots ? a.screenshot : undefined
+  }))
+
+  return {
+    sessionId,
+    activities,
+    notes: upload.notes ? tracked.notes.map(n => n.text) : [],
+    summary: generatedSummary,
+    idleSeconds: Math.floor(idleGaps.reduce((sum, g) => sum + (g.end - g.start), 0) / 1000)
+  }
+}
+
+/**
+ * Owns the lifecycle of a work session: starting it on the backend,
+ * driving the activity tracker and screen analysis while it runs, and
+ * submitting the result when it ends.
+ * Emits `started`, `restored`, `ended`, `paused`, `resumed`, `marker`, `heartbeat`,
+ * `activity`, `uploaded`, `capture-changed` and `state-changed` for other main-process modules.
+ */
+class SessionManager extends EventEmitter {
+  private recording = new RecordingStateMachine()
+  private active: ActiveSession | null = null
+  private idleGaps: IdleGap[] = []
+  private captureGaps: CaptureGap[] = []
+  private privacyPauses: PrivacyPause[] = []
+  private pausedAt: { at: number; reason: PauseReason } | null = null
+  private screenshots = true // false when the session's preset records activity only
+  private checkpointInterval: NodeJS.Timeout | null = null
+  private heartbeatInterval: NodeJS.Timeout | null = null
+  private uploads = new Set<Promise<void>>()
+  private countdown: { timer: NodeJS.Timeout; finish: (completed: boolean) => void } | null = null
+
+  // Continuous screen analysis state
+  private screenAnalysisInterval: NodeJS.Timeout | null = null
+  private previousInsights: string[] = []
+  private projectName: string | null = null
+  private projectDescription: string | null = null
+
+  constructor() {
+    super()
+    this.recording.on('stateChange', (change: { prev: RecordingState; next: RecordingState }) => {
+      emitEvent('session:state-changed', change)
+      this.emit('state-changed', change)
+    })
+  }
+
+  getActive(): ActiveSession | null {
+    return this.active ? { ...this.active } : null
+  }
+
+  getRecordingState(): RecordingState {
+    return this.recording.state
+  }
+
+  getProjectName(): string | null {
+    return this.projectName
+  }
+
+  getPauseReason(): PauseReason | null {
+    return this.pausedAt?.reason ?? null
+  }
+
+  getPausedAt(): number | null {
+    return this.pausedAt?.at ?? null
+  }
+
+  /**
+   * Worked time of the running session, excluding idle gaps and the current pause.
+   * Measured on the monotonic clock; wall-clock times are only kept for display.
+   */
+  getElapsedSeconds(): number {
+    return this.active ? Math.floor(this.recording.elapsedMs() / 1000) : 0
+  }
+
+  /**
+   * Whether closing the app now would interrupt a recording or a submission.
+   */
+  hasPendingWork(): boolean {
+    return !!this.active || this.uploads.size > 0
+  }
+
+  /**
+   * Finalize before quitting: submit the running session and wait for
+   * submissions still in flight.
+   */
+  async shutdown(): Promise<void> {
+    this.cancelCountdown()
+    if (this.active) {
+      await this.end(undefined, 'quit')
+    }
+    await Promise.all(this.uploads)
+  }
+
+  /**
+   * Timeline of a session: live for the active session, from history otherwise.
+   * Null when the session is unknown or was recorded without the timeline setting.
+   */
+  async getTimeline(sessionId: string): Promise<TimelineEntry[] | null> {
+    if (this.active?.sessionId === sessionId) {
+      const { timeline } = await getSettings()
+      return timeline.enabled ? toTimeline(activityTracker.getActivities(), this.idleGaps, this.privacyPauses) : null
+    }
+    return (await sessionHistory.get(sessionId))?.timeline ?? null
+  }
+
+  /**
+   * Per-minute input activity of a session, or null if it wasn't recorded.
+   */
+  async getInputActivity(sessionId: string): Promise<InputActivityBucket[] | null> {
+    if (this.active?.sessionId === sessionId) {
+      const { inputMetrics } = await getSettings()
+      return inputMetrics.enabled ? inputActivity.getBuckets() : null
+    }
+    return (await sessionHistory.get(sessionId))?.inputActivity ?? null
+  }
+
+  /**
+   * Drift's own CPU, memory and disk-write usage over a session, or null for
+   * sessions recorded before sampling existed.
+   */
+  async getPerformanceReport(sessionId: string): Promise<PerformanceReport | null> {
+    if (this.active?.sessionId === sessionId) {
+      return summarizeUsage(resourceMonitor.getSamples())
+    }
+    const samples = (await sessionHistory.get(sessionId))?.resourceUsage
+    return samples ? summarizeUsage(samples) : null
+  }
+
+  /**
+   * Text snippets copied during a session.
+   */
+  async getClips(sessionId: string): Promise<Clip[]> {
+    if (this.active?.sessionId === sessionId) {
+      return clipboardWatcher.getClips()
+    }
+    return (await sessionHistory.get(sessionId))?.clips ?? []
+  }
+
+  /**
+   * Places in a session where the query was on screen, from OCR of its screenshots.
+   */
+  async searchScreenText(sessionId: string, query: string): Promise<ScreenTextHit[]> {
+    const entries = this.active?.sessionId === sessionId
+      ? screenOcr.getEntries()
+      : (await sessionHistory.get(sessionId))?.screenText ?? []
+    return searchScreenText(entries, query)
+  }
+
+  /**
+   * Add a note to the active session, marking this point in time.
+   */
+  addNote(text: string): void {
+    activityTracker.addNote(text)
+    if (this.active) {
+      this.emit('marker', { sessionId: this.active.sessionId, text, at: Date.now() })
+    }
+  }
+
+  /**
+   * Append a timestamped note to the active session, or to a completed one by id.
+   */
+  async appendNote(text: string, sessionId?: string): Promise<any> {
+    const trimmed = text?.trim()
+    if (!trimmed) {
+      return { error: 'Note is empty' }
+    }
+
+    if (!sessionId || sessionId === this.active?.sessionId) {
+      if (!this.active) {
+        return { error: 'No active session' }
+      }
+      this.addNote(trimmed)
+      return { ok: true, sessionId: this.active.sessionId }
+    }
+
+    const note = await sessionHistory.appendNote(sessionId, trimmed)
+    if (!note) {
+      return { error: `Unknown session: ${sessionId}` }
+    }
+    return { ok: true, sessionId, note }
+  }
+
+  /**
+   * Capture only this rectangle of the screen for the rest of the session; null captures the full target again.
+   */
+  setCaptureRegion(region: Rectangle | null): { error: string } | { ok: true } {
+    if (!this.active) {
+      return { error: 'No active session' }
+    }
+    const result = screenCapture.setRegion(region)
+    if ('ok' in result) {
+      this.active.captureRegion = region ? { ...region } : null
+      this.checkpoint()
+      this.emit('capture-changed', this.getActive())
+    }
+    return result
+  }
+
+  /**
+   * Pause the active session. `at` may lie in the past, e.g. when input stopped.
+   */
+  pause(reason: PauseReason, at: number = Date.now()): boolean {
+    if (!this.active || this.pausedAt || !this.recording.transition(RecordingState.Paused)) return false
+
+    this.pausedAt = { at, reason }
+    this.recording.discard(Date.now() - at)
+    activityTracker.pause(at)
+    inputActivity.pause()
+    clipboardWatcher.pause()
+    emitEvent('session:paused', { reason, at })
+    this.checkpoint()
+    this.emit('paused', { sessionId: this.active.sessionId, reason, at })
+    console.log('[Session] Paused:', reason)
+    return true
+  }
+
+  /**
+   * Resume a paused session, recording the pause as an idle gap.
+   */
+  resume(at: number = Date.now()): boolean {
+    if (!this.active || !this.pausedAt || !this.recording.transition(RecordingState.Recording)) return false
+
+    const gap = { start: this.pausedAt.at, end: Math.max(at, this.pausedAt.at), reason: this.pausedAt.reason }
+    this.idleGaps.push(gap)
+    this.pausedAt = null
+    activityTracker.resume()
+    inputActivity.resume()
+    clipboardWatcher.resume()
+    emitEvent('session:resumed', gap)
+    this.checkpoint()
+    this.emit('resumed', { sessionId: this.active.sessionId, ...gap })
+    console.log('[Session] Resumed after', Math.round((gap.end - gap.start) / 1000), 's')
+    return true
+  }
+
+  /**
+   * Record a stretch where capture stalled, so the session shows it as incomplete.
+   */
+  markCaptureGap(gap: CaptureGap): void {
+    if (!this.active) return
+    this.captureGaps.push(gap)
+    this.checkpoint()
+  }
+
+  /**
+   * Record time spent in a sensitive app. Unlike pause(), the session keeps running.
+   */
+  markPrivacyPause(pause: PrivacyPause): void {
+    if (!this.active) return
+    this.privacyPauses.push(pause)
+    this.checkpoint()
+  }
+
+  /**
+   * Move the running session to another brief. Depending on the setting, the
+   * session is reassigned as a whole, or ended and continued as a new session.
+   */
+  async switchBrief(briefId: string): Promise<any> {
+    if (!this.active) {
+      return { error: 'No active session' }
+    }
+    if (briefId === this.active.briefId) {
+      return { ok: true, sessionId: this.active.sessionId, briefId }
+    }
+
+    const { briefSwitch } = await getSettings()
+    const from = { sessionId: this.active.sessionId, briefId: this.active.briefId }
+
+    if (briefSwitch.mode === 'split') {
+      const { role, captureTarget, captureRegion } = this.active
+      const ended = await this.end(undefined, 'switch')
+      const started = await this.start(briefId, role, captureTarget)
+      if (started?.error) {
+        return { ...started, ended }
+      }
+      if (captureRegion) {
+        this.setCaptureRegion(captureRegion)
+      }
+      emitEvent('session:brief-switched', { mode: 'split', from, to: { sessionId: started.sessionId, briefId } })
+      return { ok: true, mode: 'split', ended, started }
+    }
+
+    const authToken = await getAuthToken()
+    if (!authToken) {
+      return { error: 'Not authenticated' }
+    }
+
+    try {
+      const data = await driftApi.reassignSession(from.sessionId, briefId)
+
+      // The session may have ended while the request was in flight
+      if (this.active?.sessionId !== from.sessionId) {
+        return { error: 'No active session' }
+      }
+      this.active.briefId = briefId
+      this.active.briefName = data.briefName || this.active.briefName
+      this.projectName = this.active.briefName
+      this.previousInsights = []
+      this.checkpoint()
+
+      emitEvent('session:brief-switched', { mode: 'reassign', from, to: { sessionId: from.sessionId, briefId } })
+      return { ok: true, mode: 'reassign', session: this.getActive() }
+    } catch (error) {
+      const failure = toDriftError(error)
+      console.error('Session reassign error:', failure.message)
+      return { error: failure.message }
+    }
+  }
+
+  /**
+   * Start a session after the configured countdown, so the user can switch to
+   * the right window first. Emits `session:countdown-tick` once per second.
+   */
+  async startAfterCountdown(briefId: string, role: string, captureTarget: string | null = null): Promise<any> {
+    if (this.active) {
+      return { error: 'Session already active', sessionId: this.active.sessionId }
+    }
+    if (this.countdown) {
+      return { error: 'Countdown already running' }
+    }
+
+    // Don't make the user wait through a countdown for a start that can't succeed
+    const authRequired = await requireLiveToken()
+    if (authRequired) return authRequired
+
+    const lowDisk = await checkDiskSpace()
+    if (lowDisk) return lowDisk
+
+    const { countdown } = await getSettings()
+    if (countdown.seconds > 0) {
+      if (!this.recording.transition(RecordingState.CountingDown)) {
+        return { error: `Cannot start while ${this.recording.state.toLowerCase()}` }
+      }
+      if (!(await this.runCountdown(countdown.seconds))) {
+        return { error: 'Countdown cancelled', cancelled: true }
+      }
+    }
+    return this.start(briefId, role, captureTarget)
+  }
+
+  /**
+   * Abort a running countdown; the session is not started.
+   */
+  cancelCountdown(): boolean {
+    if (!this.countdown) return false
+    clearInterval(this.countdown.timer)
+    this.countdown.finish(false)
+    this.countdown = null
+    this.recording.transition(RecordingState.Idle)
+    emitEvent('session:countdown-cancelled')
+    return true
+  }
+
+  private runCountdown(seconds: number): Promise<boolean> {
+    return new Promise((finish) => {
+      let remainingSeconds = Math.ceil(seconds)
+      emitEvent('session:countdown-tick', { remainingSeconds })
+
+      const timer = setInterval(() => {
+        remainingSeconds--
+        if (remainingSeconds > 0) {
+          emitEvent('session:countdown-tick', { remainingSeconds })
+          return
+        }
+        clearInterval(timer)
+        this.countdown = null
+        finish(true)
+      }, 1000)
+      this.countdown = { timer, finish }
+    })
+  }
+
+  /**
+   * Start a session for a brief. State is only set after the backend confirms.
+   * `captureTarget` restricts screen capture to one display or window (see capture:list-targets).
+   * Otherwise the brief's capture preset decides, see presets.ts.
+   * `continuesSessionId` links the session to one handed off from another device.
+   */
+  async start(
+    briefId: string,
+    role: string,
+    captureTarget: string | null = null,
+    continuesSessionId: string | null = null
+  ): Promise<any> {
+    // Check if session already active
+    if (this.active) {
+      return { error: 'Session already active', sessionId: this.active.sessionId }
+    }
+    if (!this.recording.can(RecordingState.Recording)) {
+      return { error: `Cannot start while ${this.recording.state.toLowerCase()}` }
+    }
+
+    const lowDisk = await checkDiskSpace()
+    if (lowDisk) {
+      this.abandonCountdown()
+      return lowDisk
+    }
+
+    const authRequired = await requireLiveToken()
+    if (authRequired) {
+      this.abandonCountdown()
+      return authRequired
+    }
+
+    try {
+      const data = await driftApi.startSession({
+        briefId,
+        role,
+        ...(continuesSessionId ? { continuesSessionId } : {})
+      })
+
+      // An explicit target wins over the preset's
+      const preset = await capturePresets.resolve(briefId)
+      captureTarget = captureTarget ?? preset?.captureTarget ?? null
+
+      // ONLY set state after successful response
+      const startedAt = Date.now()
+      const timeZone = currentTimeZone()
+      this.active = {
+        sessionId: data.sessionId,
+        account: (await accounts.getActive())?.name ?? null,
+        briefId: data.briefId || briefId,
+        briefName: data.briefName || 'Project',
+        role,
+        startedAt,
+        timeZone,
+        startOffsetMinutes: offsetMinutesAt(startedAt, timeZone),
+        captureTarget,
+        captureRegion: null,
+        presetId: preset?.id ?? null
+      }
+      screenCapture.setTarget(captureTarget)
+      this.idleGaps = []
+      this.captureGaps = []
+      this.privacyPauses = []
+      this.pausedAt = null
+
+      this.recording.transition(RecordingState.Recording)
+      await this.beginTracking()
+
+      emitEvent('session:started', data)
+      this.emit('started', this.getActive())
+      return data
+    } catch (error: any) {
+      this.active = null
+      this.abandonCountdown()
+
+      console.error('Session start error:', error.message)
+      if (error instanceof DriftError && (error.kind === 'network' || error.kind === 'timeout')) {
+        return { error: 'Keine Verbindung zum Server möglich' }
+      }
+      return { error: error.message || String(error) }
+    }
+  }
+
+  // A countdown that ends without a session falls back to idle
+  private abandonCountdown(): void {
+    if (this.recording.state === RecordingState.CountingDown) {
+      this.recording.transition(RecordingState.Idle)
+    }
+  }
+
+  /**
+   * Start everything that records a running session.
+   */
+  private async beginTracking(): Promise<void> {
+    const { role, briefName, briefId, presetId } = this.active!
+
+    const { inputMetrics, clipboard, capture, ocr } = await getSettings()
+    // A preset deleted since the start falls back to the global capture settings
+    const preset = presetId ? await capturePresets.resolve(briefId, presetId) : null
+    this.screenshots = preset?.screenshots ?? true
+
+    // NOW start tracking
+    activityTracker.setCaptureQuality(preset?.capture ?? capture)
+    activityTracker.setSessionScreenshots(this.screenshots)
+    activityTracker.start(role, (activity) => {
+      emitEvent('session:activity', activity)
+      this.emit('activity', activity)
+      screenOcr.offer(activity)
+    })
+    console.log('[Session] Started tracking activities for role:', role)
+
+    if (inputMetrics.enabled) {
+      inputActivity.start()
+    }
+    if (clipboard.enabled) {
+      clipboardWatcher.start(clipboard)
+    }
+    if (ocr.enabled) {
+      screenOcr.start(ocr)
+    }
+    resourceMonitor.start()
+
+    // Without these the session would record nothing but app names
+    const missing = missingPermissions(['screen', 'accessibility'])
+    if (missing.length > 0) {
+      console.warn('[Session] Missing permissions:', missing.join(', '))
+      emitEvent('permissions:missing', missing)
+    }
+
+    // Start continuous screen analysis AFTER session confirmed
+    this.startScreenAnalysis(15000, { name: briefName })
+
+    this.checkpointInterval = setInterval(() => this.checkpoint(), CHECKPOINT_MS)
+    this.heartbeatInterval = setInterval(() => this.heartbeat(), HEARTBEAT_MS)
+    await this.checkpoint()
+  }
+
+  /**
+   * Report the worked time of the running session, excluding idle gaps and the current pause.
+   */
+  private heartbeat(): void {
+    if (!this.active) return
+
+    const payload = {
+      sessionId: this.active.sessionId,
+      elapsedSeconds: this.getElapsedSeconds(),
+      paused: !!this.pausedAt
+    }
+    emitEvent('session:heartbeat', payload)
+    this.emit('heartbeat', payload)
+  }
+
+  /**
+   * Save the running session so it survives a crash or restart.
+   */
+  private async checkpoint(): Promise<void> {
+    const s = await getStore()
+    if (!this.active) {
+      s.delete('runningSession')
+      return
+    }
+    s.set('runningSession', {
+      active: this.active,
+      idleGaps: this.idleGaps,
+      captureGaps: this.captureGaps,
+      privacyPauses: this.privacyPauses,
+      pausedAt: this.pausedAt,
+      elapsedMs: this.recording.elapsedMs(),
+      savedAt: Date.now()
+    })
+  }
+
+  /**
+   * Continue a session that was running when the app last quit or crashed.
+   * The time the app was closed is recorded as an idle gap. Activity from
+   * before the restart was only held in memory and is lost.
+   */
+  async restore(): Promise<boolean> {
+    if (this.active || !this.recording.can(RecordingState.Recording)) return false
+
+    const s = await getStore()
+    const saved = s.get('runningSession')
+    if (!saved?.active) return false
+
+    this.active = saved.active as ActiveSession
+    // Checkpoints written before sessions kept their time zone
+    this.active.timeZone ??= currentTimeZone()
+    this.active.startOffsetMinutes ??= offsetMinutesAt(this.active.startedAt, this.active.timeZone)
+    this.active.presetId ??= null
+    this.idleGaps = saved.idleGaps || []
+    this.captureGaps = saved.captureGaps || []
+    this.privacyPauses = saved.privacyPauses || []
+    const closedAt = saved.pausedAt?.at ?? saved.savedAt
+    // Checkpoints from before the monotonic clock only have wall-clock times
+    const elapsedMs: number = saved.elapsedMs ??
+      closedAt - this.active.startedAt - this.idleGaps.reduce((sum, g) => sum + (g.end - g.start), 0)
+    this.idleGaps.push({ start: closedAt, end: Math.max(Date.now(), closedAt), reason: saved.pausedAt?.reason ?? 'app-closed' })
+    this.pausedAt = null
+    this.recording.transition(RecordingState.Recording)
+    this.recording.restoreElapsed(elapsedMs)
+
+    screenCapture.setTarget(this.active.captureTarget)
+    if (this.active.captureRegion) {
+      screenCapture.setRegion(this.active.captureRegion)
+    }
+
+    await this.beginTracking()
+    console.log('[Session] Restored session', this.active.sessionId)
+    emitEvent('session:restored', this.getActive()!)
+    this.emit('restored', this.getActive())
+    return true
+  }
+
+  /**
+   * End the active session, submit it to the backend and record it locally.
+   * Needs no sign-in: without a token the session is finalized and its upload
+   * queued, like an offline submission, so it goes out once the user signs in again.
+   */
+  async end(summary?: string, reason: EndReason = 'user'): Promise<any> {
+    if (!this.active) {
+      return { error: 'No active session' }
+    }
+    if (!this.recording.transition(RecordingState.Stopping)) {
+      return { error: 'Session is already stopping' }
+    }
+
+    this.stopScreenAnalysis()
+    screenCapture.setTarget(null)
+    screenCapture.setRegion(null)
+
+    const session = this.active
+    const endedAt = Date.now()
+
+    // Close an open pause so it counts as idle time
+    if (this.pausedAt) {
+      this.idleGaps.push({ start: this.pausedAt.at, end: endedAt, reason: this.pausedAt.reason })
+      this.pausedAt = null
+    }
+    const idleGaps = this.idleGaps
+    this.idleGaps = []
+    const captureGaps = this.captureGaps
+    this.captureGaps = []
+    const privacyPauses = this.privacyPauses
+    this.privacyPauses = []
+
+    // Stop activity tracking and get results
+    const tracked = activityTracker.stop()
+    const { summary: activitySummary, notes } = tracked
+    console.log('[Session] Stopped tracking, got', tracked.activities.length, 'relevant activities,', notes.length, 'notes')
+
+    const inputBuckets = inputActivity.stop()
+    const clips = clipboardWatcher.stop()
+    const screenText = screenOcr.stop()
+    const resourceUsage = resourceMonitor.stop()
+
+    const { upload, timeline, inputMetrics } = await getSettings()
+    const manifest = buildUploadManifest(session.sessionId, tracked, upload, idleGaps, summary)
+
+    this.active = null
+    if (this.checkpointInterval) {
+      clearInterval(this.checkpointInterval)
+      this.checkpointInterval = null
+    }
+    if (this.heartbeatInterval) {
+      clearInterval(this.heartbeatInterval)
+      this.heartbeatInterval = null
+    }
+    await this.checkpoint()
+    const { briefId, briefName } = session
+    this.emit('ended', { sessionId: session.sessionId, briefId, reason, at: endedAt })
+    this.recording.transition(RecordingState.Finalizing)
+
+    // Post-session plugins see the manifest before it goes out and can extend the summary
+    const plugins = await runSessionPlugins({
+      hook: 'session-finished',
+      session: { id: session.sessionId, briefId, briefName, role: session.role, startedAt: session.startedAt, endedAt },
+      manifest
+    })
+    if (plugins.summaryLines.length > 0) {
+      manifest.summary = [manifest.summary, ...plugins.summaryLines].filter(Boolean).join(' | ')
+    }
+
+    const record = {
+      id: session.sessionId,
+      account: session.account,
+      briefId,
+      briefName,
+      role: session.role,
+      startedAt: session.startedAt,
+      endedAt,
+      timeZone: session.timeZone,
+      startOffsetMinutes: session.startOffsetMinutes,
+      // Differs from the start offset when DST began or ended during the session
+      endOffsetMinutes: offsetMinutesAt(endedAt, session.timeZone),
+      // The clock stopped when the session entered Stopping
+      durationSeconds: Math.floor(this.recording.elapsedMs() / 1000),
+      idleGaps,
+      summary: manifest.summary,
+      summaryLines: [] as string[],
+      submissionId: undefined as string | undefined,
+      activitySummary,
+      notes,
+      timeline: timeline.enabled ? toTimeline(tracked.activities, idleGaps, privacyPauses) : undefined,
+      inputActivity: inputMetrics.enabled && inputBuckets.length > 0 ? inputBuckets : undefined,
+      clips: clips.length > 0 ? clips : undefined,
+      screenText: screenText.length > 0 ? screenText : undefined,
+      captureGaps: captureGaps.length > 0 ? captureGaps : undefined,
+      privacyPauses: privacyPauses.length > 0 ? privacyPauses : undefined,
+      resourceUsage: resourceUsage.length > 0 ? resourceUsage : undefined,
+      uploaded: false
+    }
+
+    // Offline or signed out: keep the submission and send it when the connection or sign-in returns
+    const signedIn = !!(await getAuthToken())
+    if (!connectivity.isOnline() || !signedIn) {
+      await sessionHistory.add(record)
+      await plugins.attach(session.sessionId)
+      await uploadQueue.enqueue(session.sessionId, session.account, manifest)
+      this.recording.transition(RecordingState.Idle)
+      const result = { queued: true, activitySummary, notes, briefId, briefName }
+      emitEvent('session:ended', result)
+      if (isAutomatic(reason)) {
+        emitEvent('session:auto-ended', { ...result, reason })
+      }
+      await notify(
+        'uploadFailed',
+        signedIn ? 'Drift · Offline' : 'Drift · Signed out',
+        signedIn ? `${briefName} will be uploaded when you're back online.` : `${briefName} will be uploaded when you sign in again.`
+      )
+      return result
+    }
+
+    let settle!: () => void
+    const submission = new Promise<void>((resolve) => (settle = resolve))
+    this.uploads.add(submission)
+
+    try {
+      const data = await driftApi.endSession(manifest)
+      await sessionHistory.add({
+        ...record,
+        summaryLines: data.summaryLines || [],
+        submissionId: data.submissionId,
+        uploaded: true
+      })
+      await plugins.attach(session.sessionId)
+      this.emit('uploaded', session.sessionId)
+
+      const result = { ...data, activitySummary, notes, briefId, briefName }
+      emitEvent('session:ended', result)
+      if (isAutomatic(reason)) {
+        emitEvent('session:auto-ended', { ...result, reason })
+        await notify('sessionAutoStopped', 'Drift · Session stopped', `${briefName} was stopped and submitted.`)
+      } else {
+        await notify('uploadComplete', 'Drift · Session submitted', `${briefName} was uploaded.`)
+      }
+      return result
+    } catch (error) {
+      const failure = toDriftError(error)
+      console.error('Session end error:', failure.message)
+      await sessionHistory.add(record)
+      await plugins.attach(session.sessionId)
+
+      // Never reached the server: queue it like an offline submission
+      const queued = !failure.reachedServer
+      if (queued) {
+        await uploadQueue.enqueue(session.sessionId, session.account, manifest)
+      }
+
+      // Provide detailed error
+      let message = failure.message
+      if (failure.kind === 'timeout') {
+        message = 'Request timeout - server nicht erreichbar'
+      } else if (failure.reachedServer) {
+        message = `Server error: ${failure.status}`
+      } else if (failure.kind === 'network') {
+        message = 'Keine Verbindung zum Server'
+      }
+
+      if (isAutomatic(reason)) {
+        emitEvent('session:auto-ended', { error: message, briefId, briefName, reason })
+        await notify('sessionAutoStopped', 'Drift · Session stopped', `${briefName} was stopped but could not be uploaded: ${message}`)
+      } else {
+        await notify('uploadFailed', 'Drift · Upload failed', `${briefName}: ${message}`)
+      }
+      return { error: message, briefId, briefName, queued, retry: failure.retry }
+    } finally {
+      this.uploads.delete(submission)
+      this.recording.transition(RecordingState.Idle)
+      settle()
+    }
+  }
+
+  /**
+   * Periodically capture the screen and ask the backend for new insights.
+   */
+  startScreenAnalysis(intervalMs: number, projectInfo?: { name: string; description?: string }): void {
+    if (this.screenAnalysisInterval) {
+      clearInterval(this.screenAnalysisInterval)
+    }
+
+    this.previousInsights = []
+    this.projectName = projectInfo?.name || null
+    this.projectDescription = projectInfo?.description || null
+
+    this.screenAnalysisInterval = setInterval(() => this.analyzeScreen(), intervalMs)
+
+    // Run immediately
+    this.analyzeScreen()
+  }
+
+  stopScreenAnalysis(): void {
+    if (this.screenAnalysisInterval) {
+      clearInterval(this.screenAnalysisInterval)
+      this.screenAnalysisInterval = null
+    }
+    this.previousInsights = []
+  }
+
+  private async analyzeScreen(): Promise<void> {
+    const authToken = await getAuthToken()
+    const status = activityTracker.getStatus()
+
+    if (!authToken || !status.isTracking || status.isPaused) {
+      return
+    }
+
+    // Screen analysis uploads screenshots, so it is off when those are kept local
+    const { upload } = await getSettings()
+    if (!upload.screenshots || !this.screenshots || !(await captureAllowed(activityTracker.getCurrentApp()))) {
+      return
+    }
+
+    try {
+      // Capture the current target
+      const frame = await screenCapture.captureFrame(1 / 3, 50)
+      if (!frame) return
+
+      const screenshot = frame.toString('base64')
+
+      // Send to backend for analysis
+      const data = await driftApi.analyzeScreen({
+        screenshot,
+        projectName: this.projectName,
+        projectDescription: this.projectDescription,
+        previousInsights: this.previousInsights.slice(-10)
+      })
+
+      if (data.bullets && data.bullets.length > 0 && !data.skip) {
+        // Add to previous insights to avoid repetition
+        this.previousInsights.push(...data.bullets)
+
+        // Keep only last 20 insights
+        if (this.previousInsights.length > 20) {
+          this.previousInsights = this.previousInsights.slice(-20)
+        }
+
+        // Broadcast to renderer
+        emitEvent('session:screen-insight', {
+          bullets: data.bullets,
+          timestamp: Date.now()
+        })
+
+        console.log('[ScreenAnalysis] New insights:', data.bullets)
+      }
+    } catch (error) {
+      console.error('[ScreenAnalysis] Error:', error)
+    }
+  }
+}
+
+// Singleton instance
+export const sessionManager = new SessionManager()
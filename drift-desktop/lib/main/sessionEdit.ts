@@ -0,0 +1,295 @@
+import { randomUUID } from 'crypto'
+import { sessionHistory, SessionRecord, TimelineEntry, SessionEdit, EditableField } from './sessionHistory'
+import { sessionManager } from './session'
+import { uploadQueue } from './uploadQueue'
+import { briefCache } from './briefs'
+import { sessionAttachments } from './attachments'
+import { jobScheduler } from './jobs'
+import { driftApi, DriftError, SessionUpdateRequest } from './api'
+import { offsetMinutesAt } from './timezone'
+import type { AggregatedActivity } from './activityTracker'
+
+type Interval = { start: number; end: number }
+
+// Parts of `intervals` inside [from, to], clamped to it
+function clampIntervals<T extends Interval>(intervals: T[] | undefined, from: number, to: number): T[] {
+  return (intervals ?? [])
+    .filter((i) => i.end > from && i.start < to)
+    .map((i) => ({ ...i, start: Math.max(i.start, from), end: Math.min(i.end, to) }))
+}
+
+function idleSeconds(gaps: Interval[]): number {
+  return Math.floor(gaps.reduce((sum, g) => sum + (g.end - g.start), 0) / 1000)
+}
+
+// Timestamped artifacts in [from, to)
+function between<T>(items: T[] | undefined, at: (item: T) => number, from: number, to: number): T[] | undefined {
+  const inside = (items ?? []).filter((item) => at(item) >= from && at(item) < to)
+  return inside.length > 0 ? inside : undefined
+}
+
+function mergeActivity(lists: AggregatedActivity[][]): AggregatedActivity[] {
+  const byApp = new Map<string, AggregatedActivity>()
+  for (const activity of lists.flat()) {
+    const existing = byApp.get(activity.app)
+    if (existing) {
+      existing.totalDuration += activity.totalDuration
+      existing.files = [...new Set([...existing.files, ...activity.files])]
+      existing.titles = [...new Set([...existing.titles, ...activity.titles])]
+    } else {
+      byApp.set(activity.app, { ...activity, files: [...activity.files], titles: [...activity.titles] })
+    }
+  }
+  return [...byApp.values()].sort((a, b) => b.totalDuration - a.totalDuration)
+}
+
+function activityFromTimeline(timeline: TimelineEntry[]): AggregatedActivity[] {
+  return mergeActivity([
+    timeline
+      .filter((e) => !e.locked && !e.private)
+      .map((e) => ({ app: e.app, totalDuration: e.durationSeconds, files: e.file ? [e.file] : [], titles: e.title ? [e.title] : [] }))
+  ])
+}
+
+function concat<T>(lists: Array<T[] | undefined>): T[] | undefined {
+  const all = lists.flatMap((l) => l ?? [])
+  return all.length > 0 ? all : undefined
+}
+
+// Sessions that can't be edited right now: the one recording, and those still waiting to upload
+async function checkEditable(ids: string[]): Promise<string | null> {
+  const active = sessionManager.getActive()?.sessionId
+  if (active && ids.includes(active)) return 'End the active session first'
+  const pending = await uploadQueue.pendingSessionIds()
+  const waiting = ids.find((id) => pending.includes(id))
+  return waiting ? `Session ${waiting} is still waiting to upload` : null
+}
+
+/**
+ * Combine sessions of the same brief into one, e.g. after an accidental double
+ * start. The time between them becomes an idle gap, artifacts are concatenated
+ * and the earliest session's id is kept. Submissions already on the server are
+ * not changed: the merged record counts as uploaded only if every part was,
+ * and lists the parts and their submissions in `mergedFrom`.
+ */
+export async function mergeSessions(ids: string[]): Promise<{ ok: true; session: SessionRecord } | { error: string }> {
+  const unique = [...new Set(ids)]
+  if (unique.length < 2) return { error: 'Select at least two sessions to merge' }
+
+  const records: SessionRecord[] = []
+  for (const id of unique) {
+    const record = await sessionHistory.get(id)
+    if (!record) return { error: `Unknown session: ${id}` }
+    records.push(record)
+  }
+  const blocked = await checkEditable(unique)
+  if (blocked) return { error: blocked }
+  if (new Set(records.map((r) => r.briefId)).size > 1) return { error: 'Only sessions of the same brief can be merged' }
+  if (new Set(records.map((r) => r.account)).size > 1) return { error: 'Sessions were recorded under different accounts' }
+
+  records.sort((a, b) => a.startedAt - b.startedAt)
+  const [first] = records
+  const last = records.reduce((a, b) => (b.endedAt > a.endedAt ? b : a))
+
+  // Time between the parts doesn't count as work
+  const breaks: Interval[] = []
+  for (let i = 1; i < records.length; i++) {
+    const prevEnd = Math.max(...records.slice(0, i).map((r) => r.endedAt))
+    if (records[i].startedAt > prevEnd) breaks.push({ start: prevEnd, end: records[i].startedAt })
+  }
+  const idleGaps = [...records.flatMap((r) => r.idleGaps), ...breaks.map((g) => ({ ...g, reason: 'manual' as const }))]
+    .sort((a, b) => a.start - b.start)
+
+  const merged: SessionRecord = {
+    ...first,
+    endedAt: last.endedAt,
+    durationSeconds: records.reduce((sum, r) => sum + r.durationSeconds, 0),
+    idleGaps,
+    summary: records.map((r) => r.summary).filter(Boolean).join('\n'),
+    summaryLines: records.flatMap((r) => r.summaryLines),
+    activitySummary: mergeActivity(records.map((r) => r.activitySummary)),
+    notes: records.flatMap((r) => r.notes).sort((a, b) => a.timestamp - b.timestamp),
+    timeline: concat(records.map((r) => r.timeline)),
+    inputActivity: concat(records.map((r) => r.inputActivity)),
+    clips: concat(records.map((r) => r.clips)),
+    screenText: concat(records.map((r) => r.screenText)),
+    captureGaps: concat(records.map((r) => r.captureGaps)),
+    privacyPauses: concat(records.map((r) => r.privacyPauses)),
+    resourceUsage: concat(records.map((r) => r.resourceUsage)),
+    shareLinks: concat(records.map((r) => r.shareLinks)),
+    uploaded: records.every((r) => r.uploaded),
+    mergedFrom: [...(first.mergedFrom ?? []), ...records.slice(1).map((r) => ({ id: r.id, submissionId: r.submissionId }))]
+  }
+
+  await sessionHistory.replace(records.map((r) => r.id), [merged])
+  await sessionAttachments.reassign(records.slice(1).map((r) => r.id), merged.id)
+  console.log('[Sessions] Merged', records.length, 'sessions into', merged.id)
+  return { ok: true, session: merged }
+}
+
+/**
+ * Split a session at `at`, e.g. after a forgotten stop. Notes and other
+ * timestamped artifacts go to the part they fall in. The first part keeps the
+ * id and upload state; the second is a new, local-only session. Per-app
+ * activity is rebuilt from the timeline when there is one, otherwise it stays
+ * with the first part.
+ */
+export async function splitSession(id: string, at: number): Promise<{ ok: true; sessions: [SessionRecord, SessionRecord] } | { error: string }> {
+  const record = await sessionHistory.get(id)
+  if (!record) return { error: `Unknown session: ${id}` }
+  const blocked = await checkEditable([id])
+  if (blocked) return { error: blocked }
+  if (!(at > record.startedAt && at < record.endedAt)) return { error: 'Split time must lie within the session' }
+
+  const part = (from: number, to: number): Partial<SessionRecord> => {
+    const idleGaps = clampIntervals(record.idleGaps, from, to)
+    const timeline = between(record.timeline, (e) => e.start, from, to)
+    return {
+      startedAt: from,
+      endedAt: to,
+      ...(record.timeZone
+        ? { startOffsetMinutes: offsetMinutesAt(from, record.timeZone), endOffsetMinutes: offsetMinutesAt(to, record.timeZone) }
+        : {}),
+      durationSeconds: Math.max(0, Math.floor((to - from) / 1000) - idleSeconds(idleGaps)),
+      idleGaps,
+      notes: record.notes.filter((n) => n.timestamp >= from && n.timestamp < to),
+      timeline,
+      inputActivity: between(record.inputActivity, (b) => b.minute, from, to),
+      clips: between(record.clips, (c) => c.timestamp, from, to),
+      screenText: between(record.screenText, (t) => t.at, from, to),
+      captureGaps: clampIntervals(record.captureGaps, from, to),
+      privacyPauses: clampIntervals(record.privacyPauses, from, to),
+      resourceUsage: between(record.resourceUsage, (s) => s.at, from, to),
+      ...(record.timeline ? { activitySummary: activityFromTimeline(timeline ?? []) } : {})
+    }
+  }
+
+  const firstPart: SessionRecord = { ...record, ...part(record.startedAt, at) }
+  const secondPart: SessionRecord = {
+    ...record,
+    ...part(at, record.endedAt),
+    id: randomUUID(),
+    summary: '',
+    summaryLines: [],
+    submissionId: undefined,
+    shareLinks: undefined,
+    mergedFrom: undefined,
+    activitySummary: record.timeline ? activityFromTimeline(between(record.timeline, (e) => e.start, at, record.endedAt) ?? []) : [],
+    uploaded: false,
+    splitFrom: record.id
+  }
+  for (const r of [firstPart, secondPart]) {
+    if (r.captureGaps?.length === 0) delete r.captureGaps
+    if (r.privacyPauses?.length === 0) delete r.privacyPauses
+  }
+
+  await sessionHistory.replace([record.id], [firstPart, secondPart])
+  console.log('[Sessions] Split', record.id, 'into', firstPart.id, 'and', secondPart.id)
+  return { ok: true, sessions: [firstPart, secondPart] }
+}
+
+export interface SessionPatch {
+  title?: string | null // null or empty clears it
+  description?: string | null
+  briefId?: string
+  startedAt?: number // correct a late start; the end stays
+}
+
+/**
+ * Correct a session after the fact. Every change is kept in the record's
+ * `edits`; for uploaded sessions it is also sent to the server in the
+ * background, retried until it gets an answer.
+ */
+export async function updateSession(id: string, patch: SessionPatch): Promise<{ ok: true; session: SessionRecord } | { error: string }> {
+  const record = await sessionHistory.get(id)
+  if (!record) return { error: `Unknown session: ${id}` }
+  const blocked = await checkEditable([id])
+  if (blocked) return { error: blocked }
+
+  const changes: SessionEdit['changes'] = {}
+  const apply: Partial<SessionRecord> = {}
+  for (const field of ['title', 'description'] as const) {
+    if (patch[field] === undefined) continue
+    const value = patch[field]?.trim() || undefined
+    if (value === record[field]) continue
+    changes[field] = { from: record[field] ?? null, to: value ?? null }
+    apply[field] = value
+  }
+
+  if (patch.briefId !== undefined && patch.briefId !== record.briefId) {
+    const result = await briefCache.fetch()
+    if ('error' in result) return { error: `Could not load briefs: ${result.error}` }
+    const brief = result.briefs.find((b) => b.id === patch.briefId)
+    if (!brief) return { error: `Unknown brief: ${patch.briefId}` }
+    changes.briefId = { from: record.briefId, to: brief.id }
+    apply.briefId = brief.id
+    apply.briefName = brief.name
+  }
+
+  if (patch.startedAt !== undefined && patch.startedAt !== record.startedAt) {
+    if (!(patch.startedAt > 0 && patch.startedAt < record.endedAt)) return { error: 'Start must be before the end of the session' }
+    const idleGaps = clampIntervals(record.idleGaps, patch.startedAt, record.endedAt)
+    changes.startedAt = { from: record.startedAt, to: patch.startedAt }
+    apply.startedAt = patch.startedAt
+    if (record.timeZone) apply.startOffsetMinutes = offsetMinutesAt(patch.startedAt, record.timeZone)
+    apply.idleGaps = idleGaps
+    apply.durationSeconds = Math.max(0, Math.floor((record.endedAt - patch.startedAt) / 1000) - idleSeconds(idleGaps))
+  }
+
+  if (Object.keys(changes).length === 0) return { ok: true, session: record }
+
+  const edit: SessionEdit = { at: Date.now(), changes, sync: record.uploaded ? 'pending' : 'local' }
+  const updated = await sessionHistory.update(id, (r) => {
+    Object.assign(r, apply)
+    r.edits = [...(r.edits ?? []), edit]
+  })
+  if (!updated) return { error: `Unknown session: ${id}` }
+
+  if (record.uploaded) {
+    await jobScheduler.schedule('session-edit-sync', { at: Date.now() }, { id: `session-edit:${id}`, payload: { sessionId: id }, maxAttempts: 10 })
+  }
+  console.log('[Sessions] Edited', id, ':', Object.keys(changes).join(', '))
+  return { ok: true, session: updated }
+}
+
+/**
+ * Send the current values of every field with unsynced edits. A change the
+ * server refuses is marked rejected instead of being retried.
+ */
+async function syncEdits(payload: unknown): Promise<void> {
+  const { sessionId } = payload as { sessionId: string }
+  const record = await sessionHistory.get(sessionId)
+  const pending = (record?.edits ?? []).filter((e) => e.sync === 'pending')
+  if (!record || pending.length === 0) return
+
+  const fields = new Set(pending.flatMap((e) => Object.keys(e.changes) as EditableField[]))
+  const request: SessionUpdateRequest = {}
+  if (fields.has('title')) request.title = record.title ?? ''
+  if (fields.has('description')) request.description = record.description ?? ''
+  if (fields.has('briefId')) request.briefId = record.briefId
+  if (fields.has('startedAt')) request.startedAt = record.startedAt
+
+  let outcome: Pick<SessionEdit, 'sync' | 'error'> = { sync: 'synced' }
+  try {
+    await driftApi.updateSession(sessionId, request)
+  } catch (error) {
+    // Retry on network trouble, server errors and expired tokens
+    if (!(error instanceof DriftError) || error.kind === 'rejected' || error.status === null || error.status >= 500) throw error
+    outcome = { sync: 'rejected', error: error.message }
+    console.warn('[Sessions] Server rejected edits of', sessionId, ':', error.message)
+  }
+
+  const sent = new Set(pending.map((e) => e.at))
+  await sessionHistory.update(sessionId, (r) => {
+    for (const e of r.edits ?? []) {
+      if (e.sync === 'pending' && sent.has(e.at)) Object.assign(e, outcome)
+    }
+  })
+}
+
+/**
+ * Handle edit syncs queued before the last quit.
+ */
+export function startSessionEditSync(): void {
+  jobScheduler.register('session-edit-sync', syncEdits)
+}
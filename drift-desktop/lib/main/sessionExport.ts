@@ -0,0 +1,424 @@
+import { app, shell } from 'electron'
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { sessionHistory, SessionRecord, formatDuration } from './sessionHistory'
+import { getWebBaseUrl } from './api'
+import { profiles } from './profiles'
+import { isoWithOffset, localParts, offsetMinutesAt } from './timezone'
+
+// Times and dates are shown in the zone a session was recorded in, not where it is exported
+function time(ms: number, timeZone?: string): string {
+  const p = localParts(ms, timeZone)
+  return `${String(p.hours).padStart(2, '0')}:${String(p.minutes).padStart(2, '0')}`
+}
+
+function localDate(ms: number, timeZone?: string): string {
+  const p = localParts(ms, timeZone)
+  return `${p.year}-${String(p.month).padStart(2, '0')}-${String(p.day).padStart(2, '0')}`
+}
+
+/**
+ * Start and end as ISO 8601 with the offsets captured when the session was recorded.
+ */
+export function sessionIsoTimes(record: SessionRecord): { started: string; ended: string } {
+  return {
+    started: isoWithOffset(record.startedAt, record.startOffsetMinutes ?? offsetMinutesAt(record.startedAt, record.timeZone)),
+    ended: isoWithOffset(record.endedAt, record.endOffsetMinutes ?? offsetMinutesAt(record.endedAt, record.timeZone))
+  }
+}
+
+function slug(text: string): string {
+  return text.toLowerCase().replace(/[^a-z0-9]+/g, '-').replace(/^-+|-+$/g, '').slice(0, 40) || 'session'
+}
+
+/**
+ * Stable file name for a session's export, e.g. 2026-10-15-api-redesign-1a2b3c4d.md
+ */
+export function sessionFileName(record: SessionRecord, extension: string): string {
+  return `${localDate(record.startedAt, record.timeZone)}-${slug(record.briefName)}-${record.id.slice(0, 8)}.${extension}`
+}
+
+/**
+ * Render a session as a Markdown recap.
+ */
+export function renderSessionMarkdown(record: SessionRecord, webUrl: string): string {
+  const lines: string[] = []
+  const idleSeconds = Math.floor(record.idleGaps.reduce((sum, g) => sum + (g.end - g.start), 0) / 1000)
+
+  const tz = record.timeZone
+  lines.push(`# ${record.briefName} — ${localDate(record.startedAt, tz)}`, '')
+  lines.push(`- **Time:** ${time(record.startedAt, tz)}–${time(record.endedAt, tz)}${tz ? ` (${tz})` : ''}`)
+  lines.push(`- **Worked:** ${formatDuration(record.durationSeconds)}${idleSeconds > 0 ? ` (${formatDuration(idleSeconds)} idle excluded)` : ''}`)
+  lines.push(`- **Role:** ${record.role}`)
+  if (record.submissionId) {
+    lines.push(`- **Submission:** \`${record.submissionId}\` ([open Drift](${webUrl}))`)
+  } else {
+    lines.push('- **Submission:** not uploaded')
+  }
+  lines.push('')
+
+  const summary = record.summaryLines.length > 0 ? record.summaryLines : record.summary ? [record.summary] : []
+  if (summary.length > 0) {
+    lines.push('## Summary', '')
+    for (const line of summary) lines.push(`- ${line}`)
+    lines.push('')
+  }
+
+  if (record.notes.length > 0) {
+    lines.push('## Notes', '')
+    for (const note of [...record.notes].sort((a, b) => a.timestamp - b.timestamp)) {
+      lines.push(`- \`${time(note.timestamp, tz)}\` ${note.text}`)
+    }
+    lines.push('')
+  }
+
+  if (record.activitySummary.length > 0) {
+    lines.push('## Activity', '')
+    lines.push('| App | Time | Files |', '| --- | --- | --- |')
+    for (const a of record.activitySummary) {
+      const files = a.files.slice(0, 5).map((f) => `\`${f}\``).join(', ')
+      lines.push(`| ${a.app} | ${formatDuration(a.totalDuration)} | ${files} |`)
+    }
+    lines.push('')
+  }
+
+  if (record.idleGaps.length > 0) {
+    lines.push('## Breaks', '')
+    for (const gap of record.idleGaps) {
+      lines.push(`- ${time(gap.start, tz)}–${time(gap.end, tz)} (${gap.reason})`)
+    }
+    lines.push('')
+  }
+
+  return lines.join('\n')
+}
+
+/**
+ * Write a session's Markdown recap to Documents/Drift and return the file path.
+ */
+export async function exportSessionMarkdown(sessionId: string): Promise<{ path: string } | { error: string }> {
+  const record = await sessionHistory.get(sessionId)
+  if (!record) {
+    return { error: `Unknown session: ${sessionId}` }
+  }
+
+  const dir = join(app.getPath('documents'), 'Drift')
+  await fs.mkdir(dir, { recursive: true })
+
+  const path = join(dir, sessionFileName(record, 'md'))
+  await fs.writeFile(path, renderSessionMarkdown(record, await getWebBaseUrl()))
+  console.log('[Export] Wrote', path)
+  return { path }
+}
+
+async function exists(path: string): Promise<boolean> {
+  return fs.access(path).then(
+    () => true,
+    () => false
+  )
+}
+
+/**
+ * Open the system file manager at what the session has on disk: its Markdown
+ * export in Documents/Drift if there is one, else its attachments folder.
+ * Nothing is written; a session with neither gets an error.
+ */
+export async function revealSessionFiles(sessionId: string): Promise<{ path: string } | { error: string }> {
+  const record = await sessionHistory.get(sessionId)
+  if (!record) {
+    return { error: `Unknown session: ${sessionId}` }
+  }
+
+  const exported = join(app.getPath('documents'), 'Drift', sessionFileName(record, 'md'))
+  if (await exists(exported)) {
+    shell.showItemInFolder(exported)
+    return { path: exported }
+  }
+
+  const attachments = join(profiles.dataDir(), 'attachments', sessionId)
+  if (await exists(attachments)) {
+    const error = await shell.openPath(attachments)
+    return error ? { error } : { path: attachments }
+  }
+  return { error: 'This session has no files on disk; export it first' }
+}
+
+const ORG_DAYS = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat']
+
+// Org-mode inactive timestamp, e.g. [2026-10-15 Thu 09:30]
+function orgTimestamp(ms: number, timeZone?: string): string {
+  return `[${localDate(ms, timeZone)} ${ORG_DAYS[localParts(ms, timeZone).weekday]} ${time(ms, timeZone)}]`
+}
+
+// h:mm, as Org writes clock durations
+function orgDuration(seconds: number): string {
+  const minutes = Math.floor(seconds / 60)
+  return `${Math.floor(minutes / 60)}:${String(minutes % 60).padStart(2, '0')}`
+}
+
+function orgTag(text: string): string {
+  return text.replace(/[^A-Za-z0-9_@]+/g, '_').replace(/^_+|_+$/g, '')
+}
+
+/**
+ * Render a session as an Org-mode entry: properties, one CLOCK line per
+ * worked stretch (idle gaps split the session), and notes as sub-headings.
+ */
+export function renderSessionOrg(record: SessionRecord, webUrl: string): string {
+  const lines: string[] = []
+  const tags = ['drift', orgTag(record.role)].filter(Boolean)
+
+  lines.push(`* ${record.briefName} :${tags.join(':')}:`)
+  lines.push('  :PROPERTIES:')
+  lines.push(`  :DRIFT_ID: ${record.id}`)
+  lines.push(`  :BRIEF: ${record.briefName}`)
+  lines.push(`  :BRIEF_ID: ${record.briefId}`)
+  lines.push(`  :ROLE: ${record.role}`)
+  lines.push(`  :DURATION: ${orgDuration(record.durationSeconds)}`)
+  if (record.submissionId) {
+    lines.push(`  :SUBMISSION: ${record.submissionId}`)
+    lines.push(`  :URL: ${webUrl}`)
+  }
+  lines.push('  :END:')
+
+  // Worked stretches between idle gaps
+  const gaps = [...record.idleGaps].sort((a, b) => a.start - b.start)
+  const stretches: Array<[number, number]> = []
+  let from = record.startedAt
+  for (const gap of gaps) {
+    if (gap.start > from) stretches.push([from, Math.min(gap.start, record.endedAt)])
+    from = Math.max(from, gap.end)
+  }
+  if (record.endedAt > from) stretches.push([from, record.endedAt])
+
+  lines.push('  :LOGBOOK:')
+  for (const [start, end] of stretches.reverse()) {
+    lines.push(`  CLOCK: ${orgTimestamp(start, record.timeZone)}--${orgTimestamp(end, record.timeZone)} => ${orgDuration((end - start) / 1000).padStart(5)}`)
+  }
+  lines.push('  :END:')
+
+  const summary = record.summaryLines.length > 0 ? record.summaryLines : record.summary ? [record.summary] : []
+  if (summary.length > 0) {
+    lines.push('** Summary')
+    for (const line of summary) lines.push(`- ${line}`)
+  }
+
+  if (record.notes.length > 0) {
+    lines.push('** Notes')
+    for (const note of [...record.notes].sort((a, b) => a.timestamp - b.timestamp)) {
+      lines.push(`*** ${orgTimestamp(note.timestamp, record.timeZone)} ${note.text}`)
+    }
+  }
+
+  return lines.join('\n') + '\n'
+}
+
+/**
+ * Write a session's Org-mode entry to `path`, or to Documents/Drift by default.
+ */
+export async function exportSessionOrg(sessionId: string, path?: string): Promise<{ path: string } | { error: string }> {
+  const record = await sessionHistory.get(sessionId)
+  if (!record) {
+    return { error: `Unknown session: ${sessionId}` }
+  }
+
+  if (!path) {
+    const dir = join(app.getPath('documents'), 'Drift')
+    await fs.mkdir(dir, { recursive: true })
+    path = join(dir, sessionFileName(record, 'org'))
+  }
+
+  try {
+    await fs.writeFile(path, renderSessionOrg(record, await getWebBaseUrl()))
+  } catch (error: any) {
+    return { error: `Could not write ${path}: ${error.message}` }
+  }
+  console.log('[Export] Wrote', path)
+  return { path }
+}
+
+/**
+ * Append sessions in `range` to an Org journal file, skipping sessions
+ * already in it (matched by their DRIFT_ID property), so it can be run repeatedly.
+ */
+export async function appendOrgJournal(
+  path: string,
+  range?: { from?: number; to?: number }
+): Promise<{ path: string; appended: number } | { error: string }> {
+  const existing = await fs.readFile(path, 'utf8').catch(() => '')
+  const records = (await sessionHistory.list(range))
+    .filter((r) => !existing.includes(`:DRIFT_ID: ${r.id}`))
+    .sort((a, b) => a.startedAt - b.startedAt)
+  if (records.length === 0) {
+    return { path, appended: 0 }
+  }
+
+  const webUrl = await getWebBaseUrl()
+  const separator = existing && !existing.endsWith('\n') ? '\n' : ''
+  try {
+    await fs.appendFile(path, separator + records.map((r) => renderSessionOrg(r, webUrl)).join(''))
+  } catch (error: any) {
+    return { error: `Could not write ${path}: ${error.message}` }
+  }
+  console.log('[Export] Appended', records.length, 'sessions to', path)
+  return { path, appended: records.length }
+}
+
+const CSV_COLUMNS = ['date', 'start', 'end', 'brief', 'tags', 'duration_hours', 'duration', 'idle_minutes', 'notes', 'uploaded', 'session_id', 'started_at', 'ended_at', 'time_zone']
+
+// Quote fields that contain separators, quotes or line breaks
+function csvField(value: string | number): string {
+  const text = String(value)
+  return /[",\r\n]/.test(text) ? `"${text.replace(/"/g, '""')}"` : text
+}
+
+/**
+ * Render sessions as CSV, one row per session, oldest first. Durations exclude
+ * idle time; duration_hours is decimal for billing spreadsheets. Local columns
+ * use the zone the session was recorded in; started_at/ended_at carry the offset.
+ */
+export function renderSessionsCsv(records: SessionRecord[]): string {
+  const rows = [...records]
+    .sort((a, b) => a.startedAt - b.startedAt)
+    .map((r) => [
+      localDate(r.startedAt, r.timeZone),
+      time(r.startedAt, r.timeZone),
+      time(r.endedAt, r.timeZone),
+      r.briefName,
+      ['drift', r.role].filter(Boolean).join(';'),
+      (r.durationSeconds / 3600).toFixed(2),
+      formatDuration(r.durationSeconds),
+      Math.round(r.idleGaps.reduce((sum, g) => sum + (g.end - g.start), 0) / 60000),
+      r.notes.length,
+      r.uploaded ? 'yes' : 'no',
+      r.id,
+      ...Object.values(sessionIsoTimes(r)),
+      r.timeZone ?? ''
+    ])
+  return [CSV_COLUMNS, ...rows].map((row) => row.map(csvField).join(',')).join('\r\n') + '\r\n'
+}
+
+/**
+ * Write sessions in `range` to a CSV file, by default Documents/Drift/drift-sessions-<date>.csv.
+ */
+export async function exportSessionsCsv(
+  range?: { from?: number; to?: number },
+  path?: string
+): Promise<{ path: string; sessions: number } | { error: string }> {
+  const records = await sessionHistory.list(range)
+
+  if (!path) {
+    const dir = join(app.getPath('documents'), 'Drift')
+    await fs.mkdir(dir, { recursive: true })
+    path = join(dir, `drift-sessions-${localDate(Date.now())}.csv`)
+  }
+
+  try {
+    await fs.writeFile(path, renderSessionsCsv(records))
+  } catch (error: any) {
+    return { error: `Could not write ${path}: ${error.message}` }
+  }
+  console.log('[Export] Wrote', records.length, 'sessions to', path)
+  return { path, sessions: records.length }
+}
+
+export type MarkerFormat = 'srt' | 'vtt' | 'ffmetadata' | 'youtube'
+
+const MARKER_EXTENSIONS: Record<MarkerFormat, string> = {
+  srt: 'srt',
+  vtt: 'vtt',
+  ffmetadata: 'ffmetadata.txt',
+  youtube: 'chapters.txt'
+}
+
+// How long a subtitle cue stays up, unless the next marker comes sooner
+const CUE_MS = 5000
+
+// hh:mm:ss plus milliseconds after `separator` (',' for SRT, '.' for WebVTT)
+function cueTime(ms: number, separator: string): string {
+  const s = Math.floor(ms / 1000)
+  const pad = (n: number, width = 2) => String(n).padStart(width, '0')
+  return `${pad(Math.floor(s / 3600))}:${pad(Math.floor((s % 3600) / 60))}:${pad(s % 60)}${separator}${pad(ms % 1000, 3)}`
+}
+
+// m:ss, or h:mm:ss from an hour on, as YouTube reads chapter times
+function chapterTime(ms: number): string {
+  const s = Math.floor(ms / 1000)
+  const h = Math.floor(s / 3600)
+  const mm = Math.floor((s % 3600) / 60)
+  const ss = String(s % 60).padStart(2, '0')
+  return h > 0 ? `${h}:${String(mm).padStart(2, '0')}:${ss}` : `${mm}:${ss}`
+}
+
+// FFmpeg metadata values escape '=', ';', '#', '\' and line breaks with a backslash
+function ffmetadataValue(text: string): string {
+  return text.replace(/[=;#\\\n]/g, (c) => `\\${c}`)
+}
+
+/**
+ * Render a session's markers and notes as subtitle cues or chapters. Times are
+ * offsets from the session start, so they line up with a recording started with it.
+ */
+export function renderSessionMarkers(record: SessionRecord, format: MarkerFormat): string {
+  const length = record.endedAt - record.startedAt
+  const markers = [...record.notes]
+    .sort((a, b) => a.timestamp - b.timestamp)
+    .map((n) => ({ at: Math.min(Math.max(0, n.timestamp - record.startedAt), length), text: n.text.replace(/\s+/g, ' ').trim() }))
+    .filter((m) => m.text)
+  const endOf = (i: number) => (i + 1 < markers.length ? markers[i + 1].at : length)
+
+  switch (format) {
+    case 'srt':
+      return markers
+        .map((m, i) => `${i + 1}\n${cueTime(m.at, ',')} --> ${cueTime(Math.min(m.at + CUE_MS, endOf(i)), ',')}\n${m.text}\n`)
+        .join('\n')
+    case 'vtt':
+      return ['WEBVTT', '', ...markers.map((m, i) => `${cueTime(m.at, '.')} --> ${cueTime(Math.min(m.at + CUE_MS, endOf(i)), '.')}\n${m.text}\n`)].join('\n')
+    case 'ffmetadata': {
+      const lines = [';FFMETADATA1', `title=${ffmetadataValue(record.briefName)}`]
+      markers.forEach((m, i) => {
+        lines.push('', '[CHAPTER]', 'TIMEBASE=1/1000', `START=${m.at}`, `END=${endOf(i)}`, `title=${ffmetadataValue(m.text)}`)
+      })
+      return lines.join('\n') + '\n'
+    }
+    case 'youtube': {
+      // YouTube only shows chapters when the first one starts at 0:00
+      const chapters = markers[0]?.at === 0 ? markers : [{ at: 0, text: record.briefName }, ...markers]
+      return chapters.map((m) => `${chapterTime(m.at)} ${m.text}`).join('\n') + '\n'
+    }
+  }
+}
+
+/**
+ * Write a session's markers as subtitles or chapters to `path`, or to Documents/Drift by default.
+ */
+export async function exportSessionMarkers(
+  sessionId: string,
+  format: MarkerFormat,
+  path?: string
+): Promise<{ path: string; markers: number } | { error: string }> {
+  if (!(format in MARKER_EXTENSIONS)) {
+    return { error: `Unknown format: ${format}. Use one of ${Object.keys(MARKER_EXTENSIONS).join(', ')}` }
+  }
+  const record = await sessionHistory.get(sessionId)
+  if (!record) {
+    return { error: `Unknown session: ${sessionId}` }
+  }
+  if (record.notes.length === 0) {
+    return { error: 'Session has no markers or notes' }
+  }
+
+  if (!path) {
+    const dir = join(app.getPath('documents'), 'Drift')
+    await fs.mkdir(dir, { recursive: true })
+    path = join(dir, sessionFileName(record, MARKER_EXTENSIONS[format]))
+  }
+
+  try {
+    await fs.writeFile(path, renderSessionMarkers(record, format))
+  } catch (error: any) {
+    return { error: `Could not write ${path}: ${error.message}` }
+  }
+  console.log('[Export] Wrote', record.notes.length, 'markers to', path)
+  return { path, markers: record.notes.length }
+}
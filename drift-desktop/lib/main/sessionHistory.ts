@@ -0,0 +1,367 @@
+import { EventEmitter } from 'events'
+import { getStore } from './store'
+import type { ActivityEntry, AggregatedActivity, ManualNote } from './activityTracker'
+import type { CaptureGap, IdleGap, PrivacyPause } from './session'
+import type { InputActivityBucket } from './inputActivity'
+import type { Clip } from './clipboardWatcher'
+import type { ScreenText } from './ocr'
+import type { ResourceSample } from './resourceUsage'
+import type { ShareLink } from './shareLinks'
+
+// One stretch of focus on a single window
+export interface TimelineEntry {
+  app: string
+  title: string
+  file?: string
+  url?: string // front browser tab, when the browser URL setting was on
+  start: number
+  durationSeconds: number
+  locked?: boolean // the screen was locked; app and title are empty
+  private?: boolean // a sensitive app was in the foreground; capture was paused, title is empty
+}
+
+// A completed session as kept on this machine
+export interface SessionRecord {
+  id: string
+  account: string | null // account the session was recorded under
+  briefId: string
+  briefName: string
+  role: string
+  startedAt: number // UTC epoch ms
+  endedAt: number
+  timeZone?: string // IANA zone the session was recorded in; missing on older records
+  startOffsetMinutes?: number // local offset from UTC at start and end, east positive
+  endOffsetMinutes?: number
+  durationSeconds: number // excludes idle gaps
+  idleGaps: IdleGap[]
+  summary: string
+  summaryLines: string[]
+  submissionId?: string
+  activitySummary: AggregatedActivity[]
+  notes: ManualNote[]
+  timeline?: TimelineEntry[] // only when the timeline setting was on
+  inputActivity?: InputActivityBucket[] // only when the input metrics setting was on
+  clips?: Clip[] // only when the clipboard setting was on
+  screenText?: ScreenText[] // only when the OCR setting was on
+  captureGaps?: CaptureGap[] // only when capture stalled
+  privacyPauses?: PrivacyPause[] // only when a sensitive app was used
+  resourceUsage?: ResourceSample[] // Drift's own CPU/memory/disk usage while recording
+  shareLinks?: ShareLink[]
+  mergedFrom?: Array<{ id: string; submissionId?: string }> // sessions merged into this one
+  splitFrom?: string // session this was split off from
+  title?: string
+  description?: string
+  edits?: SessionEdit[]
+  uploaded: boolean
+}
+
+// Totals for a single calendar day
+export interface DailySummary {
+  date: string
+  sessionCount: number
+  totalSeconds: number
+  noteCount: number
+  byBrief: Array<{ briefId: string; briefName: string; seconds: number }>
+}
+
+// Aggregates for a dashboard over a week, a month or a custom range
+export interface SessionStats {
+  from: number
+  to: number
+  sessionCount: number
+  totalSeconds: number
+  idleSeconds: number
+  idleRatio: number // idle share of recorded plus idle time, 0..1
+  byBrief: Array<{ briefId: string; briefName: string; seconds: number; sessionCount: number }>
+  byWeekday: number[] // seconds per weekday, indexed like Date.getDay() (0 = Sunday)
+  longestSessions: Array<{ id: string; briefName: string; startedAt: number; durationSeconds: number }>
+}
+
+export type StatsRange = 'week' | 'month' | { from: number; to: number }
+
+// Sessions listed under longestSessions
+const LONGEST_SESSIONS = 5
+
+// Deleted sessions are purged from the trash after this many days
+export const TRASH_DAYS = 30
+
+export type EditableField = 'title' | 'description' | 'briefId' | 'startedAt'
+
+// One correction made after the session ended, kept as an audit trail
+export interface SessionEdit {
+  at: number
+  changes: Partial<Record<EditableField, { from: string | number | null; to: string | number | null }>>
+  sync: 'local' | 'pending' | 'synced' | 'rejected' // local: the session was never uploaded
+  error?: string // why the server rejected it
+}
+
+// A deleted session, restorable until the trash is emptied
+export interface TrashedSession {
+  record: SessionRecord
+  deletedAt: number
+}
+
+/**
+ * Bounds of a stats range: the current calendar week (from Monday) or month, local time.
+ */
+export function resolveStatsRange(range: StatsRange, now: Date = new Date()): { from: number; to: number } {
+  if (typeof range === 'object') return range
+  if (range === 'month') {
+    return { from: new Date(now.getFullYear(), now.getMonth(), 1).getTime(), to: now.getTime() }
+  }
+  const daysSinceMonday = (now.getDay() + 6) % 7
+  return { from: new Date(now.getFullYear(), now.getMonth(), now.getDate() - daysSinceMonday).getTime(), to: now.getTime() }
+}
+
+export function formatDuration(seconds: number): string {
+  const hours = Math.floor(seconds / 3600)
+  const minutes = Math.floor((seconds % 3600) / 60)
+  return hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`
+}
+
+/**
+ * Convert tracked activities to timeline entries, dropping screenshots.
+ */
+export function toTimeline(
+  activities: ActivityEntry[],
+  idleGaps: IdleGap[] = [],
+  privacyPauses: PrivacyPause[] = []
+): TimelineEntry[] {
+  // Sensitive app stretches appear only as private entries, without window titles
+  const isPrivate = (a: ActivityEntry) =>
+    privacyPauses.some((p) => p.app === a.app && a.timestamp < p.end && a.timestamp + a.duration * 1000 > p.start)
+
+  const entries: TimelineEntry[] = activities.filter((a) => !isPrivate(a)).map((a) => ({
+    app: a.app,
+    title: a.title,
+    file: a.file,
+    url: a.url,
+    start: a.timestamp,
+    durationSeconds: a.duration
+  }))
+  for (const gap of idleGaps) {
+    if (gap.reason !== 'locked') continue
+    entries.push({ app: '', title: '', start: gap.start, durationSeconds: Math.floor((gap.end - gap.start) / 1000), locked: true })
+  }
+  for (const pause of privacyPauses) {
+    entries.push({ app: pause.app, title: '', start: pause.start, durationSeconds: Math.floor((pause.end - pause.start) / 1000), private: true })
+  }
+  return entries.sort((a, b) => a.start - b.start)
+}
+
+/**
+ * Local history of completed sessions.
+ * The backend remains the source of truth for submissions; this keeps
+ * enough on disk for recaps and exports without a network round-trip.
+ * Emits `saved` with the record whenever a single session is added or updated,
+ * and `removed` with the ids of sessions that were merged away or trashed.
+ */
+class SessionHistory extends EventEmitter {
+  async list(range?: { from?: number; to?: number }): Promise<SessionRecord[]> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    return all.filter((r) => {
+      if (range?.from !== undefined && r.endedAt < range.from) return false
+      if (range?.to !== undefined && r.startedAt > range.to) return false
+      return true
+    })
+  }
+
+  async get(id: string): Promise<SessionRecord | null> {
+    const all = await this.list()
+    return all.find((r) => r.id === id) || null
+  }
+
+  async add(record: SessionRecord): Promise<void> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    all.push(record)
+    s.set('sessions', all)
+    this.emit('saved', record)
+  }
+
+  /**
+   * Insert sessions, overwriting stored ones with the same id, in a single write.
+   */
+  async upsertMany(records: SessionRecord[]): Promise<void> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    for (const record of records) {
+      const index = all.findIndex((r) => r.id === record.id)
+      if (index >= 0) {
+        all[index] = record
+      } else {
+        all.push(record)
+      }
+    }
+    s.set('sessions', all)
+  }
+
+  /**
+   * Remove sessions and store others in their place in a single write, e.g. after a merge.
+   * Emits `saved` for each stored record and `removed` with the ids that are gone.
+   */
+  async replace(removeIds: string[], records: SessionRecord[]): Promise<void> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    const kept = records.map((r) => r.id)
+    s.set('sessions', [...all.filter((r) => !removeIds.includes(r.id) && !kept.includes(r.id)), ...records])
+    for (const record of records) this.emit('saved', record)
+    const removed = removeIds.filter((id) => !kept.includes(id))
+    if (removed.length > 0) this.emit('removed', removed)
+  }
+
+  /**
+   * Apply a change to every stored session in a single write.
+   */
+  async updateAll(change: (record: SessionRecord) => void): Promise<void> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    all.forEach(change)
+    s.set('sessions', all)
+  }
+
+  /**
+   * Apply a change to a stored session. Returns the updated record, or null if unknown.
+   */
+  async update(id: string, change: (record: SessionRecord) => void): Promise<SessionRecord | null> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    const record = all.find((r) => r.id === id)
+    if (!record) return null
+    change(record)
+    s.set('sessions', all)
+    this.emit('saved', record)
+    return record
+  }
+
+  /**
+   * Add a note to a completed session. Notes added after the upload stay local.
+   */
+  async appendNote(id: string, text: string): Promise<ManualNote | null> {
+    const note = { text, timestamp: Date.now() }
+    const record = await this.update(id, (r) => r.notes.push(note))
+    return record ? note : null
+  }
+
+  /**
+   * Move a session to the trash, with all its artifacts. Submissions on the server are not affected.
+   */
+  async trash(id: string): Promise<boolean> {
+    const s = await getStore()
+    const all: SessionRecord[] = s.get('sessions') || []
+    const record = all.find((r) => r.id === id)
+    if (!record) return false
+
+    const trash: TrashedSession[] = s.get('sessionTrash') || []
+    trash.push({ record, deletedAt: Date.now() })
+    s.set('sessionTrash', trash)
+    s.set('sessions', all.filter((r) => r.id !== id))
+    this.emit('removed', [id])
+    return true
+  }
+
+  /**
+   * Put a trashed session back into the history.
+   */
+  async restore(id: string): Promise<SessionRecord | null> {
+    const s = await getStore()
+    const trash: TrashedSession[] = s.get('sessionTrash') || []
+    const entry = trash.find((t) => t.record.id === id)
+    if (!entry) return null
+
+    const all: SessionRecord[] = s.get('sessions') || []
+    s.set('sessions', [...all.filter((r) => r.id !== id), entry.record])
+    s.set('sessionTrash', trash.filter((t) => t !== entry))
+    this.emit('saved', entry.record)
+    return entry.record
+  }
+
+  async listTrash(): Promise<TrashedSession[]> {
+    const s = await getStore()
+    return ((s.get('sessionTrash') || []) as TrashedSession[]).sort((a, b) => b.deletedAt - a.deletedAt)
+  }
+
+  /**
+   * Permanently delete trashed sessions, or only those deleted more than `olderThanDays` ago.
+   * Returns how many were purged. Emits `purged` with their ids.
+   */
+  async emptyTrash(olderThanDays?: number): Promise<number> {
+    const s = await getStore()
+    const trash: TrashedSession[] = s.get('sessionTrash') || []
+    const cutoff = olderThanDays === undefined ? Infinity : Date.now() - olderThanDays * 24 * 60 * 60 * 1000
+    const kept = trash.filter((t) => t.deletedAt >= cutoff)
+    if (kept.length !== trash.length) {
+      s.set('sessionTrash', kept)
+      this.emit('purged', trash.filter((t) => !kept.includes(t)).map((t) => t.record.id))
+    }
+    return trash.length - kept.length
+  }
+
+  /**
+   * Summarize all sessions that ended on the given local day.
+   */
+  async summarizeDay(day: Date = new Date()): Promise<DailySummary> {
+    const start = new Date(day.getFullYear(), day.getMonth(), day.getDate()).getTime()
+    const end = start + 24 * 60 * 60 * 1000
+    const sessions = (await this.list()).filter((r) => r.endedAt >= start && r.endedAt < end)
+
+    const byBrief: Record<string, { briefId: string; briefName: string; seconds: number }> = {}
+    for (const r of sessions) {
+      if (!byBrief[r.briefId]) {
+        byBrief[r.briefId] = { briefId: r.briefId, briefName: r.briefName, seconds: 0 }
+      }
+      byBrief[r.briefId].seconds += r.durationSeconds
+    }
+
+    return {
+      date: `${day.getFullYear()}-${String(day.getMonth() + 1).padStart(2, '0')}-${String(day.getDate()).padStart(2, '0')}`,
+      sessionCount: sessions.length,
+      totalSeconds: sessions.reduce((sum, r) => sum + r.durationSeconds, 0),
+      noteCount: sessions.reduce((sum, r) => sum + r.notes.length, 0),
+      byBrief: Object.values(byBrief).sort((a, b) => b.seconds - a.seconds)
+    }
+  }
+
+  /**
+   * Recorded time per brief and weekday, the longest sessions and the idle
+   * ratio for sessions that started within the range.
+   */
+  async getStats(range: StatsRange): Promise<SessionStats> {
+    const { from, to } = resolveStatsRange(range)
+    const sessions = (await this.list()).filter((r) => r.startedAt >= from && r.startedAt <= to)
+
+    const byBrief: Record<string, SessionStats['byBrief'][number]> = {}
+    const byWeekday = [0, 0, 0, 0, 0, 0, 0]
+    let totalSeconds = 0
+    let idleSeconds = 0
+    for (const r of sessions) {
+      if (!byBrief[r.briefId]) {
+        byBrief[r.briefId] = { briefId: r.briefId, briefName: r.briefName, seconds: 0, sessionCount: 0 }
+      }
+      byBrief[r.briefId].seconds += r.durationSeconds
+      byBrief[r.briefId].sessionCount++
+      byWeekday[new Date(r.startedAt).getDay()] += r.durationSeconds
+      totalSeconds += r.durationSeconds
+      idleSeconds += Math.floor(r.idleGaps.reduce((sum, g) => sum + (g.end - g.start), 0) / 1000)
+    }
+
+    return {
+      from,
+      to,
+      sessionCount: sessions.length,
+      totalSeconds,
+      idleSeconds,
+      idleRatio: totalSeconds + idleSeconds > 0 ? idleSeconds / (totalSeconds + idleSeconds) : 0,
+      byBrief: Object.values(byBrief).sort((a, b) => b.seconds - a.seconds),
+      byWeekday,
+      longestSessions: [...sessions]
+        .sort((a, b) => b.durationSeconds - a.durationSeconds)
+        .slice(0, LONGEST_SESSIONS)
+        .map((r) => ({ id: r.id, briefName: r.briefName, startedAt: r.startedAt, durationSeconds: r.durationSeconds }))
+    }
+  }
+}
+
+// Singleton instance
+export const sessionHistory = new SessionHistory()
@@ -0,0 +1,72 @@
+import type { SessionRecord } from './sessionHistory'
+
+// Sessions starting and ending within this window of each other are duplicates
+const DUPLICATE_TOLERANCE_MS = 60 * 1000
+
+export type OverlapResolution = 'merge' | 'trim' | 'delete'
+
+// A pair of clashing sessions and what to do about it
+export interface OverlapSuggestion {
+  kind: 'duplicate' | 'overlap'
+  sessionIds: [string, string]
+  overlapSeconds: number
+  resolution: OverlapResolution
+  // For trims: the session to shorten and its new end time
+  trim?: { sessionId: string; endedAt: number }
+  // For deletes: the session to drop
+  deleteId?: string
+}
+
+/**
+ * Find sessions whose time ranges clash and suggest how to resolve each pair.
+ *
+ * - Near-identical sessions are duplicates: keep the uploaded/longer one.
+ * - Overlapping sessions on the same brief should be merged.
+ * - Overlapping sessions on different briefs: trim the earlier one so it ends
+ *   when the later one starts.
+ */
+export function detectOverlaps(records: SessionRecord[]): OverlapSuggestion[] {
+  const sorted = [...records].sort((a, b) => a.startedAt - b.startedAt)
+  const suggestions: OverlapSuggestion[] = []
+
+  for (let i = 0; i < sorted.length; i++) {
+    const a = sorted[i]
+    for (let j = i + 1; j < sorted.length; j++) {
+      const b = sorted[j]
+      // Sorted by start, so nothing later can overlap `a` either
+      if (b.startedAt >= a.endedAt) break
+
+      const overlapMs = Math.min(a.endedAt, b.endedAt) - b.startedAt
+      const overlapSeconds = Math.floor(overlapMs / 1000)
+      const sessionIds: [string, string] = [a.id, b.id]
+
+      const isDuplicate =
+        a.briefId === b.briefId &&
+        Math.abs(a.startedAt - b.startedAt) <= DUPLICATE_TOLERANCE_MS &&
+        Math.abs(a.endedAt - b.endedAt) <= DUPLICATE_TOLERANCE_MS
+
+      if (isDuplicate) {
+        const keep = a.uploaded !== b.uploaded ? (a.uploaded ? a : b) : a.durationSeconds >= b.durationSeconds ? a : b
+        suggestions.push({
+          kind: 'duplicate',
+          sessionIds,
+          overlapSeconds,
+          resolution: 'delete',
+          deleteId: keep === a ? b.id : a.id
+        })
+      } else if (a.briefId === b.briefId) {
+        suggestions.push({ kind: 'overlap', sessionIds, overlapSeconds, resolution: 'merge' })
+      } else {
+        suggestions.push({
+          kind: 'overlap',
+          sessionIds,
+          overlapSeconds,
+          resolution: 'trim',
+          trim: { sessionId: a.id, endedAt: b.startedAt }
+        })
+      }
+    }
+  }
+
+  return suggestions
+}
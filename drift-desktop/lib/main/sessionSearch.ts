@@ -0,0 +1,108 @@
+import { sessionHistory, SessionRecord } from './sessionHistory'
+
+export type SearchField = 'title' | 'summary' | 'note' | 'tag' | 'screen-text'
+
+// Where a query matched, anchored to a point in the session where possible
+export interface SearchMatch {
+  field: SearchField
+  at: number
+  snippet: string
+}
+
+export interface SessionSearchHit {
+  sessionId: string
+  briefName: string
+  startedAt: number
+  score: number
+  matches: SearchMatch[]
+}
+
+// Title matches outrank summaries and notes, which outrank screen text
+const FIELD_WEIGHTS: Record<SearchField, number> = {
+  title: 5,
+  tag: 3,
+  summary: 2,
+  note: 2,
+  'screen-text': 1
+}
+
+// Matches returned per session
+const MAX_MATCHES = 5
+
+const SNIPPET_CONTEXT = 60
+
+function terms(query: string): string[] {
+  return [...new Set(query.toLowerCase().split(/\s+/).filter(Boolean))]
+}
+
+function snippet(text: string, index: number, length: number): string {
+  const start = Math.max(0, index - SNIPPET_CONTEXT)
+  const end = Math.min(text.length, index + length + SNIPPET_CONTEXT)
+  return `${start > 0 ? '…' : ''}${text.slice(start, end)}${end < text.length ? '…' : ''}`
+}
+
+// Everything searchable in a session, with the time each piece belongs to
+function documents(record: SessionRecord): Array<{ field: SearchField; at: number; text: string }> {
+  return [
+    { field: 'title' as const, at: record.startedAt, text: [record.title, record.briefName].filter(Boolean).join('\n') },
+    { field: 'tag' as const, at: record.startedAt, text: record.role },
+    { field: 'summary' as const, at: record.startedAt, text: [record.description, record.summary, ...record.summaryLines].filter(Boolean).join('\n') },
+    ...record.notes.map((n) => ({ field: 'note' as const, at: n.timestamp, text: n.text })),
+    ...(record.screenText ?? []).map((s) => ({ field: 'screen-text' as const, at: s.at, text: s.text }))
+  ]
+}
+
+/**
+ * Rank a session against the query terms, or null unless every term occurs
+ * somewhere in it. Each occurrence counts with its field's weight.
+ */
+export function scoreSession(record: SessionRecord, queryTerms: string[]): SessionSearchHit | null {
+  const found = new Set<string>()
+  const matches: Array<SearchMatch & { weight: number }> = []
+  let score = 0
+
+  for (const doc of documents(record)) {
+    const text = doc.text.toLowerCase()
+    const weight = FIELD_WEIGHTS[doc.field]
+    let first: { index: number; length: number } | null = null
+
+    for (const term of queryTerms) {
+      let index = text.indexOf(term)
+      if (index === -1) continue
+      found.add(term)
+      first = first ?? { index, length: term.length }
+      while (index !== -1) {
+        score += weight
+        index = text.indexOf(term, index + term.length)
+      }
+    }
+    if (first) matches.push({ field: doc.field, at: doc.at, snippet: snippet(doc.text, first.index, first.length), weight })
+  }
+
+  if (found.size < queryTerms.length) return null
+  return {
+    sessionId: record.id,
+    briefName: record.briefName,
+    startedAt: record.startedAt,
+    score,
+    matches: matches
+      .sort((a, b) => b.weight - a.weight || a.at - b.at)
+      .slice(0, MAX_MATCHES)
+      .map(({ weight, ...match }) => match)
+  }
+}
+
+/**
+ * Search all stored sessions: titles, role tags, summaries, notes and OCR
+ * text. Best match first; ties go to the newer session.
+ */
+export async function searchSessions(query: string, limit: number = 50): Promise<SessionSearchHit[]> {
+  const queryTerms = terms(query)
+  if (queryTerms.length === 0) return []
+
+  return (await sessionHistory.list())
+    .map((record) => scoreSession(record, queryTerms))
+    .filter((hit): hit is SessionSearchHit => hit !== null)
+    .sort((a, b) => b.score - a.score || b.startedAt - a.startedAt)
+    .slice(0, limit)
+}
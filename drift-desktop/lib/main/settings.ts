@@ -0,0 +1,456 @@
+import { EventEmitter } from 'events'
+import type { Rectangle } from 'electron'
+import { getStore } from './store'
+import type { ShortcutConfig } from './shortcuts'
+import type { CaptureQuality } from './capture'
+import type { CapturePreset } from './presets'
+
+/**
+ * User-configurable settings for the desktop app.
+ * Persisted under the `settings` key of the electron store and merged
+ * over the defaults on read, so new fields are picked up automatically.
+ */
+export interface Settings {
+  endOfDay: {
+    enabled: boolean
+    time: string // local time, HH:MM
+  }
+  // Pause the session after this long without keyboard/mouse input
+  idle: {
+    enabled: boolean
+    thresholdMinutes: number
+  }
+  // Offer to record when a call starts in Zoom, Teams, Meet and similar apps
+  meetings: {
+    detect: boolean
+    prompt: boolean // also show a notification, not just the in-app prompt
+  }
+  // Suggest recording after sustained activity without a session, during work hours only
+  nudge: {
+    enabled: boolean
+    afterMinutes: number
+    snoozeMinutes: number
+    workHours: {
+      start: string // local time, HH:MM
+      end: string
+      days: number[] // like Date.getDay(): 0 = Sunday
+    }
+  }
+  // Recorded-time goals in minutes; 0 turns a goal off
+  goals: {
+    dailyMinutes: number
+    weeklyMinutes: number
+  }
+  // While recording on battery power; a stopPercent of 0 never ends the session
+  battery: {
+    warnPercent: number
+    lowPowerCapture: boolean // stop screenshots once below warnPercent
+    stopPercent: number
+  }
+  // Window sampling and screenshot size; change through capture:set-quality, which validates
+  capture: CaptureQuality
+  // No screenshots while one of these apps is in the foreground (case-insensitive name match).
+  // Time in sensitive apps is also marked in the timeline, optionally with clipboard capture paused.
+  privacy: {
+    excludedApps: string[]
+    sensitiveApps: string[]
+    pauseClipboardForSensitive: boolean
+  }
+  // Signed POST after each session upload; empty url disables it
+  webhook: {
+    url: string
+    secret: string // HMAC-SHA256 key for X-Drift-Signature
+  }
+  // Post a summary of each uploaded session to a Slack channel
+  slack: {
+    enabled: boolean
+    token: string // bot token with chat:write
+    channel: string // channel id or #name
+  }
+  // Keep one Markdown note per session in this folder (e.g. an Obsidian vault)
+  vault: {
+    enabled: boolean
+    path: string
+  }
+  // Commit each session's note and metadata to a local Git repository; remote is pushed to when set
+  gitBackup: {
+    enabled: boolean
+    repoPath: string
+    authorName: string
+    authorEmail: string
+    remote: string // e.g. 'origin'; empty to never push
+  }
+  // Screen areas blacked out in every capture, by display id, in global DIP coordinates
+  redaction: {
+    zones: Record<string, Rectangle[]>
+  }
+  // Sessions don't start, and running ones are ended, below this much free space
+  disk: {
+    minFreeMB: number
+  }
+  // After the machine wakes from sleep: resume the paused session right away, or ask first
+  sleep: {
+    resumeOnWake: 'auto' | 'prompt'
+  }
+  // Switching briefs mid-session: move the whole session, or end it and continue in a new one
+  briefSwitch: {
+    mode: 'reassign' | 'split'
+  }
+  // Keep a per-session timeline of focused apps and window titles on this machine
+  timeline: {
+    enabled: boolean
+    browserUrls: boolean // also the URL of the front browser tab (macOS: Chromium browsers and Safari)
+  }
+  // Record seconds-with-input per minute for engagement heatmaps; never keys or positions
+  inputMetrics: {
+    enabled: boolean
+  }
+  // Save text copied during a session with the session
+  clipboard: {
+    enabled: boolean
+    maxChars: number // longer snippets are truncated
+    maxClips: number // per session
+    denyApps: string[] // case-insensitive app name substrings
+  }
+  // Recognize text on session screenshots (needs tesseract) so sessions can be searched for it
+  ocr: {
+    enabled: boolean
+    intervalSeconds: number // at most one screenshot per interval
+    languages: string // tesseract language codes, e.g. 'eng+deu'
+  }
+  // Drop per-session timelines, input metrics, clips and screen text after this many days; session metadata is kept
+  retention: {
+    enabled: boolean
+    mediaDays: number
+  }
+  // Language of pages the app serves itself (e.g. after signing in); 'auto' follows the browser
+  locale: {
+    language: string
+  }
+  // Voice note dictation
+  transcription: {
+    language: string // BCP 47 tag such as 'de-DE', or 'auto' for the system language
+  }
+  // Delay before a session started from the UI begins recording; 0 starts immediately
+  countdown: {
+    seconds: number
+  }
+  // Overlay on the captured screen area while recording
+  recordingIndicator: {
+    enabled: boolean
+    style: 'border' | 'badge' // red frame around the area, or a corner badge
+  }
+  // What may leave this machine; disabled artifacts are stripped before upload
+  upload: {
+    screenshots: boolean
+    windowTitles: boolean
+    notes: boolean
+  }
+  // OS notifications, per event type
+  notifications: {
+    sessionAutoStopped: boolean
+    uploadComplete: boolean
+    uploadFailed: boolean
+    authExpired: boolean
+    authExpiring: boolean // only while the window is hidden
+    dailyRecap: boolean
+    focusBlockEnded: boolean
+    wokeFromSleep: boolean
+    lowBattery: boolean
+    lowDisk: boolean
+    goalReached: boolean
+    meetingDetected: boolean
+    notRecording: boolean
+  }
+  // Calendar feeds used to suggest a brief when a meeting starts
+  calendar: {
+    icsUrls: string[] // secret iCal addresses (Google, Outlook, iCloud)
+    briefMappings: Array<{ match: string; briefId: string }> // case-insensitive title substring -> brief
+  }
+  // Anonymous usage statistics; off unless the user opts in
+  telemetry: {
+    enabled: boolean
+    endpoint: string
+  }
+  // Which backend to talk to
+  environment: {
+    name: 'production' | 'staging' | 'custom'
+    customUrl: string // API base URL, used when name is 'custom'
+  }
+  // Global shortcuts changed by the user; missing actions use the built-in defaults
+  shortcuts: Partial<ShortcutConfig>
+  // Named capture setups; a brief records with its own preset, else the default, else `capture`
+  capturePresets: {
+    presets: CapturePreset[]
+    defaultId: string | null
+    briefDefaults: Record<string, string> // brief id -> preset id
+  }
+  // Brief ids listed first in the tray's Start Recording menu
+  pinnedBriefs: string[]
+  // Programs run on every finished session before upload; they get the manifest as JSON on stdin.
+  // They run as the user with full access to their files; only the environment and working directory are limited.
+  plugins: Array<{
+    name: string
+    command: string // executable, run without a shell
+    args: string[]
+    enabled: boolean
+    timeoutSeconds: number // 1-600, default 60
+  }>
+  // Local port the browser sends the sign-in result to; the first free one in the range is used
+  authCallback: {
+    portFrom: number // 0: any port the OS picks
+    portTo: number
+  }
+  // Token-protected HTTP API on localhost for scripts and devices like Stream Deck
+  controlApi: {
+    enabled: boolean
+    port: number
+    token: string // generated when first enabled
+  }
+  // Let the companion browser extension start/stop recordings and report the current tab
+  browserExtension: {
+    enabled: boolean
+    chromeExtensionIds: string[] // Chrome, Edge, Brave...: ids allowed to connect
+    firefoxExtensionIds: string[]
+  }
+  // Outbound HTTP(S) proxy. 'system' follows the OS configuration
+  proxy: {
+    mode: 'system' | 'manual' | 'direct'
+    url: string // e.g. http://proxy.corp:8080, used in manual mode
+    username: string
+    password: string
+  }
+}
+
+export type SettingsPatch = {
+  [K in keyof Settings]?: Settings[K] extends any[]
+    ? Settings[K]
+    : Settings[K] extends object
+      ? Partial<Settings[K]>
+      : Settings[K]
+}
+
+const DEFAULT_SETTINGS: Settings = {
+  endOfDay: {
+    enabled: false,
+    time: '18:00'
+  },
+  idle: {
+    enabled: true,
+    thresholdMinutes: 5
+  },
+  meetings: {
+    detect: true,
+    prompt: true
+  },
+  nudge: {
+    enabled: false,
+    afterMinutes: 20,
+    snoozeMinutes: 60,
+    workHours: {
+      start: '09:00',
+      end: '18:00',
+      days: [1, 2, 3, 4, 5]
+    }
+  },
+  goals: {
+    dailyMinutes: 0,
+    weeklyMinutes: 0
+  },
+  battery: {
+    warnPercent: 20,
+    lowPowerCapture: true,
+    stopPercent: 5
+  },
+  capture: {
+    sampleSeconds: 3,
+    scale: 0.5,
+    quality: 60
+  },
+  privacy: {
+    excludedApps: ['1Password', 'Bitwarden', 'KeePassXC', 'LastPass', 'Dashlane', 'Keychain Access'],
+    sensitiveApps: [],
+    pauseClipboardForSensitive: true
+  },
+  webhook: {
+    url: '',
+    secret: ''
+  },
+  slack: {
+    enabled: false,
+    token: '',
+    channel: ''
+  },
+  vault: {
+    enabled: false,
+    path: ''
+  },
+  gitBackup: {
+    enabled: false,
+    repoPath: '',
+    authorName: 'Drift',
+    authorEmail: 'drift@localhost',
+    remote: ''
+  },
+  redaction: {
+    zones: {}
+  },
+  disk: {
+    minFreeMB: 500
+  },
+  sleep: {
+    resumeOnWake: 'auto'
+  },
+  briefSwitch: {
+    mode: 'reassign'
+  },
+  timeline: {
+    enabled: false,
+    browserUrls: false
+  },
+  inputMetrics: {
+    enabled: false
+  },
+  clipboard: {
+    enabled: false,
+    maxChars: 2000,
+    maxClips: 200,
+    denyApps: ['1Password', 'Bitwarden', 'KeePass', 'LastPass', 'Dashlane', 'Keychain Access']
+  },
+  ocr: {
+    enabled: false,
+    intervalSeconds: 60,
+    languages: 'eng'
+  },
+  retention: {
+    enabled: false,
+    mediaDays: 30
+  },
+  locale: {
+    language: 'auto'
+  },
+  transcription: {
+    language: 'auto'
+  },
+  countdown: {
+    seconds: 0
+  },
+  recordingIndicator: {
+    enabled: true,
+    style: 'border'
+  },
+  upload: {
+    screenshots: true,
+    windowTitles: true,
+    notes: true
+  },
+  notifications: {
+    sessionAutoStopped: true,
+    uploadComplete: false,
+    uploadFailed: true,
+    authExpired: true,
+    authExpiring: true,
+    dailyRecap: true,
+    focusBlockEnded: true,
+    wokeFromSleep: true,
+    lowBattery: true,
+    lowDisk: true,
+    goalReached: true,
+    meetingDetected: true,
+    notRecording: true
+  },
+  calendar: {
+    icsUrls: [],
+    briefMappings: []
+  },
+  telemetry: {
+    enabled: false,
+    endpoint: ''
+  },
+  environment: {
+    name: 'production',
+    customUrl: ''
+  },
+  shortcuts: {},
+  capturePresets: {
+    presets: [],
+    defaultId: null,
+    briefDefaults: {}
+  },
+  pinnedBriefs: [],
+  plugins: [],
+  authCallback: {
+    portFrom: 53180,
+    portTo: 53189
+  },
+  controlApi: {
+    enabled: false,
+    port: 7821,
+    token: ''
+  },
+  browserExtension: {
+    enabled: false,
+    chromeExtensionIds: [],
+    firefoxExtensionIds: ['extension@drift.app']
+  },
+  proxy: {
+    mode: 'system',
+    url: '',
+    username: '',
+    password: ''
+  }
+}
+
+const events = new EventEmitter()
+
+function merge(base: Settings, patch: SettingsPatch | Record<string, any>): Settings {
+  const next: any = { ...base }
+  for (const key of Object.keys(base) as (keyof Settings)[]) {
+    const value = (patch as any)[key]
+    if (value === undefined) continue
+    const current = base[key]
+    if (current && typeof current === 'object' && !Array.isArray(current)) {
+      next[key] = { ...current, ...value }
+    } else {
+      next[key] = value
+    }
+  }
+  return next
+}
+
+/**
+ * Get the current settings, with defaults filled in.
+ */
+export async function getSettings(): Promise<Settings> {
+  const s = await getStore()
+  return merge(DEFAULT_SETTINGS, s.get('settings') || {})
+}
+
+/**
+ * Apply a partial update and persist it. Emits a change to all subscribers.
+ */
+export async function updateSettings(patch: SettingsPatch): Promise<Settings> {
+  const s = await getStore()
+  const prev = await getSettings()
+  const next = merge(prev, patch)
+  s.set('settings', next)
+  events.emit('changed', next, prev)
+  return next
+}
+
+/**
+ * Notify subscribers after the settings were replaced underneath them, e.g. by
+ * switching profiles. `prev` is what was in effect before.
+ */
+export async function reloadSettings(prev: Settings): Promise<Settings> {
+  const next = await getSettings()
+  events.emit('changed', next, prev)
+  return next
+}
+
+/**
+ * Subscribe to settings changes.
+ */
+export function onSettingsChanged(listener: (next: Settings, prev: Settings) => void): void {
+  events.on('changed', listener)
+}
@@ -0,0 +1,64 @@
+import { clipboard } from 'electron'
+import { driftApi, getWebBaseUrl, toDriftError } from './api'
+import { sessionHistory } from './sessionHistory'
+
+// A link that shows a session's summary without signing in
+export interface ShareLink {
+  token: string
+  url: string
+  createdAt: number
+  expiresAt: number | null // null: until revoked
+  revoked?: boolean
+}
+
+/**
+ * Create a share link for an uploaded session and copy it to the clipboard.
+ * Without `expiryHours` the link works until it is revoked.
+ */
+export async function createShareLink(sessionId: string, expiryHours?: number): Promise<ShareLink | { error: string }> {
+  const record = await sessionHistory.get(sessionId)
+  if (!record) return { error: 'Session not found' }
+  if (!record.uploaded) return { error: 'Session has not been uploaded yet' }
+
+  try {
+    const { token, expiresAt } = await driftApi.createShareLink(sessionId, expiryHours ?? null)
+    const link: ShareLink = {
+      token,
+      url: `${await getWebBaseUrl()}/share/${token}`,
+      createdAt: Date.now(),
+      expiresAt: expiresAt ? Date.parse(expiresAt.endsWith('Z') ? expiresAt : `${expiresAt}Z`) : null
+    }
+
+    await sessionHistory.update(sessionId, (r) => {
+      r.shareLinks = [...(r.shareLinks ?? []), link]
+    })
+    clipboard.writeText(link.url)
+    return link
+  } catch (error) {
+    const failure = toDriftError(error)
+    console.error('[Share] Creating link failed:', failure.message)
+    return { error: failure.message }
+  }
+}
+
+/**
+ * Revoke a share link created for a session.
+ */
+export async function revokeShareLink(sessionId: string, token: string): Promise<{ ok: true } | { error: string }> {
+  try {
+    await driftApi.revokeShareLink(token)
+  } catch (error) {
+    // Already gone on the server; still mark it locally
+    const failure = toDriftError(error)
+    if (failure.kind !== 'not-found') {
+      console.error('[Share] Revoking link failed:', failure.message)
+      return { error: failure.message }
+    }
+  }
+
+  await sessionHistory.update(sessionId, (r) => {
+    const link = r.shareLinks?.find((l) => l.token === token)
+    if (link) link.revoked = true
+  })
+  return { ok: true }
+}
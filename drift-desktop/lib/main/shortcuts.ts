@@ -1,6 +1,7 @@
 import { globalShortcut, app, BrowserWindow } from 'electron'
+import { getSettings, updateSettings } from './settings'
 
-export type ShortcutAction = 'toggleOverlay' | 'submitChat' | 'toggleSession' | 'toggleVoice' | 'escape'
+export type ShortcutAction = 'toggleOverlay' | 'submitChat' | 'toggleSession' | 'toggleVoice' | 'quickCapture' | 'escape'
 export type ShortcutConfig = Record<ShortcutAction, string>
 
 const DEFAULT_SHORTCUTS: ShortcutConfig = {
@@ -8,9 +9,75 @@ const DEFAULT_SHORTCUTS: ShortcutConfig = {
   submitChat: 'CommandOrControl+Enter',
   toggleSession: 'CommandOrControl+Alt+S',
   toggleVoice: 'CommandOrControl+Alt+V',
+  quickCapture: 'CommandOrControl+Alt+N',
   escape: 'Escape'
 }
 
+const MODIFIERS: Record<string, string> = {
+  command: 'command',
+  cmd: 'command',
+  control: 'control',
+  ctrl: 'control',
+  commandorcontrol: 'commandorcontrol',
+  cmdorctrl: 'commandorcontrol',
+  alt: 'alt',
+  option: 'alt',
+  altgr: 'altgr',
+  shift: 'shift',
+  super: 'super',
+  meta: 'super'
+}
+
+const NAMED_KEYS = [
+  'plus', 'space', 'tab', 'capslock', 'numlock', 'scrolllock', 'backspace', 'delete', 'insert',
+  'return', 'enter', 'up', 'down', 'left', 'right', 'home', 'end', 'pageup', 'pagedown', 'escape', 'esc',
+  'volumeup', 'volumedown', 'volumemute', 'medianexttrack', 'mediaprevioustrack', 'mediastop', 'mediaplaypause',
+  'printscreen', 'numdec', 'numadd', 'numsub', 'nummult', 'numdiv'
+]
+
+const KEY_ALIASES: Record<string, string> = { esc: 'escape', return: 'enter' }
+
+/**
+ * Check an Electron accelerator such as `CommandOrControl+Shift+K`.
+ * Returns an error message, or null when it is valid.
+ */
+export function validateAccelerator(accelerator: string): string | null {
+  const parts = accelerator?.split('+').map((p) => p.trim()) ?? []
+  if (parts.length === 0 || parts.some((p) => !p)) return `Invalid shortcut: ${accelerator}`
+
+  const key = parts[parts.length - 1].toLowerCase()
+  const modifiers = parts.slice(0, -1).map((p) => p.toLowerCase())
+  const unknown = modifiers.find((m) => !MODIFIERS[m])
+  if (unknown) return `Unknown modifier: ${unknown}`
+  if (new Set(modifiers.map((m) => MODIFIERS[m])).size !== modifiers.length) return 'Duplicate modifier'
+
+  const validKey =
+    /^[a-z0-9]$/.test(key) ||
+    /^[)!@#$%^&*(:;"'<>?~`{}|\\[\],./=_-]$/.test(key) ||
+    /^f([1-9]|1[0-9]|2[0-4])$/.test(key) ||
+    /^num[0-9]$/.test(key) ||
+    NAMED_KEYS.includes(key)
+  return validKey ? null : `Unknown key: ${parts[parts.length - 1]}`
+}
+
+/**
+ * Canonical form for comparing accelerators: aliases resolved, modifiers sorted.
+ */
+function normalizeAccelerator(accelerator: string): string {
+  const parts = accelerator.split('+').map((p) => p.trim().toLowerCase())
+  const key = parts.pop()!
+  const modifiers = parts.map((m) => MODIFIERS[m] ?? m).sort()
+  return [...modifiers, KEY_ALIASES[key] ?? key].join('+')
+}
+
+type ShortcutResult = {
+  ok: boolean
+  failed: ShortcutAction[]
+  shortcuts: ShortcutConfig
+  error?: string
+  conflicts?: Array<{ action: ShortcutAction; with: ShortcutAction | 'system' }>
+}
+
 /**
  * Handles registration of global keyboard shortcuts.
  * This class only dispatches events to the state machine and does not
@@ -20,6 +87,9 @@ export class ShortcutsHelper {
   private mainWindow: BrowserWindow
   private shortcuts: ShortcutConfig = { ...DEFAULT_SHORTCUTS }
   private willQuitHandlerAttached = false
+  // Play/pause media key; only claimed while a handler is set, so music players keep it otherwise
+  private mediaPlayPause: (() => void) | null = null
+  private quickCapture: (() => void) | null = null
 
   constructor(mainWindow: BrowserWindow) {
     this.mainWindow = mainWindow
@@ -33,26 +103,102 @@ export class ShortcutsHelper {
     return { ...this.shortcuts }
   }
 
-  public resetShortcuts(): { ok: boolean; failed: ShortcutAction[]; shortcuts: ShortcutConfig } {
+  public async resetShortcuts(): Promise<ShortcutResult> {
     this.shortcuts = { ...DEFAULT_SHORTCUTS }
     const failed = this.reRegisterAll()
+    await this.persist()
     return { ok: failed.length === 0, failed, shortcuts: { ...this.shortcuts } }
   }
 
-  public setShortcuts(next: Partial<ShortcutConfig>): { ok: boolean; failed: ShortcutAction[]; shortcuts: ShortcutConfig } {
-    const previous = { ...this.shortcuts }
-    this.shortcuts = { ...this.shortcuts, ...next }
+  public setShortcut(action: ShortcutAction, accelerator: string): Promise<ShortcutResult> {
+    return this.setShortcuts({ [action]: accelerator })
+  }
+
+  /**
+   * Validate, check for conflicts, rebind live and persist. Nothing changes unless all of it succeeds.
+   */
+  public async setShortcuts(next: Partial<ShortcutConfig>): Promise<ShortcutResult> {
+    const current = { ...this.shortcuts }
+
+    for (const [action, accelerator] of Object.entries(next) as [ShortcutAction, string][]) {
+      if (!(action in DEFAULT_SHORTCUTS)) {
+        return { ok: false, failed: [], shortcuts: current, error: `Unknown action: ${action}` }
+      }
+      const error = validateAccelerator(accelerator)
+      if (error) {
+        return { ok: false, failed: [action], shortcuts: current, error }
+      }
+    }
+
+    // Two actions on the same keys would shadow each other
+    const candidate = { ...current, ...next }
+    const conflicts: NonNullable<ShortcutResult['conflicts']> = []
+    for (const action of Object.keys(next) as ShortcutAction[]) {
+      for (const other of Object.keys(candidate) as ShortcutAction[]) {
+        if (other !== action && normalizeAccelerator(candidate[other]) === normalizeAccelerator(candidate[action])) {
+          conflicts.push({ action, with: other })
+        }
+      }
+    }
+    if (conflicts.length > 0) {
+      return { ok: false, failed: conflicts.map((c) => c.action), shortcuts: current, error: 'Shortcut already in use', conflicts }
+    }
+
+    this.shortcuts = candidate
     const failed = this.reRegisterAll()
 
     if (failed.length > 0) {
-      this.shortcuts = previous
+      // Registration fails when another application owns the keys
+      this.shortcuts = current
       this.reRegisterAll()
-      return { ok: false, failed, shortcuts: { ...this.shortcuts } }
+      return {
+        ok: false,
+        failed,
+        shortcuts: { ...this.shortcuts },
+        error: 'Shortcut is used by another application',
+        conflicts: failed.map((action) => ({ action, with: 'system' as const }))
+      }
     }
 
+    await this.persist()
     return { ok: true, failed: [], shortcuts: { ...this.shortcuts } }
   }
 
+  /**
+   * Apply shortcuts saved in settings. Saved ones that no longer register fall back to the defaults.
+   */
+  public async loadSaved(): Promise<void> {
+    const { shortcuts: saved } = await getSettings()
+    const valid = Object.fromEntries(
+      Object.entries(saved).filter(([action, accelerator]) => action in DEFAULT_SHORTCUTS && !validateAccelerator(accelerator))
+    ) as Partial<ShortcutConfig>
+    if (Object.keys(valid).length === 0) return
+
+    this.shortcuts = { ...DEFAULT_SHORTCUTS, ...valid }
+    const failed = this.reRegisterAll()
+    if (failed.length > 0) {
+      console.warn('[Shortcuts] Saved shortcuts unavailable, using defaults for:', failed.join(', '))
+      for (const action of failed) this.shortcuts[action] = DEFAULT_SHORTCUTS[action]
+      this.reRegisterAll()
+    }
+  }
+
+  private async persist(): Promise<void> {
+    await updateSettings({ shortcuts: { ...this.shortcuts } })
+  }
+
+  public setMediaPlayPauseHandler(handler: (() => void) | null): void {
+    if (this.mediaPlayPause) {
+      globalShortcut.unregister('MediaPlayPause')
+    }
+    this.mediaPlayPause = handler
+    this.registerMediaKeys()
+  }
+
+  public setQuickCaptureHandler(handler: () => void): void {
+    this.quickCapture = handler
+  }
+
   public registerGlobalShortcuts(): void {
     this.reRegisterAll()
     if (!this.willQuitHandlerAttached) {
@@ -86,12 +232,25 @@ export class ShortcutsHelper {
       failed.push('toggleOverlay')
     }
 
+    // Quick note: global like the overlay toggle, so it works while the main window is hidden
+    if (!globalShortcut.register(this.shortcuts.quickCapture, () => this.quickCapture?.())) {
+      failed.push('quickCapture')
+    }
+
     // Initially register window-specific shortcuts
     failed.push(...this.registerWindowShortcuts());
+    this.registerMediaKeys()
 
     return failed
   }
 
+  // On macOS this needs the accessibility permission
+  private registerMediaKeys(): void {
+    if (this.mediaPlayPause && !globalShortcut.register('MediaPlayPause', this.mediaPlayPause)) {
+      console.warn('[Shortcuts] Could not register the play/pause media key')
+    }
+  }
+
   // Register shortcuts only when windows are visible
   private registerWindowShortcuts(): ShortcutAction[] {
     const failed: ShortcutAction[] = []
@@ -0,0 +1,82 @@
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { uploadQueue } from './uploadQueue'
+import { sessionHistory, SessionRecord, formatDuration } from './sessionHistory'
+import { externalHttp, getWebBaseUrl } from './api'
+
+const POST_MESSAGE_URL = 'https://slack.com/api/chat.postMessage'
+
+/**
+ * Slack mrkdwn summary of a session. Notes only with `includeNotes`, i.e. when upload.notes is on.
+ */
+export function renderSlackMessage(record: SessionRecord, webUrl: string, includeNotes: boolean): string {
+  const lines = [`*${record.briefName}* · ${formatDuration(record.durationSeconds)} · ${record.role}`]
+  const summary = record.summaryLines.length > 0 ? record.summaryLines : record.summary ? [record.summary] : []
+  for (const line of summary) lines.push(`• ${line}`)
+  if (includeNotes && record.notes.length > 0) {
+    lines.push('', '_Notes_')
+    for (const note of record.notes) lines.push(`• ${note.text}`)
+  }
+  if (record.submissionId) {
+    lines.push('', `<${webUrl}|Open in Drift>`)
+  }
+  return lines.join('\n')
+}
+
+/**
+ * Posts a summary of every uploaded session to a Slack channel with a bot
+ * token (chat:write scope). `preview` renders the same message without sending it.
+ */
+class SlackIntegration {
+  start(): void {
+    sessionManager.on('uploaded', (sessionId: string) => this.post(sessionId))
+    uploadQueue.on('uploaded', (sessionId: string) => this.post(sessionId))
+  }
+
+  /**
+   * The message that would be posted for a session (the latest one by default).
+   */
+  async preview(sessionId?: string): Promise<{ channel: string; text: string } | { error: string }> {
+    const record = sessionId
+      ? await sessionHistory.get(sessionId)
+      : (await sessionHistory.list()).sort((a, b) => b.endedAt - a.endedAt)[0]
+    if (!record) {
+      return { error: sessionId ? `Unknown session: ${sessionId}` : 'No sessions yet' }
+    }
+    const { slack, upload } = await getSettings()
+    return { channel: slack.channel, text: renderSlackMessage(record, await getWebBaseUrl(), upload.notes) }
+  }
+
+  private async post(sessionId: string): Promise<void> {
+    const { slack, upload } = await getSettings()
+    if (!slack.enabled || !slack.token || !slack.channel) return
+
+    const record = await sessionHistory.get(sessionId)
+    if (!record) return
+
+    try {
+      const response = await externalHttp.post(
+        POST_MESSAGE_URL,
+        { channel: slack.channel, text: renderSlackMessage(record, await getWebBaseUrl(), upload.notes), unfurl_links: false },
+        {
+          headers: {
+            'Content-Type': 'application/json; charset=utf-8',
+            'Authorization': `Bearer ${slack.token}`
+          },
+          timeout: 10000
+        }
+      )
+      // Slack reports failures in the body with a 200 status
+      if (!response.data?.ok) {
+        console.error('[Slack] Post failed:', response.data?.error)
+        return
+      }
+      console.log('[Slack] Posted summary for', sessionId)
+    } catch (error: any) {
+      console.error('[Slack] Post failed:', error.response?.status ?? error.message)
+    }
+  }
+}
+
+// Singleton instance
+export const slackIntegration = new SlackIntegration()
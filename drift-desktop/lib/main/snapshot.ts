@@ -0,0 +1,49 @@
+import { sessionManager } from './session'
+import { sessionHistory } from './sessionHistory'
+import { accounts } from './accounts'
+import { activityTracker } from './activityTracker'
+import { focusTimer } from './focus'
+import { liveSync } from './liveSync'
+import { resolveApiBaseUrl } from './api'
+import { offsetMinutesAt } from './timezone'
+import { connectivity } from './connectivity'
+import { getSettings } from './settings'
+import { latestEventSeq } from './events'
+
+/**
+ * Everything the UI needs to render its current state, in one call.
+ * Used to hydrate the renderer after a reload or restart; events after
+ * `eventSeq` can then be fetched with replayEvents().
+ */
+export async function getAppSnapshot() {
+  const active = sessionManager.getActive()
+  const account = await accounts.getActive()
+  const pending = (await sessionHistory.list()).filter((r) => !r.uploaded && r.account === (account?.name ?? null))
+  const status = activityTracker.getStatus()
+
+  return {
+    // Events up to here are reflected below; replay from this seq to stay current
+    eventSeq: latestEventSeq(),
+    recordingState: sessionManager.getRecordingState(),
+    session: active
+      ? {
+          ...active,
+          pauseReason: sessionManager.getPauseReason(),
+          elapsedSeconds: sessionManager.getElapsedSeconds(),
+          utcOffsetMinutes: offsetMinutesAt(Date.now(), active.timeZone), // now, after any DST change since the start
+          notes: activityTracker.getNotes(),
+          activityCount: status.activityCount
+        }
+      : null,
+    pendingUploads: pending.map((r) => ({ id: r.id, briefName: r.briefName, endedAt: r.endedAt })),
+    auth: {
+      signedIn: !!account,
+      account: account ? { name: account.name, email: account.email } : null
+    },
+    focus: focusTimer.getStatus(),
+    liveSync: liveSync.getStatus(),
+    connectivity: connectivity.get(),
+    settings: await getSettings(),
+    apiUrl: resolveApiBaseUrl((await getSettings()).environment)
+  }
+}
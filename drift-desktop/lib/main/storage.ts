@@ -0,0 +1,101 @@
+import { app } from 'electron'
+import { promises as fs } from 'fs'
+import { join, relative } from 'path'
+
+export type StorageCategory = 'database' | 'crashReports' | 'cache' | 'logs' | 'exports' | 'other'
+
+export interface StorageUsage {
+  totalBytes: number
+  categories: Record<StorageCategory, number>
+  freeBytes: number | null // on the disk holding the app data; null if unknown
+  dataDir: string
+}
+
+// Top-level entries of the app data directory, by category
+const CATEGORY_ENTRIES: Partial<Record<StorageCategory, string[]>> = {
+  database: ['config.json', 'data.key', 'profiles.json', 'profiles'],
+  crashReports: ['crash-reports', 'Crashpad'],
+  cache: ['Cache', 'Code Cache', 'GPUCache', 'DawnCache', 'DawnGraphiteCache', 'blob_storage', 'Service Worker', 'Shared Dictionary']
+}
+
+function categoryOf(entry: string): StorageCategory {
+  // Backups taken before store migrations and restores, e.g. config.json.v0.bak
+  if (/^config\.json\.[\w-]+\.bak$/.test(entry)) return 'database'
+  for (const [category, names] of Object.entries(CATEGORY_ENTRIES)) {
+    if (names!.includes(entry)) return category as StorageCategory
+  }
+  return 'other'
+}
+
+/**
+ * Total size of a file or directory tree. Symlinks are not followed; missing paths count as 0.
+ */
+async function sizeOf(path: string): Promise<number> {
+  const stat = await fs.lstat(path).catch(() => null)
+  if (!stat) return 0
+  if (!stat.isDirectory()) return stat.size
+
+  const entries = await fs.readdir(path).catch(() => [] as string[])
+  let total = 0
+  for (const entry of entries) {
+    total += await sizeOf(join(path, entry))
+  }
+  return total
+}
+
+/**
+ * Free space on the disk holding the app data, or null if it can't be read.
+ */
+export async function getFreeBytes(): Promise<number | null> {
+  try {
+    const stats = await fs.statfs(app.getPath('userData'))
+    return stats.bavail * stats.bsize
+  } catch (error) {
+    console.warn('[Storage] Could not read free disk space:', error)
+    return null
+  }
+}
+
+// Whether `path` lies inside `dir`
+function isInside(path: string, dir: string): boolean {
+  const rel = relative(dir, path)
+  return !!rel && !rel.startsWith('..')
+}
+
+/**
+ * Disk space used by Drift, split by category, plus free space on the data disk.
+ * Captures are uploaded rather than kept, so there are no media categories.
+ */
+export async function getStorageUsage(): Promise<StorageUsage> {
+  const dataDir = app.getPath('userData')
+  const logsDir = app.getPath('logs')
+  const categories: Record<StorageCategory, number> = {
+    database: 0,
+    crashReports: 0,
+    cache: 0,
+    logs: 0,
+    exports: 0,
+    other: 0
+  }
+
+  const entries = await fs.readdir(dataDir).catch(() => [] as string[])
+  for (const entry of entries) {
+    const path = join(dataDir, entry)
+    const category = path === logsDir ? 'logs' : categoryOf(entry)
+    categories[category] += await sizeOf(path)
+  }
+
+  // Logs live outside the data directory on macOS
+  if (!isInside(logsDir, dataDir)) {
+    categories.logs += await sizeOf(logsDir)
+  }
+  // Markdown recaps and other exports in Documents/Drift
+  categories.exports = await sizeOf(join(app.getPath('documents'), 'Drift'))
+
+  return {
+    totalBytes: Object.values(categories).reduce((sum, n) => sum + n, 0),
+    categories,
+    freeBytes: await getFreeBytes(),
+    dataDir
+  }
+}
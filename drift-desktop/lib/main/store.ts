@@ -0,0 +1,36 @@
+import { decryptBuffer, encryptBuffer, getDataKey } from './crypto'
+import { migrateStore } from './storeMigrations'
+import { profiles } from './profiles'
+
+// Persistent store - lazy loaded because electron-store is ESM-only
+let store: Promise<any> | null = null
+
+// Serialized store contents are encrypted; a plaintext file from an older version is read as-is
+const ENCRYPTED_PREFIX = 'enc:'
+
+export const getStore = async () => {
+  if (!store) {
+    store = (async () => {
+      const Store = (await import('electron-store')).default
+      const key = await getDataKey()
+      const s = new Store<{ authToken?: string; userEmail?: string }>({
+        cwd: profiles.dataDir(),
+        serialize: (value) =>
+          ENCRYPTED_PREFIX + encryptBuffer(key, Buffer.from(JSON.stringify(value))).toString('base64'),
+        deserialize: (text) =>
+          text.startsWith(ENCRYPTED_PREFIX)
+            ? JSON.parse(decryptBuffer(key, Buffer.from(text.slice(ENCRYPTED_PREFIX.length), 'base64')).toString())
+            : JSON.parse(text)
+      })
+      // Before anything reads from it
+      await migrateStore(s)
+      return s
+    })()
+  }
+  return store
+}
+
+// Reopen from the new profile's folder on next use
+profiles.on('switched', () => {
+  store = null
+})
@@ -0,0 +1,146 @@
+import { promises as fs } from 'fs'
+
+// Store data as a plain object, e.g. store.store
+type StoreData = Record<string, any>
+
+interface Migration {
+  version: number // schema version after this migration
+  description: string
+  up: (data: StoreData) => void
+}
+
+/**
+ * Ordered schema changes of the local store. Append only; never edit one that has shipped.
+ */
+const MIGRATIONS: Migration[] = [
+  {
+    version: 1,
+    description: 'Move the token from before multi-account support into a "default" account',
+    up: (data) => {
+      const accounts = data.accounts || {}
+      if (data.authToken && Object.keys(accounts).length === 0) {
+        data.accounts = { default: { name: 'default', email: data.userEmail || 'user@drift.app', token: data.authToken } }
+        data.activeAccount = 'default'
+      }
+      delete data.authToken
+      delete data.userEmail
+    }
+  }
+]
+
+export const SCHEMA_VERSION = MIGRATIONS[MIGRATIONS.length - 1].version
+
+export interface MigrationResult {
+  from: number
+  to: number
+  at: number
+  backupPath: string | null
+  error?: string // nothing was written when set
+}
+
+export interface DbInfo {
+  path: string
+  sizeBytes: number
+  schemaVersion: number
+  appSchemaVersion: number // what this build expects
+  downgrade: boolean // written by a newer version of the app
+  lastMigration: MigrationResult | null // from this launch
+  integrity: { ok: boolean; problems: string[] }
+}
+
+let lastMigration: MigrationResult | null = null
+
+/**
+ * Bring the store up to SCHEMA_VERSION. Pending migrations run on a copy of
+ * the data, which is written in one go only if all of them succeed, after
+ * the store file has been backed up. A store from a newer app version is left alone.
+ */
+export async function migrateStore(store: any): Promise<MigrationResult | null> {
+  const from: number = store.get('schemaVersion') ?? 0
+  if (from > SCHEMA_VERSION) {
+    console.warn(`[Store] Schema version ${from} is newer than this app supports (${SCHEMA_VERSION}); not migrating`)
+    return null
+  }
+  const pending = MIGRATIONS.filter((m) => m.version > from)
+  if (pending.length === 0) return null
+
+  const result: MigrationResult = { from, to: SCHEMA_VERSION, at: Date.now(), backupPath: null }
+  try {
+    const exists = await fs.access(store.path).then(() => true, () => false)
+    if (exists) {
+      result.backupPath = `${store.path}.v${from}.bak`
+      await fs.copyFile(store.path, result.backupPath)
+    }
+
+    const data = structuredClone(store.store)
+    for (const migration of pending) {
+      console.log(`[Store] Migrating to v${migration.version}: ${migration.description}`)
+      migration.up(data)
+    }
+    data.schemaVersion = SCHEMA_VERSION
+    store.store = data
+  } catch (error: any) {
+    result.to = from
+    result.error = String(error?.message ?? error)
+    console.error('[Store] Migration failed, keeping schema version', from, ':', result.error)
+  }
+
+  lastMigration = result
+  return result
+}
+
+/**
+ * Structural checks of the stored data that would otherwise surface as odd
+ * behavior later.
+ */
+export function checkIntegrity(data: StoreData): { ok: boolean; problems: string[] } {
+  const problems: string[] = []
+
+  const sessions = data.sessions ?? []
+  if (!Array.isArray(sessions)) {
+    problems.push('sessions is not a list')
+  } else {
+    const ids = new Set<string>()
+    sessions.forEach((r: any, i: number) => {
+      if (!r || typeof r.id !== 'string') {
+        problems.push(`sessions[${i}] has no id`)
+        return
+      }
+      if (ids.has(r.id)) problems.push(`Duplicate session ${r.id}`)
+      ids.add(r.id)
+      if (typeof r.startedAt !== 'number' || typeof r.endedAt !== 'number' || r.endedAt < r.startedAt) {
+        problems.push(`Session ${r.id} has invalid start/end times`)
+      }
+    })
+  }
+
+  for (const key of ['uploadQueue', 'focusBlocks', 'jobs', 'standaloneNotes', 'sessionTrash', 'attachments']) {
+    if (data[key] !== undefined && !Array.isArray(data[key])) problems.push(`${key} is not a list`)
+  }
+
+  const accounts = data.accounts ?? {}
+  if (typeof accounts !== 'object' || Array.isArray(accounts)) {
+    problems.push('accounts is not a map')
+  } else if (data.activeAccount && !accounts[data.activeAccount]) {
+    problems.push(`Active account ${data.activeAccount} does not exist`)
+  }
+
+  return { ok: problems.length === 0, problems }
+}
+
+/**
+ * Schema version, size and integrity of the local store.
+ */
+export async function getDbInfo(store: any): Promise<DbInfo> {
+  const schemaVersion: number = store.get('schemaVersion') ?? 0
+  const stat = await fs.stat(store.path).catch(() => null)
+  return {
+    path: store.path,
+    sizeBytes: stat?.size ?? 0,
+    schemaVersion,
+    appSchemaVersion: SCHEMA_VERSION,
+    downgrade: schemaVersion > SCHEMA_VERSION,
+    lastMigration,
+    integrity: checkIntegrity(store.store)
+  }
+}
@@ -0,0 +1,137 @@
+import { app, ipcMain } from 'electron'
+import { randomUUID } from 'crypto'
+import { externalHttp } from './api'
+import { getStore } from './store'
+import { getSettings, onSettingsChanged } from './settings'
+import { sessionManager } from './session'
+import { jobScheduler } from './jobs'
+
+// How often queued events are sent
+const FLUSH_MS = 5 * 60 * 1000
+
+// Events kept while waiting to be sent; the oldest are dropped first
+const MAX_QUEUE = 500
+
+// Anonymous usage event. Never contains content, titles, names or ids of user data
+export interface TelemetryEvent {
+  name: 'command' | 'error' | 'session_ended'
+  at: number
+  props: Record<string, string | number>
+}
+
+/**
+ * Reduce an error to a coarse code so no message text (which may contain
+ * user data) leaves the machine.
+ */
+function errorCode(error: unknown): string {
+  const text = String((error as any)?.message ?? error)
+  const status = /\b([45]\d{2})\b/.exec(text)
+  if (status) return `http_${status[1]}`
+  if ((error as any)?.code) return String((error as any).code)
+  return 'error'
+}
+
+/**
+ * Opt-in anonymous usage telemetry. Strictly off by default: nothing is
+ * recorded, queued or sent unless enabled in settings and an endpoint is set.
+ */
+class Telemetry {
+  private enabled = false
+  private endpoint = ''
+  private queue: TelemetryEvent[] = []
+
+  async start(): Promise<void> {
+    const { telemetry } = await getSettings()
+    this.configure(telemetry.enabled, telemetry.endpoint)
+    onSettingsChanged((next) => this.configure(next.telemetry.enabled, next.telemetry.endpoint))
+
+    sessionManager.on('ended', ({ reason }) => this.record('session_ended', { reason }))
+    jobScheduler.register('telemetry-flush', () => this.flush())
+    void jobScheduler.schedule('telemetry-flush', { every: FLUSH_MS })
+  }
+
+  /**
+   * Record IPC command invocations and failed commands. Must run before
+   * the IPC handlers are registered.
+   */
+  instrumentIpc(): void {
+    const handle = ipcMain.handle.bind(ipcMain)
+    ipcMain.handle = (channel, listener) =>
+      handle(channel, async (event, ...args) => {
+        this.record('command', { command: channel })
+        try {
+          const result = await listener(event, ...args)
+          if (result && typeof result === 'object' && 'error' in result && result.error) {
+            this.record('error', { command: channel, code: errorCode(result.error) })
+          }
+          return result
+        } catch (error) {
+          this.record('error', { command: channel, code: errorCode(error) })
+          throw error
+        }
+      })
+  }
+
+  record(name: TelemetryEvent['name'], props: TelemetryEvent['props'] = {}): void {
+    if (!this.enabled) return
+    this.queue.push({ name, at: Date.now(), props })
+    if (this.queue.length > MAX_QUEUE) {
+      this.queue.shift()
+    }
+  }
+
+  /**
+   * Exactly what the next flush would send.
+   */
+  async preview(): Promise<{ enabled: boolean; endpoint: string; batch: object }> {
+    return { enabled: this.enabled, endpoint: this.endpoint, batch: await this.batch(this.queue) }
+  }
+
+  async flush(): Promise<void> {
+    if (!this.enabled || !this.endpoint || this.queue.length === 0) return
+
+    const events = this.queue
+    this.queue = []
+    try {
+      await externalHttp.post(this.endpoint, await this.batch(events), { timeout: 10000 })
+    } catch (error: any) {
+      // Keep the events for the next attempt
+      console.warn('[Telemetry] Flush failed:', error.message)
+      this.queue = [...events, ...this.queue].slice(-MAX_QUEUE)
+    }
+  }
+
+  private configure(enabled: boolean, endpoint: string): void {
+    this.enabled = enabled
+    this.endpoint = endpoint
+    if (!enabled) {
+      // Opting out discards anything not yet sent
+      this.queue = []
+    }
+  }
+
+  private async batch(events: TelemetryEvent[]) {
+    return {
+      installId: await this.installId(),
+      appVersion: app.getVersion(),
+      platform: process.platform,
+      events
+    }
+  }
+
+  /**
+   * Random per-install id, unrelated to any account.
+   */
+  private async installId(): Promise<string> {
+    const s = await getStore()
+    let id: string | undefined = s.get('telemetryInstallId')
+    if (!id) {
+      id = randomUUID()
+      s.set('telemetryInstallId', id)
+    }
+    return id
+  }
+}
+
+// Singleton instance
+export const telemetry = new Telemetry()
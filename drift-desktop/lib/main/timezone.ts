@@ -0,0 +1,85 @@
+// Local date and time parts of an instant
+export interface LocalParts {
+  year: number
+  month: number // 1-12
+  day: number
+  hours: number
+  minutes: number
+  weekday: number // 0 = Sunday
+}
+
+/**
+ * IANA time zone of this machine, e.g. Europe/Berlin.
+ */
+export function currentTimeZone(): string {
+  return Intl.DateTimeFormat().resolvedOptions().timeZone || 'UTC'
+}
+
+const formatters = new Map<string, Intl.DateTimeFormat>()
+
+function formatter(timeZone: string): Intl.DateTimeFormat {
+  let f = formatters.get(timeZone)
+  if (!f) {
+    f = new Intl.DateTimeFormat('en-US', {
+      timeZone,
+      hourCycle: 'h23',
+      year: 'numeric',
+      month: 'numeric',
+      day: 'numeric',
+      hour: 'numeric',
+      minute: 'numeric',
+      second: 'numeric',
+      weekday: 'short'
+    })
+    formatters.set(timeZone, f)
+  }
+  return f
+}
+
+const WEEKDAYS = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat']
+
+/**
+ * Wall-clock parts of `ms` in `timeZone`, or in this machine's zone when none
+ * is given or it is unknown. Follows DST, so a session recorded across a
+ * change shows the times the user saw.
+ */
+export function localParts(ms: number, timeZone?: string): LocalParts {
+  if (timeZone) {
+    try {
+      const parts = Object.fromEntries(formatter(timeZone).formatToParts(new Date(ms)).map((p) => [p.type, p.value]))
+      return {
+        year: Number(parts.year),
+        month: Number(parts.month),
+        day: Number(parts.day),
+        hours: Number(parts.hour),
+        minutes: Number(parts.minute),
+        weekday: WEEKDAYS.indexOf(parts.weekday)
+      }
+    } catch {
+      // Zone unknown to this runtime
+    }
+  }
+  const d = new Date(ms)
+  return { year: d.getFullYear(), month: d.getMonth() + 1, day: d.getDate(), hours: d.getHours(), minutes: d.getMinutes(), weekday: d.getDay() }
+}
+
+/**
+ * Offset from UTC in minutes at `ms`, east positive (e.g. 120 for CEST).
+ */
+export function offsetMinutesAt(ms: number, timeZone?: string): number {
+  if (!timeZone) return -new Date(ms).getTimezoneOffset()
+  const p = localParts(ms, timeZone)
+  const seconds = new Date(ms).getUTCSeconds()
+  const asUtc = Date.UTC(p.year, p.month - 1, p.day, p.hours, p.minutes, seconds)
+  return Math.round((asUtc - (ms - (ms % 1000))) / 60000)
+}
+
+/**
+ * ISO 8601 timestamp with the local offset, e.g. 2026-10-15T09:30:00+02:00.
+ */
+export function isoWithOffset(ms: number, offsetMinutes: number): string {
+  const local = new Date(ms + offsetMinutes * 60000).toISOString().slice(0, 19)
+  const sign = offsetMinutes < 0 ? '-' : '+'
+  const abs = Math.abs(offsetMinutes)
+  return `${local}${sign}${String(Math.floor(abs / 60)).padStart(2, '0')}:${String(abs % 60).padStart(2, '0')}`
+}
@@ -0,0 +1,182 @@
+import { app, BrowserWindow, Menu, MenuItemConstructorOptions, Tray, nativeImage } from 'electron'
+import appIcon from '@/resources/build/icon.png?asset'
+import { sessionManager } from './session'
+import { windowRegistry } from './windowRegistry'
+import { uploadQueue } from './uploadQueue'
+import { goalTracker } from './goals'
+import { briefCache, Brief } from './briefs'
+import { onSettingsChanged } from './settings'
+
+// Upper bound on finalizing at quit, so a hung upload can't keep the app alive
+const SHUTDOWN_TIMEOUT_MS = 30_000
+
+// h:mm:ss, as shown next to the menu bar icon
+function clock(totalSeconds: number): string {
+  const h = Math.floor(totalSeconds / 3600)
+  const m = Math.floor((totalSeconds % 3600) / 60)
+  const s = totalSeconds % 60
+  return `${h}:${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}`
+}
+
+/**
+ * Tray icon that keeps Drift reachable while its window is hidden.
+ * Closing the main window only hides it while a session is recording or
+ * uploading, so that work keeps running. Every way of quitting finalizes it first.
+ * On macOS the menu bar title shows the elapsed session time, updated on every session heartbeat.
+ * The menu lists pinned and recent briefs to start recording against, rebuilt whenever the
+ * cached brief list changes.
+ */
+class TrayManager {
+  private tray: Tray | null = null
+  private quitting = false
+  private finalizing = false
+  private finalized = false
+
+  start(): void {
+    if (this.tray) return
+
+    this.tray = new Tray(nativeImage.createFromPath(appIcon).resize({ width: 16, height: 16 }))
+    this.tray.setToolTip('Drift')
+    this.refreshMenu()
+    this.tray.on('click', () => this.showWindow())
+
+    briefCache.on('updated', () => this.refreshMenu())
+    sessionManager.on('started', () => this.refreshMenu())
+    onSettingsChanged((next, prev) => {
+      if (next.pinnedBriefs !== prev.pinnedBriefs) this.refreshMenu()
+    })
+
+    sessionManager.on('heartbeat', ({ elapsedSeconds, paused }) => {
+      const elapsed = clock(elapsedSeconds)
+      const daily = goalTracker.getCachedProgress()?.daily
+      const goal = daily ? ` · Today ${Math.floor(daily.recordedSeconds / 60)}/${daily.goalSeconds / 60} min` : ''
+      this.tray?.setToolTip(`Drift · ${paused ? 'Paused' : 'Recording'} ${elapsed}${goal}`)
+      if (process.platform === 'darwin') {
+        this.tray?.setTitle(`${paused ? '⏸' : '🔴'} ${elapsed}`)
+      }
+    })
+    sessionManager.on('ended', () => {
+      this.refreshMenu()
+      this.tray?.setToolTip('Drift')
+      if (process.platform === 'darwin') {
+        this.tray?.setTitle('')
+      }
+    })
+
+    // Any way of quitting (menu, Cmd+Q, last window closed) closes windows for real,
+    // but holds the quit until the session is submitted
+    app.on('before-quit', (event) => {
+      this.quitting = true
+      if (this.finalized) return
+      event.preventDefault()
+      this.finalizeAndQuit()
+    })
+  }
+
+  /**
+   * Rebuild the context menu from the cached brief list. Never fetches on its own;
+   * the sync layer refreshes the cache and this follows.
+   */
+  private async refreshMenu(): Promise<void> {
+    let pinned: Brief[] = []
+    let recent: Brief[] = []
+    try {
+      pinned = await briefCache.pinned()
+      const pinnedIds = new Set(pinned.map((b) => b.id))
+      recent = (await briefCache.recent()).filter((b) => !pinnedIds.has(b.id))
+    } catch (error: any) {
+      console.warn('[Tray] Could not load briefs:', error.message)
+    }
+
+    const recording = !!sessionManager.getActive()
+    const item = (brief: Brief): MenuItemConstructorOptions => ({
+      label: brief.name,
+      enabled: !recording,
+      click: () => this.startRecording(brief)
+    })
+    const briefItems: MenuItemConstructorOptions[] = [
+      ...pinned.map(item),
+      ...(pinned.length > 0 && recent.length > 0 ? [{ type: 'separator' as const }] : []),
+      ...recent.map(item)
+    ]
+
+    this.tray?.setContextMenu(
+      Menu.buildFromTemplate([
+        { label: 'Show Drift', click: () => this.showWindow() },
+        {
+          label: 'Start Recording',
+          enabled: !recording,
+          submenu: briefItems.length > 0 ? briefItems : [{ label: 'No recent briefs', enabled: false }]
+        },
+        { type: 'separator' },
+        { label: 'Quit Drift', click: () => this.quit() }
+      ])
+    )
+  }
+
+  private async startRecording(brief: Brief): Promise<void> {
+    // Same role the CLI defaults to: the one the backend reported for this user
+    const briefs = await briefCache.fetch()
+    const role = 'role' in briefs ? briefs.role : 'dev'
+    const result = await sessionManager.start(brief.id, role)
+    if (result && 'error' in result) {
+      // The window shows why, e.g. a sign-in prompt
+      console.warn('[Tray] Could not start recording:', result.error)
+      this.showWindow()
+    }
+  }
+
+  /**
+   * Hide the window instead of closing it while there is work in progress.
+   */
+  attach(win: BrowserWindow): void {
+    win.on('close', (event) => {
+      if (this.quitting || !sessionManager.hasPendingWork()) return
+      event.preventDefault()
+      win.hide()
+      console.log('[Tray] Window hidden, session keeps running in the background')
+    })
+  }
+
+  showWindow(): void {
+    const win = windowRegistry.getMainWindow()
+    if (win && !win.isDestroyed()) {
+      win.show()
+      win.focus()
+    }
+  }
+
+  quit(): void {
+    app.quit()
+  }
+
+  /**
+   * Submit the running session and wait for pending uploads, then quit for real.
+   */
+  private async finalizeAndQuit(): Promise<void> {
+    if (this.finalizing) return
+    this.finalizing = true
+    console.log('[Tray] Quitting, finalizing session...')
+
+    let timer: NodeJS.Timeout | undefined
+    const timeout = new Promise<void>((resolve) => {
+      timer = setTimeout(() => {
+        console.warn('[Tray] Finalizing timed out, quitting anyway')
+        resolve()
+      }, SHUTDOWN_TIMEOUT_MS)
+    })
+    try {
+      await Promise.race([Promise.all([sessionManager.shutdown(), uploadQueue.drain()]), timeout])
+    } catch (error) {
+      console.error('[Tray] Finalizing failed:', error)
+    } finally {
+      clearTimeout(timer)
+    }
+
+    this.finalized = true
+    app.quit()
+  }
+}
+
+// Singleton instance
+export const trayManager = new TrayManager()
@@ -0,0 +1,113 @@
+import { EventEmitter } from 'events'
+import { getStore } from './store'
+import { accounts } from './accounts'
+import { emitEvent } from './events'
+import { notify } from './notifications'
+import { sessionHistory } from './sessionHistory'
+import { connectivity } from './connectivity'
+import { driftApi, getAuthToken, toDriftError } from './api'
+
+// A session submission waiting for the connection to return
+interface QueuedUpload {
+  sessionId: string
+  account: string | null
+  manifest: Record<string, any>
+  queuedAt: number
+}
+
+/**
+ * Session submissions made while offline. They are kept on disk and sent
+ * when connectivity returns, then the local history record is marked uploaded.
+ * Emits `uploaded` with the session id for each one sent.
+ */
+class UploadQueue extends EventEmitter {
+  private flushing: Promise<void> | null = null
+
+  start(): void {
+    connectivity.on('online', () => this.flush())
+    this.flush()
+  }
+
+  async enqueue(sessionId: string, account: string | null, manifest: Record<string, any>): Promise<void> {
+    const s = await getStore()
+    const queue: QueuedUpload[] = s.get('uploadQueue') || []
+    queue.push({ sessionId, account, manifest, queuedAt: Date.now() })
+    s.set('uploadQueue', queue)
+    console.log('[Uploads] Queued session', sessionId)
+  }
+
+  async pendingSessionIds(): Promise<string[]> {
+    const s = await getStore()
+    return ((s.get('uploadQueue') || []) as QueuedUpload[]).map((u) => u.sessionId)
+  }
+
+  /**
+   * Send queued submissions of the active account. Stops at the first network failure.
+   */
+  flush(): Promise<void> {
+    if (!this.flushing) {
+      this.flushing = this.send().finally(() => {
+        this.flushing = null
+      })
+    }
+    return this.flushing
+  }
+
+  /**
+   * Wait for a flush in progress, e.g. before quitting.
+   */
+  async drain(): Promise<void> {
+    await this.flushing
+  }
+
+  private async send(): Promise<void> {
+    if (!connectivity.isOnline()) return
+
+    const authToken = await getAuthToken()
+    if (!authToken) return
+    const account = (await accounts.getActive())?.name ?? null
+
+    const s = await getStore()
+    const pending = ((s.get('uploadQueue') || []) as QueuedUpload[]).filter((u) => u.account === account)
+    if (pending.length === 0) return
+
+    const done = new Set<string>()
+    let sent = 0
+    for (const upload of pending) {
+      try {
+        const data = await driftApi.endSession(upload.manifest)
+        const record = await sessionHistory.update(upload.sessionId, (r) => {
+          r.uploaded = true
+          r.submissionId = data.submissionId
+          r.summaryLines = data.summaryLines || []
+        })
+        done.add(upload.sessionId)
+        this.removeDone(s, done)
+        sent++
+        this.emit('uploaded', upload.sessionId)
+        await notify('uploadComplete', 'Drift · Session submitted', `${record?.briefName ?? 'A queued session'} was uploaded.`)
+      } catch (error) {
+        const failure = toDriftError(error)
+        if (!failure.reachedServer) break // still unreachable; try again on the next transition
+        // Rejected by the server (e.g. the session was ended elsewhere); retrying won't help
+        console.error('[Uploads] Dropping queued session', upload.sessionId, failure.status)
+        done.add(upload.sessionId)
+      }
+    }
+
+    const remaining = this.removeDone(s, done)
+    emitEvent('uploads:flushed', { sent, remaining: remaining.length })
+  }
+
+  // Persist progress after every upload, so a quit mid-flush doesn't send sessions twice
+  private removeDone(s: any, done: Set<string>): QueuedUpload[] {
+    // Re-read: sessions may have been queued while sending
+    const queue: QueuedUpload[] = s.get('uploadQueue') || []
+    const remaining = queue.filter((u) => !done.has(u.sessionId))
+    s.set('uploadQueue', remaining)
+    return remaining
+  }
+}
+
+// Singleton instance
+export const uploadQueue = new UploadQueue()
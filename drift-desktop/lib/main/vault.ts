@@ -0,0 +1,75 @@
+import { promises as fs } from 'fs'
+import { join } from 'path'
+import { getSettings } from './settings'
+import { sessionHistory, SessionRecord } from './sessionHistory'
+import { renderSessionMarkdown, sessionFileName, sessionIsoTimes } from './sessionExport'
+import { getWebBaseUrl } from './api'
+
+// Everything below this line in a vault note is the user's and survives updates
+const USER_SECTION_MARKER = '<!-- drift:user-notes -->'
+
+function yamlString(text: string): string {
+  return JSON.stringify(text)
+}
+
+/**
+ * Front matter plus the Markdown recap of a session.
+ */
+export function renderVaultNote(record: SessionRecord, webUrl: string): string {
+  const { started, ended } = sessionIsoTimes(record)
+  const frontMatter = [
+    '---',
+    `drift_id: ${record.id}`,
+    `brief: ${yamlString(record.briefName)}`,
+    `brief_id: ${yamlString(record.briefId)}`,
+    `role: ${yamlString(record.role)}`,
+    `started: ${started}`,
+    `ended: ${ended}`,
+    ...(record.timeZone ? [`time_zone: ${yamlString(record.timeZone)}`] : []),
+    `duration_minutes: ${Math.round(record.durationSeconds / 60)}`,
+    `uploaded: ${record.uploaded}`,
+    ...(record.submissionId ? [`submission: ${yamlString(record.submissionId)}`] : []),
+    `tags: [drift, ${yamlString(record.role)}]`,
+    '---',
+    ''
+  ]
+  return frontMatter.join('\n') + renderSessionMarkdown(record, webUrl)
+}
+
+/**
+ * Keeps one Markdown note per session in a folder (e.g. an Obsidian vault).
+ * Notes are written when a session is recorded and rewritten when it
+ * changes, e.g. once it is uploaded; text the user adds below the marker
+ * line is kept.
+ */
+class VaultExporter {
+  start(): void {
+    sessionHistory.on('saved', (record: SessionRecord) => this.write(record))
+  }
+
+  /**
+   * Write the note of a session. Returns its path, or null when the vault is off.
+   */
+  async write(record: SessionRecord): Promise<string | null> {
+    const { vault } = await getSettings()
+    if (!vault.enabled || !vault.path) return null
+
+    const path = join(vault.path, sessionFileName(record, 'md'))
+    try {
+      const existing = await fs.readFile(path, 'utf8').catch(() => null)
+      const markerAt = existing?.indexOf(USER_SECTION_MARKER) ?? -1
+      const userSection = markerAt >= 0 ? existing!.slice(markerAt) : `${USER_SECTION_MARKER}\n`
+
+      await fs.mkdir(vault.path, { recursive: true })
+      await fs.writeFile(path, `${renderVaultNote(record, await getWebBaseUrl())}\n${userSection}`)
+      console.log('[Vault] Wrote', path)
+      return path
+    } catch (error) {
+      console.error('[Vault] Could not write note:', error)
+      return null
+    }
+  }
+}
+
+// Singleton instance
+export const vaultExporter = new VaultExporter()
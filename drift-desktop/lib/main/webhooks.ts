@@ -0,0 +1,74 @@
+import { createHmac, randomUUID } from 'crypto'
+import { getSettings } from './settings'
+import { sessionManager } from './session'
+import { uploadQueue } from './uploadQueue'
+import { sessionHistory } from './sessionHistory'
+import { externalHttp, getWebBaseUrl } from './api'
+
+/**
+ * HMAC-SHA256 of `${timestamp}.${body}`, hex encoded. Receivers recompute it
+ * with the shared secret and compare against the X-Drift-Signature header.
+ */
+export function signPayload(secret: string, timestamp: number, body: string): string {
+  return createHmac('sha256', secret).update(`${timestamp}.${body}`).digest('hex')
+}
+
+/**
+ * Posts a signed `session.completed` payload to the configured URL whenever
+ * a session has been uploaded, directly or later from the offline queue,
+ * so Zapier/n8n-style automations can pick it up. Notes are only included
+ * when they may leave the machine (upload.notes).
+ */
+class WebhookNotifier {
+  start(): void {
+    sessionManager.on('uploaded', (sessionId: string) => this.sessionCompleted(sessionId))
+    uploadQueue.on('uploaded', (sessionId: string) => this.sessionCompleted(sessionId))
+  }
+
+  private async sessionCompleted(sessionId: string): Promise<void> {
+    const { webhook, upload } = await getSettings()
+    if (!webhook.url) return
+
+    const record = await sessionHistory.get(sessionId)
+    if (!record) return
+
+    const webUrl = await getWebBaseUrl()
+    const body = JSON.stringify({
+      id: randomUUID(),
+      event: 'session.completed',
+      session: {
+        id: record.id,
+        briefId: record.briefId,
+        briefName: record.briefName,
+        role: record.role,
+        startedAt: new Date(record.startedAt).toISOString(),
+        endedAt: new Date(record.endedAt).toISOString(),
+        durationSeconds: record.durationSeconds,
+        summary: record.summaryLines.length > 0 ? record.summaryLines : record.summary ? [record.summary] : [],
+        notes: upload.notes ? record.notes.map((n) => ({ at: new Date(n.timestamp).toISOString(), text: n.text })) : [],
+        submissionId: record.submissionId ?? null
+      },
+      links: { web: webUrl }
+    })
+
+    const timestamp = Math.floor(Date.now() / 1000)
+    const headers: Record<string, string> = {
+      'Content-Type': 'application/json',
+      'X-Drift-Timestamp': String(timestamp)
+    }
+    if (webhook.secret) {
+      headers['X-Drift-Signature'] = `sha256=${signPayload(webhook.secret, timestamp, body)}`
+    }
+
+    try {
+      // The payload id lets receivers drop duplicates, so retrying is safe
+      await externalHttp.post(webhook.url, body, { headers, timeout: 10000, retry: true })
+      console.log('[Webhook] Delivered session.completed for', sessionId)
+    } catch (error: any) {
+      console.error('[Webhook] Delivery failed:', error.response?.status ?? error.message)
+    }
+  }
+}
+
+// Singleton instance
+export const webhookNotifier = new WebhookNotifier()
@@ -8,6 +8,7 @@ import { BrowserWindow } from 'electron'
 class WindowRegistry {
   private static instance: WindowRegistry
   private _mainWindow: BrowserWindow | null = null
+  private _windows = new Map<string, BrowserWindow>()
 
   private constructor() {}
 
@@ -26,10 +27,32 @@ class WindowRegistry {
     return this._mainWindow
   }
 
+  /**
+   * Register a secondary window (e.g. the mini recorder) under a label.
+   * It receives broadcasts until it closes.
+   */
+  public addWindow(label: string, win: BrowserWindow): void {
+    this._windows.set(label, win)
+    win.on('closed', () => {
+      if (this._windows.get(label) === win) this._windows.delete(label)
+    })
+  }
+
+  /**
+   * Look up a window by label; 'main' is the main window.
+   */
+  public getWindow(label: string): BrowserWindow | null {
+    const win = label === 'main' ? this._mainWindow : this._windows.get(label) ?? null
+    return win && !win.isDestroyed() ? win : null
+  }
+
   public broadcast(channel: string, ...args: any[]): void {
     if (this._mainWindow && !this._mainWindow.isDestroyed()) {
       this._mainWindow.webContents.send(channel, ...args)
     }
+    for (const win of this._windows.values()) {
+      if (!win.isDestroyed()) win.webContents.send(channel, ...args)
+    }
   }
 }
 
@@ -0,0 +1,101 @@
+import { EventEmitter } from 'events'
+
+/**
+ * Finite-state machine for the recording lifecycle of a session.
+ *
+ * Idle         ──► CountingDown ──► Recording
+ * Idle         ──► Recording                     (no countdown, or a restored session)
+ * CountingDown ──► Idle                          (cancelled, or the backend refused to start)
+ * Recording    ◄─► Paused
+ * Recording    ──► Stopping                      (also from Paused)
+ * Stopping     ──► Finalizing ──► Idle           (tracking stopped, then the upload settles)
+ */
+
+export enum RecordingState {
+  Idle = 'IDLE',
+  CountingDown = 'COUNTING_DOWN',
+  Recording = 'RECORDING',
+  Paused = 'PAUSED',
+  Stopping = 'STOPPING',
+  Finalizing = 'FINALIZING'
+}
+
+/** Allowed transitions */
+const TRANSITIONS: Record<RecordingState, RecordingState[]> = {
+  [RecordingState.Idle]: [RecordingState.CountingDown, RecordingState.Recording],
+  [RecordingState.CountingDown]: [RecordingState.Idle, RecordingState.Recording],
+  [RecordingState.Recording]: [RecordingState.Paused, RecordingState.Stopping],
+  [RecordingState.Paused]: [RecordingState.Recording, RecordingState.Stopping],
+  [RecordingState.Stopping]: [RecordingState.Finalizing],
+  [RecordingState.Finalizing]: [RecordingState.Idle]
+}
+
+/**
+ * Unlike the UI state machine, callers name the target state; anything not in
+ * the transition table is rejected and logged.
+ * Emits `stateChange` events with `{ prev, next }` payloads whenever the state updates.
+ *
+ * Also keeps the session's recorded time: the time spent in Recording since
+ * the session started, on the monotonic clock so NTP adjustments and manual
+ * clock changes don't alter durations.
+ */
+export class RecordingStateMachine extends EventEmitter {
+  private current = RecordingState.Idle
+  private recordedMs = 0
+  private recordingSince: number | null = null // performance.now() when Recording was entered
+
+  /** Get current state */
+  public get state(): RecordingState {
+    return this.current
+  }
+
+  /** Whether `next` is reachable from the current state */
+  public can(next: RecordingState): boolean {
+    return TRANSITIONS[this.current].includes(next)
+  }
+
+  /**
+   * Move to `next`. Returns false (and leaves the state alone) for an invalid transition.
+   */
+  public transition(next: RecordingState): boolean {
+    const prev = this.current
+    if (!this.can(next)) {
+      console.warn(`[Recording] Rejected transition ${prev} -> ${next}`)
+      return false
+    }
+    this.current = next
+
+    const now = performance.now()
+    if (prev === RecordingState.Recording && this.recordingSince !== null) {
+      this.recordedMs += now - this.recordingSince
+      this.recordingSince = null
+    }
+    if (next === RecordingState.Recording) {
+      // A new session starts from zero
+      if (prev === RecordingState.Idle || prev === RecordingState.CountingDown) this.recordedMs = 0
+      this.recordingSince = now
+    }
+
+    this.emit('stateChange', { prev, next })
+    return true
+  }
+
+  /** Time spent recording in this session, excluding pauses */
+  public elapsedMs(): number {
+    return this.recordedMs + (this.recordingSince !== null ? performance.now() - this.recordingSince : 0)
+  }
+
+  /** Continue from the recorded time of a session restored after a restart */
+  public restoreElapsed(ms: number): void {
+    this.recordedMs = Math.max(0, ms)
+    if (this.recordingSince !== null) this.recordingSince = performance.now()
+  }
+
+  /**
+   * Take back recorded time that turned out to be idle, e.g. when a pause is
+   * dated back to the last input.
+   */
+  public discard(ms: number): void {
+    this.recordedMs = Math.max(0, this.recordedMs - Math.max(0, ms))
+  }
+}
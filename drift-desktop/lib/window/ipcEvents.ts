@@ -1,5 +1,6 @@
 import { type BrowserWindow, ipcMain, shell } from 'electron'
 import os from 'os'
+import { windowRegistry } from '@/lib/main/windowRegistry'
 
 const handleIPC = (channel: string, handler: (...args: any[]) => void) => {
   ipcMain.removeHandler(channel);
@@ -76,4 +77,24 @@ export const registerChatWindowIPC = (chatWindow: BrowserWindow) => {
       chatWindow.webContents.send('chat-focus-input');
     }
   });
-}
\ No newline at end of file
+}
+/**
+ * Window management by label ('main', 'mini-recorder'), so any window can be
+ * pinned above other apps or let clicks pass through to them.
+ */
+export const registerWindowManagementIPC = () => {
+  handleIPC('window:set-pinned', (_e, label: string, pinned: boolean) => {
+    const win = windowRegistry.getWindow(label)
+    if (!win) return { error: `Unknown window: ${label}` }
+    win.setAlwaysOnTop(pinned, 'floating')
+    return { ok: true }
+  })
+
+  handleIPC('window:set-click-through', (_e, label: string, enabled: boolean) => {
+    const win = windowRegistry.getWindow(label)
+    if (!win) return { error: `Unknown window: ${label}` }
+    // Forward mouse moves so the page can turn click-through off again on hover
+    win.setIgnoreMouseEvents(enabled, { forward: true })
+    return { ok: true }
+  })
+}
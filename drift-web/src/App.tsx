@@ -6,6 +6,7 @@ import { ProjectWorkspace } from '@/components/workspace/ProjectWorkspace'
 import { PlanningView } from '@/components/planning/PlanningView'
 import { ChatView } from '@/components/chat/ChatView'
 import { DesktopAuth } from '@/pages/DesktopAuth'
+import { SharedSession } from '@/pages/SharedSession'
 import { MCPHub } from '@/pages/MCPHub'
 import {
   SidebarInset,
@@ -37,6 +38,11 @@ export default function App() {
     return <DesktopAuth />
   }
 
+  // Public /share/<token> links created from the desktop app
+  if (window.location.pathname.startsWith('/share/')) {
+    return <SharedSession token={window.location.pathname.slice('/share/'.length)} />
+  }
+
   const { isSignedIn, user: clerkUser } = useUser()
   const { orgId, getToken } = useAuth()
 
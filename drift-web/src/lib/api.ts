@@ -324,6 +324,18 @@ class DriftAPI {
     return this.fetch<UserProfile>('/api/users/me')
   }
 
+  // Public summary behind a share link created in the desktop app
+  async getSharedSession(shareToken: string) {
+    return this.fetch<{
+      briefName: string | null
+      role: string
+      startedAt: string | null
+      endedAt: string | null
+      durationMinutes: number | null
+      summaryLines: string[]
+    }>(`/api/share/${encodeURIComponent(shareToken)}`)
+  }
+
   async updateUserRole(role: 'pm' | 'dev' | 'designer', orgId?: string) {
     return this.fetch<UserProfile>('/api/users/me', {
       method: 'PATCH',
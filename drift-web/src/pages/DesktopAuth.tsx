@@ -29,9 +29,10 @@ export function DesktopAuth() {
         }
 
         if (callbackUrl) {
-          // Redirect to localhost callback with token
-          const redirectUrl = `${callbackUrl}?token=${encodeURIComponent(token)}`
-          window.location.href = redirectUrl
+          // Redirect to localhost callback with token, keeping the nonce the app put in the URL
+          const redirectUrl = new URL(callbackUrl)
+          redirectUrl.searchParams.set('token', token)
+          window.location.href = redirectUrl.toString()
         } else {
           // Fallback: show error
           setError('No callback URL provided')
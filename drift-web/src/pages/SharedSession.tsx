@@ -0,0 +1,59 @@
+import { useEffect, useState } from 'react'
+import { Loader2, Clock } from 'lucide-react'
+import { api } from '@/lib/api'
+
+type SharedSessionData = Awaited<ReturnType<typeof api.getSharedSession>>
+
+export function SharedSession({ token }: { token: string }) {
+  const [session, setSession] = useState<SharedSessionData | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    api.getSharedSession(token)
+      .then(setSession)
+      .catch((err) => {
+        console.error('Failed to load shared session:', err)
+        setError('This link has expired or was revoked')
+      })
+  }, [token])
+
+  return (
+    <div className="min-h-screen bg-[#0a0a0b] flex items-center justify-center">
+      <div className="relative z-10 p-10 max-w-xl w-full">
+        {!session && !error && (
+          <div className="flex items-center justify-center gap-3 text-indigo-400">
+            <Loader2 className="w-6 h-6 animate-spin" />
+            <span>Loading session</span>
+          </div>
+        )}
+
+        {error && (
+          <p className="text-red-400 text-center">
+            {error}
+          </p>
+        )}
+
+        {session && (
+          <>
+            <h1 className="text-3xl font-bold text-white mb-2">
+              {session.briefName || 'Work session'}
+            </h1>
+            <p className="text-zinc-400 mb-8 flex items-center gap-2">
+              <Clock className="w-4 h-4" />
+              {session.startedAt && new Date(session.startedAt).toLocaleString()}
+              {session.durationMinutes !== null && ` · ${session.durationMinutes} min`}
+              {` · ${session.role}`}
+            </p>
+            <ul className="space-y-2">
+              {session.summaryLines.map((line, i) => (
+                <li key={i} className="text-zinc-200 border-l-2 border-indigo-500 pl-4">
+                  {line}
+                </li>
+              ))}
+            </ul>
+          </>
+        )}
+      </div>
+    </div>
+  )
+}